@@ -53,8 +53,15 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use runestick::{Item, Unit, Value, VmExecution};
+use runestick::{Compression, Item, Unit, Value, VmExecution};
+
+/// The number of functions to include in the summary of a profile.
+const PROFILE_SUMMARY_LIMIT: usize = 20;
+
+/// How often to check for changes to files in `--watch` mode.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -64,6 +71,7 @@ async fn main() -> Result<()> {
     args.next();
 
     let mut path = None;
+    let mut debug = false;
     let mut trace = false;
     let mut dump_unit = false;
     let mut dump_instructions = false;
@@ -75,6 +83,9 @@ async fn main() -> Result<()> {
     let mut with_source = false;
     let mut help = false;
     let mut experimental = false;
+    let mut profile = None;
+    let mut coverage = None;
+    let mut watch = false;
 
     let mut options = rune::Options::default();
 
@@ -123,6 +134,27 @@ async fn main() -> Result<()> {
             "--experimental" => {
                 experimental = true;
             }
+            "--watch" => {
+                watch = true;
+            }
+            "--coverage" => {
+                coverage = match args.next() {
+                    Some(coverage) => Some(PathBuf::from(coverage)),
+                    None => {
+                        println!("expected output file to `--coverage`");
+                        return Ok(());
+                    }
+                };
+            }
+            "--profile" => {
+                profile = match args.next() {
+                    Some(profile) => Some(PathBuf::from(profile)),
+                    None => {
+                        println!("expected output file to `--profile`");
+                        return Ok(());
+                    }
+                };
+            }
             "-O" => {
                 let opt = match args.next() {
                     Some(opt) => opt,
@@ -137,6 +169,9 @@ async fn main() -> Result<()> {
             "--help" | "-h" => {
                 help = true;
             }
+            "debug" if path.is_none() && !debug => {
+                debug = true;
+            }
             other if !other.starts_with('-') => {
                 path = Some(PathBuf::from(other));
             }
@@ -148,10 +183,13 @@ async fn main() -> Result<()> {
     }
 
     const USAGE: &str = "rune-cli [--trace] <file>";
+    const DEBUG_USAGE: &str = "rune-cli debug <file>";
 
     if help {
         println!("Usage: {}", USAGE);
+        println!("       {}", DEBUG_USAGE);
         println!();
+        println!("  debug                    - Run the file in an interactive debugger.");
         println!("  --help, -h               - Show this help.");
         println!(
             "  --trace                  - Provide detailed tracing for each instruction executed."
@@ -166,6 +204,9 @@ async fn main() -> Result<()> {
         println!("  --dump-native-types      - Dump native types.");
         println!("  --with-source            - Include source code references where appropriate (only available if -O debug-info=true).");
         println!("  --experimental           - Enabled experimental features.");
+        println!("  --coverage <out>         - Record line and branch coverage, writing it in the LCOV format to <out>.");
+        println!("  --profile <out>          - Profile the execution, writing folded stacks to <out> and printing the functions most time was spent in.");
+        println!("  --watch                  - Recompile and rerun the file whenever it, or any module it loads, changes.");
         println!();
         println!("Compiler options:");
         println!("  -O <option>       - Update the given compiler option.");
@@ -175,6 +216,7 @@ async fn main() -> Result<()> {
         println!("  link-checks[=<true/false>]         - Perform linker checks which makes sure that called functions exist.");
         println!("  debug-info[=<true/false>]          - Enable or disable debug info.");
         println!("  macros[=<true/false>]              - Enable or disable macros (experimental).");
+        println!("  peephole[=<true/false>]            - Enable or disable peephole optimizations of the generated instructions.");
         println!("  bytecode[=<true/false>]            - Enable or disable bytecode caching (experimental).");
         return Ok(());
     }
@@ -186,7 +228,21 @@ async fn main() -> Result<()> {
         }
     };
 
-    let bytecode_path = path.with_extension("rnc");
+    let flags = Flags {
+        debug,
+        trace,
+        dump_unit,
+        dump_instructions,
+        dump_stack,
+        dump_functions,
+        dump_types,
+        dump_native_functions,
+        dump_native_types,
+        with_source,
+        profile,
+        coverage,
+    };
+
     let mut context = rune::default_context()?;
 
     if experimental {
@@ -194,19 +250,84 @@ async fn main() -> Result<()> {
     }
 
     let context = Arc::new(context);
-    let mut sources = rune::Sources::new();
+    loop {
+        let mut sources = rune::Sources::new();
+        let result = run(&context, &options, &path, &flags, &mut sources).await;
+
+        if !watch {
+            return result;
+        }
+
+        // NB: errors are reported instead of ending the watch, so that a
+        // broken file can be fixed without restarting.
+        if let Err(error) = result {
+            eprintln!("error: {}", error);
+        }
+
+        println!("# watching for changes");
+        wait_for_changes(&path, &sources).await;
+    }
+}
+
+/// Flags controlling how a file is run.
+struct Flags {
+    debug: bool,
+    trace: bool,
+    dump_unit: bool,
+    dump_instructions: bool,
+    dump_stack: bool,
+    dump_functions: bool,
+    dump_types: bool,
+    dump_native_functions: bool,
+    dump_native_types: bool,
+    with_source: bool,
+    profile: Option<PathBuf>,
+    coverage: Option<PathBuf>,
+}
+
+/// Compile and run the file at the given path, populating `sources` with the
+/// sources which were loaded.
+async fn run(
+    context: &Arc<runestick::Context>,
+    options: &rune::Options,
+    path: &Path,
+    flags: &Flags,
+    sources: &mut rune::Sources,
+) -> Result<()> {
+    let Flags {
+        debug,
+        trace,
+        dump_unit,
+        dump_instructions,
+        dump_stack,
+        dump_functions,
+        dump_types,
+        dump_native_functions,
+        dump_native_types,
+        with_source,
+        ref profile,
+        ref coverage,
+    } = *flags;
+
+    let bytecode_path = path.with_extension("rnc");
     let mut warnings = rune::Warnings::new();
 
-    let use_cache = options.bytecode && should_cache_be_used(&path, &bytecode_path)?;
+    let use_cache = options.bytecode && should_cache_be_used(path, &bytecode_path)?;
     let maybe_unit = if use_cache {
-        let f = fs::File::open(&bytecode_path)?;
-        match bincode::deserialize_from::<_, Unit>(f) {
-            Ok(unit) => {
-                log::trace!("using cache: {}", bytecode_path.display());
-                Some(Arc::new(unit))
-            }
+        let f = io::BufReader::new(fs::File::open(&bytecode_path)?);
+        match Unit::read_file(f, context) {
+            Ok(unit) => match unit.verify(context) {
+                Ok(()) => {
+                    log::trace!("using cache: {}", bytecode_path.display());
+                    Some(Arc::new(unit))
+                }
+                Err(errors) => {
+                    log::error!("failed to verify: {}: {}", bytecode_path.display(), errors);
+                    None
+                }
+            },
             Err(e) => {
-                log::error!("failed to deserialize: {}: {}", bytecode_path.display(), e);
+                log::error!("failed to read cache: {}: {}", bytecode_path.display(), e);
                 None
             }
         }
@@ -219,20 +340,19 @@ async fn main() -> Result<()> {
         None => {
             log::trace!("building file: {}", path.display());
 
-            let unit =
-                match rune::load_path(&*context, &options, &mut sources, &path, &mut warnings) {
-                    Ok(unit) => unit,
-                    Err(error) => {
-                        let mut writer = StandardStream::stderr(ColorChoice::Always);
-                        error.emit_diagnostics(&mut writer, &sources)?;
-                        return Ok(());
-                    }
-                };
+            let unit = match rune::load_path(context, options, sources, path, &mut warnings) {
+                Ok(unit) => unit,
+                Err(error) => {
+                    let mut writer = StandardStream::stderr(ColorChoice::Always);
+                    error.emit_diagnostics(&mut writer, sources)?;
+                    return Ok(());
+                }
+            };
 
             if options.bytecode {
                 log::trace!("serializing cache: {}", bytecode_path.display());
-                let f = fs::File::create(&bytecode_path)?;
-                bincode::serialize_into(f, &unit)?;
+                let f = io::BufWriter::new(fs::File::create(&bytecode_path)?);
+                unit.write_file(f, context, Compression::Deflate)?;
             }
 
             Arc::new(unit)
@@ -244,7 +364,12 @@ async fn main() -> Result<()> {
         warnings.emit_diagnostics(&mut writer, &sources)?;
     }
 
-    let vm = runestick::Vm::new(context.clone(), unit.clone());
+    let mut vm = runestick::Vm::new(context.clone(), unit.clone());
+
+    let recorded_coverage = match coverage {
+        Some(..) => Some(vm.enable_coverage()),
+        None => None,
+    };
 
     if dump_native_functions {
         println!("# functions");
@@ -364,18 +489,46 @@ async fn main() -> Result<()> {
 
     let last = std::time::Instant::now();
 
-    let mut execution: runestick::VmExecution = vm.execute(&Item::of(&["main"]), ())?;
+    let mut execution: runestick::VmExecution = match vm.execute(&Item::of(&["main"]), ()) {
+        Ok(execution) => execution,
+        Err(error) => {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            error.emit_diagnostics(&mut writer, sources)?;
+            return Ok(());
+        }
+    };
 
-    let result = if trace {
+    let result = if debug {
+        match do_debug(&mut execution, &sources).await {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => return Ok(()),
+            Err(TraceError::Io(io)) => return Err(io.into()),
+            Err(TraceError::VmError(vm)) => Err(vm),
+        }
+    } else if trace {
         match do_trace(&mut execution, &sources, dump_stack, with_source).await {
             Ok(value) => Ok(value),
             Err(TraceError::Io(io)) => return Err(io.into()),
             Err(TraceError::VmError(vm)) => Err(vm),
         }
+    } else if let Some(profile) = profile {
+        let mut profiler = runestick::Profiler::new();
+        let result = profiler.async_run(&mut execution).await;
+
+        profiler.write_folded(io::BufWriter::new(fs::File::create(profile)?))?;
+
+        println!("# profile (folded stacks written to {})", profile.display());
+        profiler.write_summary(io::stdout(), PROFILE_SUMMARY_LIMIT)?;
+        result
     } else {
         execution.async_complete().await
     };
 
+    if let (Some(path), Some(recorded)) = (coverage, &recorded_coverage) {
+        let out = io::BufWriter::new(fs::File::create(path)?);
+        recorded.write_lcov(out, &unit, sources.iter().enumerate())?;
+    }
+
     let errored;
 
     match result {
@@ -438,12 +591,33 @@ async fn main() -> Result<()> {
 
     if let Some(error) = errored {
         let mut writer = StandardStream::stderr(ColorChoice::Always);
-        error.emit_diagnostics(&mut writer, &sources)?;
+        error.emit_diagnostics(&mut writer, sources)?;
     }
 
     Ok(())
 }
 
+/// Wait until the file at the given path, or any of the sources which were
+/// loaded from it, are modified.
+async fn wait_for_changes(path: &Path, sources: &rune::Sources) {
+    let mut paths = vec![path.to_owned()];
+    paths.extend(sources.iter().filter_map(|s| s.path()).map(Path::to_owned));
+
+    let modified = modified_times(&paths);
+
+    while modified_times(&paths) == modified {
+        tokio::time::delay_for(WATCH_INTERVAL).await;
+    }
+}
+
+/// Get the modification times of the given paths.
+fn modified_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
 enum TraceError {
     Io(std::io::Error),
     VmError(runestick::VmError),
@@ -565,6 +739,199 @@ async fn do_trace(
     }
 }
 
+const DEBUG_HELP: &str = "\
+Commands:
+  break <line>        - Break when entering the given line of the main file.
+  break <path>        - Break when entering the given function, like `foo::bar`.
+  delete <id>         - Delete the breakpoint with the given id.
+  breakpoints         - List all breakpoints.
+  continue, c         - Continue until a breakpoint is hit.
+  step, s             - Step to the next line, entering any called functions.
+  next, n             - Step to the next line in the current function.
+  finish, f           - Step until the current function returns.
+  stepi, si           - Step a single instruction.
+  locals              - Print all local variables.
+  print, p <name>     - Print the value of the given local variable.
+  stack               - Print the stack of the current function.
+  where, w            - Print the current location.
+  quit, q             - Stop debugging.";
+
+/// Run the program in an interactive debugger.
+///
+/// Returns `None` if the user stopped debugging before the program completed.
+async fn do_debug(
+    execution: &mut VmExecution,
+    sources: &rune::Sources,
+) -> Result<Option<Value>, TraceError> {
+    use runestick::debugger::{Breakpoint, DebugStop, Step};
+    use runestick::Debugger;
+    use std::io::{BufRead as _, Write as _};
+
+    let mut debugger = Debugger::new();
+    let mut source_id = 0;
+
+    while let Some(source) = sources.get(source_id) {
+        debugger.insert_source(source_id, source);
+        source_id += 1;
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    print_location(execution, &debugger, sources)?;
+
+    loop {
+        print!("(rune) ");
+        io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(None),
+        };
+
+        let mut it = line.split_whitespace();
+
+        let command = match it.next() {
+            Some(command) => command,
+            None => continue,
+        };
+
+        let step = match command {
+            "break" | "b" => {
+                let breakpoint = match it.next() {
+                    Some(arg) => match arg.parse::<usize>() {
+                        Ok(line) if line > 0 => Breakpoint::line(0, line - 1),
+                        _ => Breakpoint::function(&Item::of(arg.split("::"))),
+                    },
+                    None => {
+                        println!("expected a line or a function to `break`");
+                        continue;
+                    }
+                };
+
+                let id = debugger.add_breakpoint(breakpoint);
+                println!("breakpoint {}", id);
+                continue;
+            }
+            "delete" | "d" => {
+                match it.next().and_then(|id| id.parse::<usize>().ok()) {
+                    Some(id) if debugger.remove_breakpoint(id).is_some() => {
+                        println!("deleted breakpoint {}", id);
+                    }
+                    _ => println!("expected an existing breakpoint id to `delete`"),
+                }
+
+                continue;
+            }
+            "breakpoints" => {
+                for (id, breakpoint) in debugger.breakpoints() {
+                    match breakpoint {
+                        Breakpoint::Line { line, .. } => println!("{}: line {}", id, line + 1),
+                        Breakpoint::Function { hash } => println!("{}: function {}", id, hash),
+                    }
+                }
+
+                continue;
+            }
+            "locals" => {
+                let vm = execution.vm().map_err(TraceError::VmError)?;
+
+                for (name, value) in Debugger::locals(vm) {
+                    println!("{} = {:?}", name, value);
+                }
+
+                continue;
+            }
+            "print" | "p" => {
+                let vm = execution.vm().map_err(TraceError::VmError)?;
+
+                match it.next() {
+                    Some(name) => match Debugger::local(vm, name) {
+                        Some(value) => println!("{} = {:?}", name, value),
+                        None => println!("no local variable named `{}`", name),
+                    },
+                    None => println!("expected a variable name to `print`"),
+                }
+
+                continue;
+            }
+            "stack" => {
+                let vm = execution.vm().map_err(TraceError::VmError)?;
+                let stack = vm.stack();
+                let values = stack.get(stack.stack_bottom()..).expect("bad stack slice");
+
+                if values.is_empty() {
+                    println!("*empty*");
+                }
+
+                for (n, value) in values.iter().enumerate() {
+                    println!("{}+{} = {:?}", stack.stack_bottom(), n, value);
+                }
+
+                continue;
+            }
+            "where" | "w" => {
+                print_location(execution, &debugger, sources)?;
+                continue;
+            }
+            "help" | "h" => {
+                println!("{}", DEBUG_HELP);
+                continue;
+            }
+            "quit" | "q" => return Ok(None),
+            "continue" | "c" => Step::Continue,
+            "step" | "s" => Step::Into,
+            "next" | "n" => Step::Over,
+            "finish" | "f" => Step::Out,
+            "stepi" | "si" => Step::Instruction,
+            other => {
+                println!("unrecognized command `{}`, try `help`", other);
+                continue;
+            }
+        };
+
+        match debugger
+            .async_resume(execution, step)
+            .await
+            .map_err(TraceError::VmError)?
+        {
+            DebugStop::Complete { value } => return Ok(Some(value)),
+            DebugStop::Breakpoint { id } => {
+                println!("hit breakpoint {}", id);
+            }
+            DebugStop::Step => (),
+        }
+
+        print_location(execution, &debugger, sources)?;
+    }
+}
+
+/// Print the current location of the execution being debugged.
+fn print_location(
+    execution: &VmExecution,
+    debugger: &runestick::Debugger,
+    sources: &rune::Sources,
+) -> Result<(), TraceError> {
+    let vm = execution.vm().map_err(TraceError::VmError)?;
+
+    if let Some((hash, signature)) = vm.unit().debug_info().and_then(|d| d.function_at(vm.ip())) {
+        println!("fn {} ({}):", signature, hash);
+    }
+
+    let location = debugger
+        .location(vm)
+        .and_then(|l| Some((sources.get(l.source_id)?, l.line)));
+
+    if let Some((source, line)) = location {
+        let text = source.as_str().lines().nth(line).unwrap_or_default();
+        println!("  {}:{: <3} - {}", source.name(), line + 1, text.trim_end());
+    } else if let Some(inst) = vm.unit().instruction_at(vm.ip()) {
+        println!("  {:04} = {}", vm.ip(), inst);
+    }
+
+    Ok(())
+}
+
 /// Test if path `a` is newer than path `b`.
 fn should_cache_be_used(source: &Path, cached: &Path) -> io::Result<bool> {
     let source = fs::metadata(source)?;
@@ -0,0 +1,167 @@
+use crate::protocol;
+use anyhow::{anyhow, bail, Result};
+use std::io::{BufRead, Write};
+
+/// Input connection.
+pub struct Input<R> {
+    buf: Vec<u8>,
+    reader: R,
+}
+
+impl<R> Input<R>
+where
+    R: BufRead,
+{
+    /// Construct a new input connection reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            buf: Vec::new(),
+            reader,
+        }
+    }
+
+    /// Get the next request.
+    pub fn next_request(&mut self) -> Result<Option<protocol::Request>> {
+        let content_length = match read_headers(&mut self.buf, &mut self.reader)? {
+            Some(content_length) => content_length,
+            None => return Ok(None),
+        };
+
+        self.buf.resize(content_length, 0u8);
+        self.reader.read_exact(&mut self.buf[..])?;
+
+        log::trace!("read frame: {}", self.buf.len());
+        Ok(Some(serde_json::from_slice(&self.buf)?))
+    }
+}
+
+/// Output connection.
+pub struct Output<W> {
+    seq: u64,
+    writer: W,
+}
+
+impl<W> Output<W>
+where
+    W: Write,
+{
+    /// Construct a new output connection writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self { seq: 1, writer }
+    }
+
+    /// Send a successful response to the given request.
+    pub fn response<T>(&mut self, request: &protocol::Request, body: T) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let seq = self.next_seq();
+
+        self.write(&protocol::Response {
+            seq,
+            ty: "response",
+            request_seq: request.seq,
+            success: true,
+            command: &request.command,
+            message: None,
+            body: Some(body),
+        })
+    }
+
+    /// Send an error response to the given request.
+    pub fn error<M>(&mut self, request: &protocol::Request, message: M) -> Result<()>
+    where
+        M: ToString,
+    {
+        let seq = self.next_seq();
+
+        self.write(&protocol::Response::<()> {
+            seq,
+            ty: "response",
+            request_seq: request.seq,
+            success: false,
+            command: &request.command,
+            message: Some(message.to_string()),
+            body: None,
+        })
+    }
+
+    /// Send the given event.
+    pub fn event<T>(&mut self, event: &str, body: T) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let seq = self.next_seq();
+
+        self.write(&protocol::Event {
+            seq,
+            ty: "event",
+            event,
+            body,
+        })
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.seq;
+        self.seq += 1;
+        seq
+    }
+
+    /// Write the given message.
+    fn write<T>(&mut self, message: &T) -> Result<()>
+    where
+        T: serde::Serialize,
+    {
+        let bytes = serde_json::to_vec(message)?;
+        write!(self.writer, "Content-Length: {}\r\n\r\n", bytes.len())?;
+        self.writer.write_all(&bytes)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Read headers from the given reader, returning the content length of the
+/// message that follows them.
+fn read_headers<R>(buf: &mut Vec<u8>, reader: &mut R) -> Result<Option<usize>>
+where
+    R: BufRead,
+{
+    let mut content_length = None;
+
+    loop {
+        buf.clear();
+
+        if reader.read_until(b'\n', buf)? == 0 {
+            return Ok(None);
+        }
+
+        let line = std::str::from_utf8(buf)?.trim();
+
+        if line.is_empty() {
+            break;
+        }
+
+        let mut parts = line.splitn(2, ':').map(str::trim);
+
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => (key, value),
+            out => bail!("bad header: {:?}", out),
+        };
+
+        match key.to_lowercase().as_str() {
+            "content-length" => {
+                let value = value
+                    .parse::<usize>()
+                    .map_err(|e| anyhow!("bad content-length: {}: {}", value, e))?;
+
+                content_length = Some(value);
+            }
+            key => bail!("unsupported header: {:?}", key),
+        }
+    }
+
+    match content_length {
+        Some(content_length) => Ok(Some(content_length)),
+        None => bail!("missing content-length"),
+    }
+}
@@ -0,0 +1,77 @@
+//! <div align="center">
+//!     <img alt="Rune Logo" src="https://raw.githubusercontent.com/rune-rs/rune/master/assets/icon.png" />
+//! </div>
+//!
+//! <br>
+//!
+//! <div align="center">
+//! <a href="https://rune-rs.github.io/rune/">
+//!     <b>Read the Book 📖</b>
+//! </a>
+//! </div>
+//!
+//! <br>
+//!
+//! <div align="center">
+//! <a href="https://github.com/rune-rs/rune/actions">
+//!     <img alt="Build Status" src="https://github.com/rune-rs/rune/workflows/Build/badge.svg">
+//! </a>
+//!
+//! <a href="https://github.com/rune-rs/rune/actions">
+//!     <img alt="Book Status" src="https://github.com/rune-rs/rune/workflows/Book/badge.svg">
+//! </a>
+//!
+//! <a href="https://crates.io/crates/rune">
+//!     <img alt="crates.io" src="https://img.shields.io/crates/v/rune.svg">
+//! </a>
+//!
+//! <a href="https://docs.rs/rune">
+//!     <img alt="docs.rs" src="https://docs.rs/rune/badge.svg">
+//! </a>
+//!
+//! <a href="https://discord.gg/v5AeNkT">
+//!     <img alt="Chat on Discord" src="https://img.shields.io/discord/558644981137670144.svg?logo=discord&style=flat-square">
+//! </a>
+//! </div>
+//!
+//! <br>
+//!
+//! A debug adapter for the [Rune language], speaking the
+//! [Debug Adapter Protocol].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//! [Debug Adapter Protocol]: https://microsoft.github.io/debug-adapter-protocol/
+
+mod connection;
+pub mod protocol;
+mod session;
+
+pub use crate::connection::{Input, Output};
+pub use crate::session::Session;
+
+use anyhow::Result;
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+
+/// Run a debug session, reading requests from `input` and writing responses
+/// and events to `output` until the client disconnects.
+pub fn run<R, W>(input: R, output: W, context: Arc<runestick::Context>) -> Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut input = Input::new(input);
+
+    let mut options = rune::Options::default();
+    options.macros(true);
+
+    let mut session = Session::new(Output::new(output), context, options)?;
+
+    while let Some(request) = input.next_request()? {
+        if !session.process(request)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! <div align="center">
+//!     <img alt="Rune Logo" src="https://raw.githubusercontent.com/rune-rs/rune/master/assets/icon.png" />
+//! </div>
+//!
+//! <br>
+//!
+//! <div align="center">
+//! <a href="https://rune-rs.github.io/rune/">
+//!     <b>Read the Book 📖</b>
+//! </a>
+//! </div>
+//!
+//! <br>
+//!
+//! <div align="center">
+//! <a href="https://github.com/rune-rs/rune/actions">
+//!     <img alt="Build Status" src="https://github.com/rune-rs/rune/workflows/Build/badge.svg">
+//! </a>
+//!
+//! <a href="https://github.com/rune-rs/rune/actions">
+//!     <img alt="Book Status" src="https://github.com/rune-rs/rune/workflows/Book/badge.svg">
+//! </a>
+//!
+//! <a href="https://crates.io/crates/rune">
+//!     <img alt="crates.io" src="https://img.shields.io/crates/v/rune.svg">
+//! </a>
+//!
+//! <a href="https://docs.rs/rune">
+//!     <img alt="docs.rs" src="https://docs.rs/rune/badge.svg">
+//! </a>
+//!
+//! <a href="https://discord.gg/v5AeNkT">
+//!     <img alt="Chat on Discord" src="https://img.shields.io/discord/558644981137670144.svg?logo=discord&style=flat-square">
+//! </a>
+//! </div>
+//!
+//! <br>
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+
+use anyhow::Result;
+use std::io;
+use std::sync::Arc;
+
+fn setup_logging() -> Result<()> {
+    // Set environment variable to get the debug adapter to trace log to the
+    // given file.
+    if let Some(log_path) = std::env::var_os("RUNE_TRACE_LOG_FILE") {
+        use log::LevelFilter;
+        use log4rs::append::file::FileAppender;
+        use log4rs::config::{Appender, Config, Root};
+        use log4rs::encode::pattern::PatternEncoder;
+
+        let logfile = FileAppender::builder()
+            .encoder(Box::new(PatternEncoder::new("{l} - {m}\n")))
+            .build(log_path)?;
+
+        let config = Config::builder()
+            .appender(Appender::builder().build("logfile", Box::new(logfile)))
+            .build(Root::builder().appender("logfile").build(LevelFilter::Info))?;
+
+        log4rs::init_config(config)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    setup_logging()?;
+
+    let mut context = rune::default_context()?;
+    context.install(&rune_macros::module()?)?;
+
+    log::info!("Starting debug adapter");
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    rune_debugadapter::run(stdin.lock(), stdout.lock(), Arc::new(context))?;
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! Types of the debug adapter protocol.
+//!
+//! Only the subset of the protocol which is supported by the debug adapter is
+//! modelled here.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Request {
+    pub seq: u64,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response<'a, T> {
+    pub seq: u64,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub request_seq: u64,
+    pub success: bool,
+    pub command: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<T>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Event<'a, T> {
+    pub seq: u64,
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub event: &'a str,
+    pub body: T,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeArguments {
+    #[serde(default = "default_true")]
+    pub lines_start_at1: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub supports_configuration_done_request: bool,
+    pub supports_function_breakpoints: bool,
+    pub supports_terminate_request: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchArguments {
+    /// The path to the script to launch.
+    pub program: String,
+    /// The function to call in the script.
+    #[serde(default = "default_entry")]
+    pub entry: String,
+    /// Stop as soon as the entry function has been entered.
+    #[serde(default)]
+    pub stop_on_entry: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Source {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceBreakpoint {
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetBreakpointsArguments {
+    pub source: Source,
+    #[serde(default)]
+    pub breakpoints: Vec<SourceBreakpoint>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FunctionBreakpoint {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetFunctionBreakpointsArguments {
+    pub breakpoints: Vec<FunctionBreakpoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Breakpoint {
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakpointsBody {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Thread {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadsBody {
+    pub threads: Vec<Thread>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceArguments {
+    #[serde(default)]
+    pub start_frame: Option<usize>,
+    #[serde(default)]
+    pub levels: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StackFrame {
+    pub id: usize,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTraceBody {
+    pub stack_frames: Vec<StackFrame>,
+    pub total_frames: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopesArguments {
+    pub frame_id: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scope {
+    pub name: String,
+    pub variables_reference: usize,
+    pub expensive: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopesBody {
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariablesArguments {
+    pub variables_reference: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+    pub variables_reference: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VariablesBody {
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateArguments {
+    pub expression: String,
+    #[serde(default)]
+    pub frame_id: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateBody {
+    pub result: String,
+    pub variables_reference: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoppedEventBody {
+    pub reason: &'static str,
+    pub thread_id: u64,
+    pub all_threads_stopped: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputEventBody {
+    pub category: &'static str,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitedEventBody {
+    pub exit_code: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Empty {}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_entry() -> String {
+    String::from("main")
+}
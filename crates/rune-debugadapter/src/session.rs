@@ -0,0 +1,533 @@
+use crate::connection::Output;
+use crate::protocol;
+use anyhow::{anyhow, Result};
+use rune::termcolor::NoColor;
+use rune::EmitDiagnostics as _;
+use runestick::debugger::{Breakpoint, DebugFrame, DebugStop, Step};
+use runestick::{Context, Debugger, Item, Value, Vm, VmExecution};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The only thread reported to the client.
+const THREAD_ID: u64 = 1;
+
+/// A program being debugged.
+struct Program {
+    sources: rune::Sources,
+    execution: VmExecution,
+    /// Source ids by canonical path.
+    paths: HashMap<PathBuf, usize>,
+}
+
+/// Line breakpoints registered for a single source path.
+#[derive(Default)]
+struct LineBreakpoints {
+    /// Lines to break on, where the first line is `0`.
+    lines: Vec<usize>,
+    /// Ids of the breakpoints registered with the debugger.
+    ids: Vec<usize>,
+}
+
+/// A debug session driven by requests from a client.
+pub struct Session<W> {
+    output: Output<W>,
+    runtime: tokio::runtime::Runtime,
+    context: Arc<Context>,
+    options: rune::Options,
+    debugger: Debugger,
+    program: Option<Program>,
+    /// If lines sent by the client start at `1` rather than `0`.
+    lines_start_at1: bool,
+    stop_on_entry: bool,
+    configured: bool,
+    line_breakpoints: HashMap<PathBuf, LineBreakpoints>,
+    function_breakpoints: Vec<usize>,
+}
+
+impl<W> Session<W>
+where
+    W: Write,
+{
+    /// Construct a new session writing responses and events to the given
+    /// output.
+    pub fn new(output: Output<W>, context: Arc<Context>, options: rune::Options) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            output,
+            runtime,
+            context,
+            options,
+            debugger: Debugger::new(),
+            program: None,
+            lines_start_at1: true,
+            stop_on_entry: false,
+            configured: false,
+            line_breakpoints: HashMap::new(),
+            function_breakpoints: Vec::new(),
+        })
+    }
+
+    /// Process a single request.
+    ///
+    /// Returns `false` if the session has ended.
+    pub fn process(&mut self, request: protocol::Request) -> Result<bool> {
+        log::trace!("request: {:?}", request);
+
+        let result = match request.command.as_str() {
+            "initialize" => self.initialize(&request),
+            "launch" => self.launch(&request),
+            "setBreakpoints" => self.set_breakpoints(&request),
+            "setFunctionBreakpoints" => self.set_function_breakpoints(&request),
+            "setExceptionBreakpoints" => self.output.response(&request, protocol::Empty {}),
+            "configurationDone" => self.configuration_done(&request),
+            "threads" => self.threads(&request),
+            "stackTrace" => self.stack_trace(&request),
+            "scopes" => self.scopes(&request),
+            "variables" => self.variables(&request),
+            "evaluate" => self.evaluate(&request),
+            "continue" => self.step(&request, Step::Continue),
+            "next" => self.step(&request, Step::Over),
+            "stepIn" => self.step(&request, Step::Into),
+            "stepOut" => self.step(&request, Step::Out),
+            "disconnect" | "terminate" => {
+                self.program = None;
+                self.output.response(&request, protocol::Empty {})?;
+                self.output.event("terminated", protocol::Empty {})?;
+                return Ok(false);
+            }
+            command => Err(anyhow!("unsupported command `{}`", command)),
+        };
+
+        if let Err(error) = result {
+            log::warn!("request `{}` failed: {}", request.command, error);
+            self.output.error(&request, error)?;
+        }
+
+        Ok(true)
+    }
+
+    fn initialize(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::InitializeArguments = arguments(request)?;
+        self.lines_start_at1 = args.lines_start_at1;
+
+        self.output.response(
+            request,
+            protocol::Capabilities {
+                supports_configuration_done_request: true,
+                supports_function_breakpoints: true,
+                supports_terminate_request: true,
+            },
+        )?;
+
+        self.output.event("initialized", protocol::Empty {})
+    }
+
+    fn launch(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::LaunchArguments = arguments(request)?;
+
+        let mut sources = rune::Sources::new();
+        let mut warnings = rune::Warnings::new();
+
+        let unit = match rune::load_path(
+            &self.context,
+            &self.options,
+            &mut sources,
+            Path::new(&args.program),
+            &mut warnings,
+        ) {
+            Ok(unit) => unit,
+            Err(error) => {
+                let mut out = NoColor::new(Vec::new());
+                error.emit_diagnostics(&mut out, &sources)?;
+                let output = String::from_utf8_lossy(&out.into_inner()).into_owned();
+                self.output.event(
+                    "output",
+                    protocol::OutputEventBody {
+                        category: "stderr",
+                        output,
+                    },
+                )?;
+
+                return Err(anyhow!("failed to compile `{}`", args.program));
+            }
+        };
+
+        if !warnings.is_empty() {
+            let mut out = NoColor::new(Vec::new());
+            warnings.emit_diagnostics(&mut out, &sources)?;
+            let output = String::from_utf8_lossy(&out.into_inner()).into_owned();
+            self.output.event(
+                "output",
+                protocol::OutputEventBody {
+                    category: "stderr",
+                    output,
+                },
+            )?;
+        }
+
+        let vm = Vm::new(self.context.clone(), Arc::new(unit));
+        let entry = Item::of(args.entry.split("::"));
+        let execution = vm.execute(&entry, ())?;
+
+        self.debugger = Debugger::new();
+        let mut paths = HashMap::new();
+        let mut source_id = 0;
+
+        while let Some(source) = sources.get(source_id) {
+            self.debugger.insert_source(source_id, source);
+
+            if let Some(path) = source.path() {
+                paths.insert(canonicalize(path), source_id);
+            }
+
+            source_id += 1;
+        }
+
+        self.program = Some(Program {
+            sources,
+            execution,
+            paths,
+        });
+
+        self.stop_on_entry = args.stop_on_entry;
+
+        let paths = self.line_breakpoints.keys().cloned().collect::<Vec<_>>();
+
+        for path in paths {
+            self.apply_line_breakpoints(&path);
+        }
+
+        self.output.response(request, protocol::Empty {})?;
+
+        if self.configured {
+            self.start()?;
+        }
+
+        Ok(())
+    }
+
+    fn set_breakpoints(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::SetBreakpointsArguments = arguments(request)?;
+
+        let path = match args.source.path {
+            Some(path) => canonicalize(Path::new(&path)),
+            None => return Err(anyhow!("missing source path")),
+        };
+
+        let base = self.line_base();
+
+        let lines = args
+            .breakpoints
+            .iter()
+            .map(|b| b.line.saturating_sub(base))
+            .collect::<Vec<_>>();
+
+        let breakpoints = self.line_breakpoints.entry(path.clone()).or_default();
+        breakpoints.lines = lines;
+        let verified = self.apply_line_breakpoints(&path);
+
+        let breakpoints = args
+            .breakpoints
+            .iter()
+            .map(|b| protocol::Breakpoint {
+                verified,
+                line: Some(b.line),
+            })
+            .collect();
+
+        self.output
+            .response(request, protocol::BreakpointsBody { breakpoints })
+    }
+
+    /// Register the line breakpoints for the given path with the debugger,
+    /// replacing any that were previously registered.
+    ///
+    /// Returns `true` if the path belongs to the program being debugged.
+    fn apply_line_breakpoints(&mut self, path: &Path) -> bool {
+        let breakpoints = match self.line_breakpoints.get_mut(path) {
+            Some(breakpoints) => breakpoints,
+            None => return false,
+        };
+
+        for id in breakpoints.ids.drain(..) {
+            self.debugger.remove_breakpoint(id);
+        }
+
+        let source_id = match self
+            .program
+            .as_ref()
+            .and_then(|program| program.paths.get(path))
+        {
+            Some(source_id) => *source_id,
+            None => return false,
+        };
+
+        for line in &breakpoints.lines {
+            let id = self
+                .debugger
+                .add_breakpoint(Breakpoint::line(source_id, *line));
+            breakpoints.ids.push(id);
+        }
+
+        true
+    }
+
+    fn set_function_breakpoints(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::SetFunctionBreakpointsArguments = arguments(request)?;
+
+        for id in self.function_breakpoints.drain(..) {
+            self.debugger.remove_breakpoint(id);
+        }
+
+        let mut breakpoints = Vec::new();
+
+        for b in args.breakpoints {
+            let item = Item::of(b.name.split("::"));
+            let id = self.debugger.add_breakpoint(Breakpoint::function(&item));
+            self.function_breakpoints.push(id);
+
+            breakpoints.push(protocol::Breakpoint {
+                verified: true,
+                line: None,
+            });
+        }
+
+        self.output
+            .response(request, protocol::BreakpointsBody { breakpoints })
+    }
+
+    fn configuration_done(&mut self, request: &protocol::Request) -> Result<()> {
+        self.configured = true;
+        self.output.response(request, protocol::Empty {})?;
+
+        if self.program.is_some() {
+            self.start()?;
+        }
+
+        Ok(())
+    }
+
+    /// Start running the launched program.
+    fn start(&mut self) -> Result<()> {
+        if self.stop_on_entry {
+            return self.stopped("entry");
+        }
+
+        self.resume(Step::Continue)
+    }
+
+    fn threads(&mut self, request: &protocol::Request) -> Result<()> {
+        let threads = vec![protocol::Thread {
+            id: THREAD_ID,
+            name: String::from("main"),
+        }];
+
+        self.output
+            .response(request, protocol::ThreadsBody { threads })
+    }
+
+    fn stack_trace(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::StackTraceArguments = arguments(request)?;
+        let program = self.program()?;
+        let vm = program.execution.vm()?;
+        let frames = Debugger::frames(vm);
+        let total_frames = frames.len();
+
+        let start = args.start_frame.unwrap_or_default();
+        let levels = match args.levels {
+            Some(levels) if levels > 0 => levels,
+            _ => total_frames,
+        };
+
+        let mut stack_frames = Vec::new();
+
+        for (id, frame) in frames.into_iter().enumerate().skip(start).take(levels) {
+            let name = vm
+                .unit()
+                .debug_info()
+                .and_then(|debug| debug.function_containing(frame.ip))
+                .map(|(_, signature)| signature.to_string())
+                .unwrap_or_else(|| String::from("<unknown>"));
+
+            let location = self.debugger.location_at(vm, frame.ip);
+
+            let source = location
+                .and_then(|l| program.sources.get(l.source_id))
+                .map(|source| protocol::Source {
+                    name: Some(source.name().to_owned()),
+                    path: source.path().map(|p| p.display().to_string()),
+                });
+
+            stack_frames.push(protocol::StackFrame {
+                id,
+                name,
+                source,
+                line: location.map(|l| l.line).unwrap_or_default() + self.line_base(),
+                column: self.line_base(),
+            });
+        }
+
+        self.output.response(
+            request,
+            protocol::StackTraceBody {
+                stack_frames,
+                total_frames,
+            },
+        )
+    }
+
+    fn scopes(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::ScopesArguments = arguments(request)?;
+
+        // NB: the variables reference `0` is reserved to indicate that there
+        // are no variables, so frames are offset by one.
+        let scopes = vec![protocol::Scope {
+            name: String::from("Locals"),
+            variables_reference: args.frame_id + 1,
+            expensive: false,
+        }];
+
+        self.output
+            .response(request, protocol::ScopesBody { scopes })
+    }
+
+    fn variables(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::VariablesArguments = arguments(request)?;
+
+        let variables = self
+            .frame_locals(args.variables_reference.saturating_sub(1))?
+            .into_iter()
+            .map(|(name, value)| protocol::Variable {
+                name,
+                value: format!("{:?}", value),
+                variables_reference: 0,
+            })
+            .collect();
+
+        self.output
+            .response(request, protocol::VariablesBody { variables })
+    }
+
+    fn evaluate(&mut self, request: &protocol::Request) -> Result<()> {
+        let args: protocol::EvaluateArguments = arguments(request)?;
+        let name = args.expression.trim();
+
+        let value = self
+            .frame_locals(args.frame_id.unwrap_or_default())?
+            .into_iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| anyhow!("no local variable named `{}`", name))?;
+
+        self.output.response(
+            request,
+            protocol::EvaluateBody {
+                result: format!("{:?}", value),
+                variables_reference: 0,
+            },
+        )
+    }
+
+    fn step(&mut self, request: &protocol::Request, step: Step) -> Result<()> {
+        self.program()?;
+        self.output.response(request, protocol::Empty {})?;
+        self.resume(step)
+    }
+
+    /// Resume the program, reporting back to the client why it stopped.
+    fn resume(&mut self, step: Step) -> Result<()> {
+        let program = match &mut self.program {
+            Some(program) => program,
+            None => return Ok(()),
+        };
+
+        let debugger = &mut self.debugger;
+        let execution = &mut program.execution;
+        let result = self
+            .runtime
+            .block_on(async move { debugger.async_resume(execution, step).await });
+
+        let (category, output, exit_code) = match result {
+            Ok(DebugStop::Breakpoint { .. }) => return self.stopped("breakpoint"),
+            Ok(DebugStop::Step) => return self.stopped("step"),
+            Ok(DebugStop::Complete { value }) => ("console", format!("== {:?}\n", value), 0),
+            Err(error) => {
+                let mut out = NoColor::new(Vec::new());
+                error.emit_diagnostics(&mut out, &program.sources)?;
+                let output = String::from_utf8_lossy(&out.into_inner()).into_owned();
+                ("stderr", output, 1)
+            }
+        };
+
+        self.program = None;
+
+        self.output
+            .event("output", protocol::OutputEventBody { category, output })?;
+        self.output
+            .event("exited", protocol::ExitedEventBody { exit_code })?;
+        self.output.event("terminated", protocol::Empty {})
+    }
+
+    fn stopped(&mut self, reason: &'static str) -> Result<()> {
+        self.output.event(
+            "stopped",
+            protocol::StoppedEventBody {
+                reason,
+                thread_id: THREAD_ID,
+                all_threads_stopped: true,
+            },
+        )
+    }
+
+    /// Get the locals of the frame with the given id.
+    fn frame_locals(&self, frame_id: usize) -> Result<Vec<(String, Value)>> {
+        let vm = self.program()?.execution.vm()?;
+
+        let frame: DebugFrame = Debugger::frames(vm)
+            .get(frame_id)
+            .copied()
+            .ok_or_else(|| anyhow!("no frame with id {}", frame_id))?;
+
+        Ok(Debugger::frame_locals(vm, frame))
+    }
+
+    fn program(&self) -> Result<&Program> {
+        self.program
+            .as_ref()
+            .ok_or_else(|| anyhow!("no program is running"))
+    }
+
+    /// The number the client uses for the first line.
+    fn line_base(&self) -> usize {
+        if self.lines_start_at1 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// Deserialize the arguments of the given request.
+fn arguments<T>(request: &protocol::Request) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let arguments = match &request.arguments {
+        serde_json::Value::Null => serde_json::Value::Object(Default::default()),
+        arguments => arguments.clone(),
+    };
+
+    Ok(serde_json::from_value(arguments)?)
+}
+
+/// Canonicalize the given path, falling back to the path itself if it can't
+/// be canonicalized.
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_owned())
+}
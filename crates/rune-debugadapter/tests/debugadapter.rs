@@ -0,0 +1,134 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::BufRead as _;
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn add(a, b) {
+    let c = a + b;
+    c
+}
+
+fn main() {
+    let x = 1;
+    let y = add(x, 2);
+    y * 2
+}
+"#;
+
+/// Run a debug session with the given requests, returning all messages sent
+/// by the debug adapter.
+fn session(requests: Vec<Value>) -> Result<Vec<Value>> {
+    let mut input = Vec::new();
+
+    for (seq, mut request) in requests.into_iter().enumerate() {
+        request["seq"] = json!(seq + 1);
+        request["type"] = json!("request");
+        let bytes = serde_json::to_vec(&request)?;
+        input.extend(format!("Content-Length: {}\r\n\r\n", bytes.len()).into_bytes());
+        input.extend(bytes);
+    }
+
+    let context = Arc::new(rune::default_context()?);
+    let mut output = Vec::new();
+    rune_debugadapter::run(&input[..], &mut output, context)?;
+
+    let mut messages = Vec::new();
+    let mut reader = &output[..];
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        let length = line
+            .trim()
+            .trim_start_matches("Content-Length:")
+            .trim()
+            .parse::<usize>()?;
+
+        line.clear();
+        reader.read_line(&mut line)?;
+        line.clear();
+
+        messages.push(serde_json::from_slice(&reader[..length])?);
+        reader = &reader[length..];
+    }
+
+    Ok(messages)
+}
+
+fn response<'a>(messages: &'a [Value], command: &str) -> &'a Value {
+    messages
+        .iter()
+        .find(|m| m["type"] == "response" && m["command"] == command)
+        .unwrap_or_else(|| panic!("missing response to `{}`", command))
+}
+
+fn events<'a>(messages: &'a [Value], event: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+    messages
+        .iter()
+        .filter(move |m| m["type"] == "event" && m["event"] == event)
+}
+
+#[test]
+fn test_debug_session() -> Result<()> {
+    let path = std::env::temp_dir().join("rune-debugadapter-test.rn");
+    std::fs::write(&path, SOURCE)?;
+    let path = path.display().to_string();
+
+    let messages = session(vec![
+        json!({"command": "initialize", "arguments": {"linesStartAt1": true}}),
+        json!({"command": "launch", "arguments": {"program": path, "entry": "main"}}),
+        json!({"command": "setBreakpoints", "arguments": {
+            "source": {"path": path},
+            "breakpoints": [{"line": 3}],
+        }}),
+        json!({"command": "configurationDone"}),
+        json!({"command": "stackTrace", "arguments": {"threadId": 1}}),
+        json!({"command": "variables", "arguments": {"variablesReference": 1}}),
+        json!({"command": "variables", "arguments": {"variablesReference": 2}}),
+        json!({"command": "next", "arguments": {"threadId": 1}}),
+        json!({"command": "evaluate", "arguments": {"expression": "c", "frameId": 0}}),
+        json!({"command": "continue", "arguments": {"threadId": 1}}),
+        json!({"command": "disconnect"}),
+    ])?;
+
+    assert!(messages
+        .iter()
+        .filter(|m| m["type"] == "response")
+        .all(|m| m["success"] == true));
+
+    let breakpoints = &response(&messages, "setBreakpoints")["body"]["breakpoints"];
+    assert_eq!(breakpoints[0]["verified"], true);
+
+    let stopped = events(&messages, "stopped")
+        .map(|e| e["body"]["reason"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(stopped, vec!["breakpoint", "step"]);
+
+    let frames = &response(&messages, "stackTrace")["body"]["stackFrames"];
+    assert_eq!(frames[0]["line"], 3);
+    assert_eq!(frames[0]["name"], "add(a, b)");
+    assert_eq!(frames[1]["line"], 9);
+    assert_eq!(frames[1]["name"], "main()");
+
+    let variables = messages
+        .iter()
+        .filter(|m| m["type"] == "response" && m["command"] == "variables")
+        .map(|m| {
+            m["body"]["variables"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v["name"].as_str().unwrap())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(variables, vec![vec!["a", "b"], vec!["x"]]);
+
+    let evaluate = &response(&messages, "evaluate")["body"]["result"];
+    assert_eq!(evaluate, "3");
+
+    let exited = events(&messages, "exited").next().expect("exited event");
+    assert_eq!(exited["body"]["exitCode"], 0);
+    Ok(())
+}
@@ -48,6 +48,8 @@
 //! * [fs]
 //! * [process]
 //! * [signal]
+//! * [rand]
+//! * [regex]
 //!
 //! ## Features
 //!
@@ -59,6 +61,8 @@
 //! * `fs` for the [fs module]][fs]
 //! * `process` for the [process module]][process]
 //! * `signal` for the [process module]][signal]
+//! * `rand` for the [rand module][rand]
+//! * `regex` for the [regex module][regex]
 //!
 //! [http]: https://docs.rs/rune-modules/0/rune_modules/http/
 //! [json]: https://docs.rs/rune-modules/0/rune_modules/json/
@@ -67,6 +71,8 @@
 //! [fs]: https://docs.rs/rune-modules/0/rune_modules/fs/
 //! [process]: https://docs.rs/rune-modules/0/rune_modules/process/
 //! [signal]: https://docs.rs/rune-modules/0/rune_modules/signal/
+//! [rand]: https://docs.rs/rune-modules/0/rune_modules/rand/
+//! [regex]: https://docs.rs/rune-modules/0/rune_modules/regex/
 
 #[cfg(feature = "http")]
 pub mod http;
@@ -88,3 +94,9 @@ pub mod process;
 
 #[cfg(feature = "signal")]
 pub mod signal;
+
+#[cfg(feature = "rand")]
+pub mod rand;
+
+#[cfg(feature = "regex")]
+pub mod regex;
@@ -0,0 +1,296 @@
+//! The native `rand` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["rand"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::rand::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use rand;
+//!
+//! fn main() {
+//!     let rng = rand::Rng::new(42);
+//!     let roll = rng.int_range(1, 7);
+//!     println(`rolled a {roll}`);
+//!
+//!     let cards = ["ace", "king", "queen"];
+//!     rand::shuffle(cards);
+//!     dbg(cards);
+//! }
+//! ```
+//!
+//! Integers are generated with `int()`, which produces any integer, or with
+//! `int_range(start, end)`, which produces an integer in `start..end`. Rune
+//! doesn't have range values which could be passed as a single argument, so
+//! the bounds are passed separately, just like they are to
+//! `std::iter::range`.
+//!
+//! A generator constructed with `Rng::new` produces the same sequence of
+//! values for the same seed, on every platform. The free functions use a
+//! generator local to the current thread, which is seeded from entropy unless
+//! it's reseeded with `rand::seed`.
+
+use rand_core::{RngCore as _, SeedableRng as _};
+use runestick::{Any, ContextError, Module, Value, VmError};
+use std::cell::RefCell;
+
+/// Construct the `rand` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["rand"]);
+    module.ty(&["Rng"]).build::<Rng>()?;
+    module.function(&["Rng", "new"], Rng::new)?;
+    module.function(&["Rng", "from_entropy"], Rng::from_entropy)?;
+    module.inst_fn("int", Rng::int)?;
+    module.inst_fn("int_range", Rng::int_range)?;
+    module.inst_fn("float", Rng::float)?;
+    module.inst_fn("bool", Rng::bool)?;
+    module.inst_fn("choose", Rng::choose)?;
+    module.inst_fn("shuffle", Rng::shuffle)?;
+
+    module.function(&["seed"], seed)?;
+    module.function(&["int"], int)?;
+    module.function(&["int_range"], int_range)?;
+    module.function(&["float"], float)?;
+    module.function(&["bool"], bool)?;
+    module.function(&["choose"], choose)?;
+    module.function(&["shuffle"], shuffle)?;
+    Ok(module)
+}
+
+thread_local! {
+    static THREAD_RNG: RefCell<Rng> = RefCell::new(Rng::from_entropy());
+}
+
+/// A seedable random number generator.
+#[derive(Debug, Clone, Any)]
+pub struct Rng {
+    rng: rand_pcg::Pcg64,
+}
+
+impl Rng {
+    /// Construct a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: rand_pcg::Pcg64::seed_from_u64(seed),
+        }
+    }
+
+    /// Construct a generator seeded from the entropy source of the operating
+    /// system.
+    pub fn from_entropy() -> Self {
+        Self {
+            rng: rand_pcg::Pcg64::from_entropy(),
+        }
+    }
+
+    /// Generate any integer.
+    pub fn int(&mut self) -> i64 {
+        self.rng.next_u64() as i64
+    }
+
+    /// Generate an integer in the range `start..end`, which must be
+    /// non-empty.
+    pub fn int_range(&mut self, start: i64, end: i64) -> Result<i64, VmError> {
+        if start >= end {
+            return Err(VmError::panic(format!(
+                "cannot generate an integer in the empty range {}..{}",
+                start, end
+            )));
+        }
+
+        // NB: the span of two integers always fits in an unsigned integer.
+        let span = end.wrapping_sub(start) as u64;
+        Ok(start.wrapping_add(self.below(span) as i64))
+    }
+
+    /// Generate a float in the range `0.0..1.0`.
+    pub fn float(&mut self) -> f64 {
+        // NB: a float has 53 bits of precision.
+        (self.rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a boolean.
+    pub fn bool(&mut self) -> bool {
+        self.rng.next_u64() >> 63 == 1
+    }
+
+    /// Pick a random element from a vector, or `None` if it's empty.
+    pub fn choose(&mut self, vec: &[Value]) -> Option<Value> {
+        if vec.is_empty() {
+            return None;
+        }
+
+        let index = self.below(vec.len() as u64) as usize;
+        Some(vec[index].clone())
+    }
+
+    /// Shuffle a vector in place.
+    // NB: mutable slices can't be used as instance receivers.
+    #[allow(clippy::ptr_arg)]
+    pub fn shuffle(&mut self, vec: &mut Vec<Value>) {
+        for i in (1..vec.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            vec.swap(i, j);
+        }
+    }
+
+    /// Generate an unbiased integer in the range `0..n`, where `n` is
+    /// non-zero.
+    ///
+    /// This uses the multiply and reject method by Daniel Lemire, which is
+    /// implemented here rather than taken from a library so that the values
+    /// generated for a seed never change.
+    fn below(&mut self, n: u64) -> u64 {
+        let mut m = u128::from(self.rng.next_u64()) * u128::from(n);
+
+        if (m as u64) < n {
+            let threshold = n.wrapping_neg() % n;
+
+            while (m as u64) < threshold {
+                m = u128::from(self.rng.next_u64()) * u128::from(n);
+            }
+        }
+
+        (m >> 64) as u64
+    }
+}
+
+/// Reseed the generator of the current thread.
+fn seed(seed: u64) {
+    THREAD_RNG.with(|rng| *rng.borrow_mut() = Rng::new(seed));
+}
+
+fn int() -> i64 {
+    THREAD_RNG.with(|rng| rng.borrow_mut().int())
+}
+
+fn int_range(start: i64, end: i64) -> Result<i64, VmError> {
+    THREAD_RNG.with(|rng| rng.borrow_mut().int_range(start, end))
+}
+
+fn float() -> f64 {
+    THREAD_RNG.with(|rng| rng.borrow_mut().float())
+}
+
+fn bool() -> bool {
+    THREAD_RNG.with(|rng| rng.borrow_mut().bool())
+}
+
+fn choose(vec: &[Value]) -> Option<Value> {
+    THREAD_RNG.with(|rng| rng.borrow_mut().choose(vec))
+}
+
+#[allow(clippy::ptr_arg)]
+fn shuffle(vec: &mut Vec<Value>) {
+    THREAD_RNG.with(|rng| rng.borrow_mut().shuffle(vec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use runestick::Value;
+
+    #[test]
+    fn test_seeded_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.int(), b.int());
+        }
+
+        assert_ne!(Rng::new(1).int(), Rng::new(2).int());
+    }
+
+    /// The values generated for a seed must never change, since scripts rely
+    /// on them to replay simulations.
+    #[test]
+    fn test_seeded_golden_values() {
+        let mut rng = Rng::new(42);
+
+        let ints = (0..3).map(|_| rng.int()).collect::<Vec<_>>();
+        assert_eq!(
+            ints,
+            [
+                4178418447715145737,
+                4410739922618931473,
+                -4411844864043685331
+            ]
+        );
+
+        let ranges = (0..5)
+            .map(|_| rng.int_range(1, 7).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, [4, 6, 3, 2, 4]);
+
+        assert_eq!(rng.float(), 0.8025885957751138);
+        assert_eq!(rng.float(), 0.37085692752109345);
+
+        let bools = (0..5).map(|_| rng.bool()).collect::<Vec<_>>();
+        assert_eq!(bools, [false, true, false, false, false]);
+
+        let vec = (0..5).map(Value::Integer).collect::<Vec<_>>();
+        let chosen = (0..3)
+            .map(|_| rng.choose(&vec).unwrap().into_integer().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(chosen, [0, 2, 1]);
+
+        let mut vec = (0..8).map(Value::Integer).collect::<Vec<_>>();
+        rng.shuffle(&mut vec);
+        let shuffled = vec
+            .into_iter()
+            .map(|v| v.into_integer().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(shuffled, [4, 2, 7, 0, 1, 5, 3, 6]);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..1000 {
+            let n = rng.int_range(-3, 3).unwrap();
+            assert!((-3..3).contains(&n));
+
+            let f = rng.float();
+            assert!((0.0..1.0).contains(&f));
+        }
+
+        let n = rng.int_range(i64::MIN, i64::MAX).unwrap();
+        assert!(n < i64::MAX);
+        assert!(rng.int_range(1, 1).is_err());
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Rng::new(3);
+        let mut vec = (0..10).map(Value::Integer).collect::<Vec<_>>();
+        rng.shuffle(&mut vec);
+
+        let mut numbers = vec
+            .into_iter()
+            .map(|v| v.into_integer().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_ne!(numbers, (0..10).collect::<Vec<_>>());
+        numbers.sort();
+        assert_eq!(numbers, (0..10).collect::<Vec<_>>());
+    }
+}
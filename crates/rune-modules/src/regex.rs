@@ -0,0 +1,292 @@
+//! The native `regex` module for the [Rune Language].
+//!
+//! [Rune Language]: https://github.com/rune-rs/rune
+//!
+//! ## Usage
+//!
+//! Add the following to your `Cargo.toml`:
+//!
+//! ```toml
+//! rune-modules = {version = "0.6.16", features = ["regex"]}
+//! ```
+//!
+//! Install it into your context:
+//!
+//! ```rust
+//! # fn main() -> runestick::Result<()> {
+//! let mut context = runestick::Context::with_default_modules()?;
+//! context.install(&rune_modules::regex::module()?)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use it in Rune:
+//!
+//! ```rust,ignore
+//! use regex::Regex;
+//!
+//! fn main() {
+//!     let re = Regex::new("(?P<level>[A-Z]+): (.*)")?;
+//!
+//!     if let Some(groups) = re.captures_named("ERROR: disk full") {
+//!         dbg(groups.level);
+//!     }
+//!
+//!     let line = re.replace_all("WARN: low memory", |groups| {
+//!         `[{groups[1].unwrap()}]`
+//!     })?;
+//!
+//!     dbg(line);
+//! }
+//! ```
+//!
+//! Captured groups are represented as `Option<String>`, which is `None` for
+//! groups that didn't participate in the match.
+
+use runestick::{
+    Any, ContextError, Function, Iterator, Module, Object, Shared, ToValue, Value, VmError,
+};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `regex` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["regex"]);
+    module.ty(&["Regex"]).build::<Regex>()?;
+    module.ty(&["Match"]).build::<Match>()?;
+    module.ty(&["Error"]).build::<Error>()?;
+
+    module.function(&["Regex", "new"], Regex::new)?;
+    module.inst_fn("is_match", Regex::is_match)?;
+    module.inst_fn("find", Regex::find)?;
+    module.inst_fn("find_iter", Regex::find_iter)?;
+    module.inst_fn("captures", Regex::captures)?;
+    module.inst_fn("captures_named", Regex::captures_named)?;
+    module.inst_fn("replace", Regex::replace)?;
+    module.inst_fn("replace_all", Regex::replace_all)?;
+    module.inst_fn("split", Regex::split)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Regex::display)?;
+
+    module.inst_fn("start", Match::start)?;
+    module.inst_fn("end", Match::end)?;
+    module.inst_fn("as_str", Match::as_str)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Match::display)?;
+
+    module.inst_fn(runestick::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// An error raised when compiling a regular expression.
+#[derive(Debug, Any)]
+pub struct Error {
+    inner: regex::Error,
+}
+
+impl From<regex::Error> for Error {
+    fn from(inner: regex::Error) -> Self {
+        Self { inner }
+    }
+}
+
+impl Error {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
+
+/// A compiled regular expression.
+#[derive(Debug, Clone, Any)]
+pub struct Regex {
+    inner: regex::Regex,
+}
+
+impl Regex {
+    /// Compile a regular expression.
+    fn new(pattern: &str) -> Result<Self, Error> {
+        Ok(Self {
+            inner: regex::Regex::new(pattern)?,
+        })
+    }
+
+    /// Test if the regular expression matches anywhere in the text.
+    fn is_match(&self, text: &str) -> bool {
+        self.inner.is_match(text)
+    }
+
+    /// Find the leftmost match in the text.
+    fn find(&self, text: &str) -> Option<Match> {
+        self.inner.find(text).map(Match::from)
+    }
+
+    /// Iterate over all non-overlapping matches in the text.
+    fn find_iter(&self, text: &str) -> Result<Iterator, VmError> {
+        let mut matches = Vec::new();
+
+        for m in self.inner.find_iter(text) {
+            matches.push(Match::from(m).to_value()?);
+        }
+
+        Ok(Iterator::from_double_ended(matches.into_iter()))
+    }
+
+    /// Get the groups captured by the leftmost match in the text, indexed by
+    /// their position. The group at index `0` is the whole match.
+    fn captures(&self, text: &str) -> Option<Vec<Option<String>>> {
+        self.inner.captures(text).map(|captures| groups(&captures))
+    }
+
+    /// Get the named groups captured by the leftmost match in the text.
+    fn captures_named(&self, text: &str) -> Result<Option<Object>, VmError> {
+        let captures = match self.inner.captures(text) {
+            Some(captures) => captures,
+            None => return Ok(None),
+        };
+
+        let mut object = Object::new();
+
+        for name in self.inner.capture_names().flatten() {
+            let group = captures.name(name).map(|m| m.as_str().to_owned());
+            object.insert(name.to_owned(), group.to_value()?);
+        }
+
+        Ok(Some(object))
+    }
+
+    /// Replace the leftmost match in the text.
+    ///
+    /// The replacement is either a string, where `$1` or `$name` refers to a
+    /// captured group, or a function which is called with the captured groups
+    /// and returns the replacement.
+    fn replace(&self, text: &str, replacement: Value) -> Result<String, VmError> {
+        self.replacen(text, 1, replacement)
+    }
+
+    /// Replace all non-overlapping matches in the text.
+    ///
+    /// See `replace` for how the replacement is specified.
+    fn replace_all(&self, text: &str, replacement: Value) -> Result<String, VmError> {
+        self.replacen(text, 0, replacement)
+    }
+
+    /// Split the text by the matches of the regular expression.
+    fn split(&self, text: &str) -> Vec<String> {
+        self.inner.split(text).map(String::from).collect()
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+
+    /// Replace up to `limit` matches in the text, or all of them if `limit`
+    /// is zero.
+    fn replacen(&self, text: &str, limit: usize, replacement: Value) -> Result<String, VmError> {
+        let replacement = Replacement::from_value(replacement)?;
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for (n, captures) in self.inner.captures_iter(text).enumerate() {
+            if limit != 0 && n == limit {
+                break;
+            }
+
+            // NB: the group at index 0 is always present.
+            let m = captures.get(0).expect("missing whole match");
+            out.push_str(&text[last..m.start()]);
+
+            match &replacement {
+                Replacement::String(string) => captures.expand(string, &mut out),
+                Replacement::Function(function) => {
+                    let function = function.borrow_ref()?;
+                    out.push_str(&function.call::<_, String>((groups(&captures),))?);
+                }
+            }
+
+            last = m.end();
+        }
+
+        out.push_str(&text[last..]);
+        Ok(out)
+    }
+}
+
+/// A single match of a regular expression.
+#[derive(Debug, Clone, Any)]
+pub struct Match {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl Match {
+    /// The byte offset where the match starts.
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset where the match ends.
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The matched text.
+    fn as_str(&self) -> String {
+        self.text.clone()
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        buf.push_str(&self.text);
+        Ok(())
+    }
+}
+
+impl From<regex::Match<'_>> for Match {
+    fn from(m: regex::Match<'_>) -> Self {
+        Self {
+            start: m.start(),
+            end: m.end(),
+            text: m.as_str().to_owned(),
+        }
+    }
+}
+
+/// How the matches of `replace` and `replace_all` are replaced.
+enum Replacement {
+    String(String),
+    Function(Shared<Function>),
+}
+
+impl Replacement {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::String(string) => Self::String(string.borrow_ref()?.clone()),
+            Value::StaticString(string) => Self::String((**string).clone()),
+            Value::Function(function) => Self::Function(function),
+            actual => return Err(VmError::expected::<Function>(actual.type_info()?)),
+        })
+    }
+}
+
+/// Collect the captured groups, indexed by their position.
+fn groups(captures: &regex::Captures<'_>) -> Vec<Option<String>> {
+    captures
+        .iter()
+        .map(|group| group.map(|m| m.as_str().to_owned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Regex;
+    use runestick::Value;
+
+    #[test]
+    fn test_replace_with_groups() {
+        let re = Regex::new(r"(\w+)=(\d+)").unwrap();
+        let text = "a=1, b=2";
+        let replacement = |s: &str| Value::from(s.to_owned());
+
+        assert_eq!(re.replacen(text, 1, replacement("$2")).unwrap(), "1, b=2");
+        assert_eq!(re.replacen(text, 0, replacement("$1")).unwrap(), "a, b");
+        assert_eq!(re.replacen("none", 0, replacement("$1")).unwrap(), "none");
+    }
+}
@@ -26,36 +26,493 @@
 //! use time;
 //!
 //! fn main() {
+//!     let start = time::Instant::now();
 //!     time::delay_for(time::Duration::from_secs(10)).await;
-//!     println("Message after 10 seconds!");
+//!     println(`Message after {start.elapsed()}!`);
+//!
+//!     let now = time::SystemTime::now();
+//!     println(`It's now {now.to_rfc3339()}`);
+//!
+//!     match time::timeout(time::Duration::from_millis(100), slow()).await {
+//!         Ok(value) => dbg(value),
+//!         Err(error) => println(`{error}`),
+//!     }
 //! }
 //! ```
+//!
+//! Since native types can't be compared with operators like `<`, the types in
+//! this module have a `cmp` method which returns a negative integer, zero or a
+//! positive integer, just like the comparator expected by `Vec::sort_by`.
 
-use runestick::{Any, ContextError, Module};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use runestick::{Any, ContextError, Future, Module, Value, VmError, VmErrorKind};
+use std::cmp;
+use std::fmt;
+use std::fmt::Write as _;
 
 /// Construct the `time` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["time"]);
+    module.ty(&["Duration"]).build::<Duration>()?;
+    module.ty(&["Instant"]).build::<Instant>()?;
+    module.ty(&["SystemTime"]).build::<SystemTime>()?;
+    module.ty(&["ParseError"]).build::<ParseError>()?;
+    module.ty(&["Interval"]).build::<Interval>()?;
+    module.ty(&["Elapsed"]).build::<Elapsed>()?;
+
     module.function(&["Duration", "from_secs"], Duration::from_secs)?;
+    module.function(&["Duration", "from_millis"], Duration::from_millis)?;
+    module.function(&["Duration", "from_micros"], Duration::from_micros)?;
+    module.function(&["Duration", "from_nanos"], Duration::from_nanos)?;
+    module.function(&["Duration", "from_secs_f64"], Duration::from_secs_f64)?;
+    module.inst_fn("as_secs", Duration::as_secs)?;
+    module.inst_fn("as_millis", Duration::as_millis)?;
+    module.inst_fn("as_micros", Duration::as_micros)?;
+    module.inst_fn("as_nanos", Duration::as_nanos)?;
+    module.inst_fn("as_secs_f64", Duration::as_secs_f64)?;
+    module.inst_fn("subsec_millis", Duration::subsec_millis)?;
+    module.inst_fn("subsec_nanos", Duration::subsec_nanos)?;
+    module.inst_fn("is_zero", Duration::is_zero)?;
+    module.inst_fn("cmp", Duration::cmp)?;
+    module.inst_fn(runestick::ADD, Duration::add)?;
+    module.inst_fn(runestick::ADD_ASSIGN, Duration::add_assign)?;
+    module.inst_fn(runestick::SUB, Duration::sub)?;
+    module.inst_fn(runestick::SUB_ASSIGN, Duration::sub_assign)?;
+    module.inst_fn(runestick::MUL, Duration::mul)?;
+    module.inst_fn(runestick::DIV, Duration::div)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Duration::display)?;
+
+    module.function(&["Instant", "now"], Instant::now)?;
+    module.inst_fn("elapsed", Instant::elapsed)?;
+    module.inst_fn("duration_since", Instant::duration_since)?;
+    module.inst_fn("cmp", Instant::cmp)?;
+    module.inst_fn(runestick::ADD, Instant::add)?;
+    module.inst_fn(runestick::SUB, Instant::sub)?;
+
+    module.function(&["SystemTime", "now"], SystemTime::now)?;
+    module.function(
+        &["SystemTime", "from_unix_secs"],
+        SystemTime::from_unix_secs,
+    )?;
+    module.function(
+        &["SystemTime", "from_unix_millis"],
+        SystemTime::from_unix_millis,
+    )?;
+    module.function(&["SystemTime", "parse_rfc3339"], SystemTime::parse_rfc3339)?;
+    module.function(&["SystemTime", "parse"], SystemTime::parse)?;
+    module.inst_fn("unix_secs", SystemTime::unix_secs)?;
+    module.inst_fn("unix_millis", SystemTime::unix_millis)?;
+    module.inst_fn("to_rfc3339", SystemTime::to_rfc3339)?;
+    module.inst_fn("format", SystemTime::format)?;
+    module.inst_fn("duration_since", SystemTime::duration_since)?;
+    module.inst_fn("cmp", SystemTime::cmp)?;
+    module.inst_fn(runestick::ADD, SystemTime::add)?;
+    module.inst_fn(runestick::SUB, SystemTime::sub)?;
+    module.inst_fn(runestick::STRING_DISPLAY, SystemTime::display)?;
+    module.inst_fn(runestick::STRING_DISPLAY, ParseError::display)?;
+
+    module.function(&["interval"], interval)?;
+    module.async_inst_fn("tick", Interval::tick)?;
+    module.async_inst_fn("next", Interval::next)?;
+
     module.async_function(&["delay_for"], delay_for)?;
+    module.async_function(&["timeout"], timeout)?;
+    module.inst_fn(runestick::STRING_DISPLAY, Elapsed::display)?;
     Ok(module)
 }
 
 #[derive(Debug, Clone, Copy, Any)]
 struct Duration {
-    inner: tokio::time::Duration,
+    inner: std::time::Duration,
 }
 
 impl Duration {
     /// Construct a duration from seconds.
     fn from_secs(secs: u64) -> Self {
+        Self::from(std::time::Duration::from_secs(secs))
+    }
+
+    /// Construct a duration from milliseconds.
+    fn from_millis(millis: u64) -> Self {
+        Self::from(std::time::Duration::from_millis(millis))
+    }
+
+    /// Construct a duration from microseconds.
+    fn from_micros(micros: u64) -> Self {
+        Self::from(std::time::Duration::from_micros(micros))
+    }
+
+    /// Construct a duration from nanoseconds.
+    fn from_nanos(nanos: u64) -> Self {
+        Self::from(std::time::Duration::from_nanos(nanos))
+    }
+
+    /// Construct a duration from fractional seconds, which must be finite and
+    /// not negative.
+    fn from_secs_f64(secs: f64) -> Result<Self, VmError> {
+        match std::time::Duration::try_from_secs_f64(secs) {
+            Ok(inner) => Ok(Self::from(inner)),
+            Err(error) => Err(VmError::panic(format!(
+                "bad duration `{}`: {}",
+                secs, error
+            ))),
+        }
+    }
+
+    fn as_secs(&self) -> u64 {
+        self.inner.as_secs()
+    }
+
+    fn as_millis(&self) -> u128 {
+        self.inner.as_millis()
+    }
+
+    fn as_micros(&self) -> u128 {
+        self.inner.as_micros()
+    }
+
+    fn as_nanos(&self) -> u128 {
+        self.inner.as_nanos()
+    }
+
+    fn as_secs_f64(&self) -> f64 {
+        self.inner.as_secs_f64()
+    }
+
+    fn subsec_millis(&self) -> u32 {
+        self.inner.subsec_millis()
+    }
+
+    fn subsec_nanos(&self) -> u32 {
+        self.inner.subsec_nanos()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.inner.is_zero()
+    }
+
+    fn cmp(&self, other: &Self) -> i64 {
+        ordering(self.inner.cmp(&other.inner))
+    }
+
+    fn add(&self, other: &Self) -> Result<Self, VmError> {
+        let inner = self
+            .inner
+            .checked_add(other.inner)
+            .ok_or(VmErrorKind::Overflow)?;
+
+        Ok(Self::from(inner))
+    }
+
+    fn add_assign(&mut self, other: &Self) -> Result<(), VmError> {
+        *self = self.add(other)?;
+        Ok(())
+    }
+
+    fn sub(&self, other: &Self) -> Result<Self, VmError> {
+        let inner = self
+            .inner
+            .checked_sub(other.inner)
+            .ok_or(VmErrorKind::Underflow)?;
+
+        Ok(Self::from(inner))
+    }
+
+    fn sub_assign(&mut self, other: &Self) -> Result<(), VmError> {
+        *self = self.sub(other)?;
+        Ok(())
+    }
+
+    fn mul(&self, n: u32) -> Result<Self, VmError> {
+        let inner = self.inner.checked_mul(n).ok_or(VmErrorKind::Overflow)?;
+        Ok(Self::from(inner))
+    }
+
+    fn div(&self, n: u32) -> Result<Self, VmError> {
+        let inner = self.inner.checked_div(n).ok_or(VmErrorKind::DivideByZero)?;
+        Ok(Self::from(inner))
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{:?}", self.inner)
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(inner: std::time::Duration) -> Self {
+        Self { inner }
+    }
+}
+
+/// A monotonic point in time, used to measure elapsed time.
+#[derive(Debug, Clone, Copy, Any)]
+struct Instant {
+    inner: tokio::time::Instant,
+}
+
+impl Instant {
+    fn now() -> Self {
         Self {
-            inner: tokio::time::Duration::from_secs(secs),
+            inner: tokio::time::Instant::now(),
         }
     }
+
+    /// The time elapsed since the instant.
+    fn elapsed(&self) -> Duration {
+        Duration::from(self.inner.elapsed())
+    }
+
+    /// The time elapsed from an earlier instant to this one, which is zero if
+    /// the other instant is later.
+    fn duration_since(&self, earlier: &Self) -> Duration {
+        Duration::from(self.inner.saturating_duration_since(earlier.inner))
+    }
+
+    fn cmp(&self, other: &Self) -> i64 {
+        ordering(self.inner.cmp(&other.inner))
+    }
+
+    fn add(&self, duration: &Duration) -> Result<Self, VmError> {
+        let inner = self
+            .inner
+            .checked_add(duration.inner)
+            .ok_or(VmErrorKind::Overflow)?;
+
+        Ok(Self { inner })
+    }
+
+    fn sub(&self, duration: &Duration) -> Result<Self, VmError> {
+        let inner = self
+            .inner
+            .checked_sub(duration.inner)
+            .ok_or(VmErrorKind::Underflow)?;
+
+        Ok(Self { inner })
+    }
 }
 
-/// Convert any value to a json string.
+/// A point in wall clock time, in UTC.
+#[derive(Debug, Clone, Copy, Any)]
+struct SystemTime {
+    inner: DateTime<Utc>,
+}
+
+impl SystemTime {
+    fn now() -> Self {
+        Self {
+            inner: DateTime::from(std::time::SystemTime::now()),
+        }
+    }
+
+    /// Construct a time from the seconds since the unix epoch.
+    fn from_unix_secs(secs: i64) -> Result<Self, VmError> {
+        match DateTime::from_timestamp(secs, 0) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    /// Construct a time from the milliseconds since the unix epoch.
+    fn from_unix_millis(millis: i64) -> Result<Self, VmError> {
+        match DateTime::from_timestamp_millis(millis) {
+            Some(inner) => Ok(Self { inner }),
+            None => Err(VmError::from(VmErrorKind::Overflow)),
+        }
+    }
+
+    /// Parse an RFC 3339 timestamp, like `2020-09-01T12:30:00+02:00`.
+    fn parse_rfc3339(s: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            inner: DateTime::parse_from_rfc3339(s)?.with_timezone(&Utc),
+        })
+    }
+
+    /// Parse a time in UTC according to a `strftime`-like format string.
+    fn parse(s: &str, format: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            inner: NaiveDateTime::parse_from_str(s, format)?.and_utc(),
+        })
+    }
+
+    /// The seconds since the unix epoch.
+    fn unix_secs(&self) -> i64 {
+        self.inner.timestamp()
+    }
+
+    /// The milliseconds since the unix epoch.
+    fn unix_millis(&self) -> i64 {
+        self.inner.timestamp_millis()
+    }
+
+    /// Format the time as an RFC 3339 timestamp, like
+    /// `2020-09-01T10:30:00Z`.
+    #[allow(clippy::wrong_self_convention)]
+    fn to_rfc3339(&self) -> String {
+        self.inner.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+
+    /// Format the time according to a `strftime`-like format string.
+    fn format(&self, format: &str) -> Result<String, VmError> {
+        let mut out = String::new();
+
+        // NB: formatting only fails if the format string is invalid.
+        if write!(out, "{}", self.inner.format(format)).is_err() {
+            return Err(VmError::panic(format!("bad time format `{}`", format)));
+        }
+
+        Ok(out)
+    }
+
+    /// The time elapsed from an earlier time to this one.
+    ///
+    /// This errors if the other time is later, which can happen even if it was
+    /// constructed earlier since the wall clock might be adjusted.
+    fn duration_since(&self, earlier: &Self) -> Result<Duration, VmError> {
+        match (self.inner - earlier.inner).to_std() {
+            Ok(inner) => Ok(Duration::from(inner)),
+            Err(..) => Err(VmError::from(VmErrorKind::Underflow)),
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> i64 {
+        ordering(self.inner.cmp(&other.inner))
+    }
+
+    fn add(&self, duration: &Duration) -> Result<Self, VmError> {
+        let inner = chrono::Duration::from_std(duration.inner)
+            .ok()
+            .and_then(|duration| self.inner.checked_add_signed(duration))
+            .ok_or(VmErrorKind::Overflow)?;
+
+        Ok(Self { inner })
+    }
+
+    fn sub(&self, duration: &Duration) -> Result<Self, VmError> {
+        let inner = chrono::Duration::from_std(duration.inner)
+            .ok()
+            .and_then(|duration| self.inner.checked_sub_signed(duration))
+            .ok_or(VmErrorKind::Underflow)?;
+
+        Ok(Self { inner })
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        buf.push_str(&self.to_rfc3339());
+        Ok(())
+    }
+}
+
+/// An error raised when parsing a time.
+#[derive(Debug, Any)]
+pub struct ParseError {
+    inner: chrono::ParseError,
+}
+
+impl From<chrono::ParseError> for ParseError {
+    fn from(inner: chrono::ParseError) -> Self {
+        Self { inner }
+    }
+}
+
+impl ParseError {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
+
+/// A stream of instants produced at a fixed period.
+#[derive(Debug, Any)]
+struct Interval {
+    inner: tokio::time::Interval,
+}
+
+impl Interval {
+    /// Wait until the next instant in the interval. The first tick completes
+    /// immediately.
+    async fn tick(&mut self) -> Instant {
+        Instant {
+            inner: self.inner.tick().await,
+        }
+    }
+
+    /// Get the next instant in the interval, like the `next` function of a
+    /// stream. Since the interval never ends, this is never `None`.
+    async fn next(&mut self) -> Option<Instant> {
+        Some(self.tick().await)
+    }
+}
+
+/// Construct an interval which produces an instant every `period`, which
+/// must be non-zero.
+fn interval(period: &Duration) -> Result<Interval, VmError> {
+    if period.is_zero() {
+        return Err(VmError::panic("interval period must be non-zero"));
+    }
+
+    Ok(Interval {
+        inner: tokio::time::interval(period.inner),
+    })
+}
+
+/// The error produced when a `timeout` elapsed.
+#[derive(Debug, Any)]
+pub struct Elapsed {
+    inner: tokio::time::Elapsed,
+}
+
+impl Elapsed {
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        write!(buf, "{}", self.inner)
+    }
+}
+
+/// Wait for the given duration.
 async fn delay_for(duration: &Duration) {
     tokio::time::delay_for(duration.inner).await;
 }
+
+/// Wait for a future to complete, resolving to `Ok` with its output, or to
+/// `Err` if the duration elapsed first.
+async fn timeout(duration: &Duration, future: Future) -> Result<Result<Value, Elapsed>, VmError> {
+    match tokio::time::timeout(duration.inner, future).await {
+        Ok(output) => Ok(Ok(output?)),
+        Err(inner) => Ok(Err(Elapsed { inner })),
+    }
+}
+
+/// Convert an ordering into an integer, as expected by `Vec::sort_by`.
+fn ordering(ordering: cmp::Ordering) -> i64 {
+    ordering as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Duration, SystemTime};
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let a = Duration::from_millis(1500);
+        let b = Duration::from_secs(1);
+
+        assert_eq!(a.add(&b).unwrap().as_millis(), 2500);
+        assert_eq!(a.sub(&b).unwrap().as_millis(), 500);
+        assert!(b.sub(&a).is_err());
+        assert_eq!(a.mul(2).unwrap().as_secs(), 3);
+        assert!(a.div(0).is_err());
+        assert_eq!(a.cmp(&b), 1);
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        let time = SystemTime::parse_rfc3339("2020-09-01T12:30:00+02:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2020-09-01T10:30:00Z");
+        assert_eq!(time.unix_secs(), 1_598_956_200);
+
+        let later = time.add(&Duration::from_millis(1500)).unwrap();
+        assert_eq!(later.to_rfc3339(), "2020-09-01T10:30:01.500Z");
+        assert_eq!(later.duration_since(&time).unwrap().as_millis(), 1500);
+        assert!(time.duration_since(&later).is_err());
+
+        assert!(SystemTime::parse_rfc3339("2020-09-01").is_err());
+    }
+}
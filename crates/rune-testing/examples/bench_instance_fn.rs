@@ -0,0 +1,127 @@
+//! Benchmark for instance function calls and field accesses in a loop.
+//!
+//! Run with `cargo run --release --example bench_instance_fn`.
+
+use rune::{Options, Sources, Warnings};
+use runestick::{Any, Context, FromValue, Module, Source, Vm};
+use std::sync::Arc;
+use std::time::Instant;
+
+const ITERATIONS: i64 = 1_000_000;
+
+#[derive(Debug, Clone, Any)]
+struct Point {
+    x: i64,
+}
+
+impl Point {
+    fn new(x: i64) -> Self {
+        Self { x }
+    }
+
+    fn add(&self, value: i64) -> i64 {
+        self.x + value
+    }
+
+    fn x(&self) -> i64 {
+        self.x
+    }
+}
+
+#[tokio::main]
+async fn main() -> runestick::Result<()> {
+    let mut module = Module::new(&["bench"]);
+    module.ty(&["Point"]).build::<Point>()?;
+    module.function(&["Point", "new"], Point::new)?;
+    module.inst_fn("add", Point::add)?;
+    module.getter("x", Point::x)?;
+
+    let mut context = Context::with_default_modules()?;
+    context.install(&module)?;
+
+    let options = Options::default();
+    let mut warnings = Warnings::disabled();
+    let mut sources = Sources::new();
+
+    sources.insert_default(Source::new(
+        "bench",
+        r#"
+        use bench::Point;
+
+        struct Counter { value }
+
+        impl Counter {
+            fn get(self) { self.value }
+        }
+
+        fn instance_fn(n) {
+            let counter = Counter { value: 1 };
+            let point = Point::new(1);
+            let sum = 0;
+            let i = 0;
+
+            while i < n {
+                sum = sum + counter.get() + point.add(1);
+                i = i + 1;
+            }
+
+            sum
+        }
+
+        struct Vec3 { x, y, z }
+
+        fn struct_field(n) {
+            let v = Vec3 { x: 1, y: 2, z: 3 };
+            let sum = 0;
+            let i = 0;
+
+            while i < n {
+                v.x = v.y + v.z;
+                sum = sum + v.x;
+                i = i + 1;
+            }
+
+            sum
+        }
+
+        fn field(n) {
+            let point = Point::new(1);
+            let sum = 0;
+            let i = 0;
+
+            while i < n {
+                sum = sum + point.x;
+                i = i + 1;
+            }
+
+            sum
+        }
+        "#,
+    ));
+
+    let context = Arc::new(context);
+    let unit = Arc::new(rune::load_sources(
+        &context,
+        &options,
+        &mut sources,
+        &mut warnings,
+    )?);
+
+    for name in &["instance_fn", "field", "struct_field"] {
+        let vm = Vm::new(context.clone(), unit.clone());
+        let start = Instant::now();
+        let output = vm.execute([*name], (ITERATIONS,))?.complete()?;
+        let elapsed = start.elapsed();
+        let output = i64::from_value(output)?;
+
+        println!(
+            "{}: {:?} ({:?}/iter, output: {})",
+            name,
+            elapsed,
+            elapsed / ITERATIONS as u32,
+            output
+        );
+    }
+
+    Ok(())
+}
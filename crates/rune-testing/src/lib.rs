@@ -49,6 +49,7 @@ use rune::Sources;
 use rune::UnitBuilder;
 pub use rune::WarningKind::*;
 use rune::Warnings;
+use rune::{NoopCompileVisitor, Options};
 pub use runestick::Result;
 pub use runestick::VmErrorKind::*;
 pub use runestick::{CompileMeta, Function, Span, Value};
@@ -58,13 +59,34 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 /// Compile the given source into a unit and collection of warnings.
+///
+/// Compiler options can be changed from their defaults through the
+/// `RUNE_TEST_OPTIONS` environment variable, as a comma-separated list of
+/// options using the same syntax as the `-O` option of the Rune CLI. Like
+/// `RUNE_TEST_OPTIONS=peephole=false`.
 pub fn compile_source(context: &runestick::Context, source: &str) -> Result<(Unit, Warnings)> {
+    let mut options = Options::default();
+
+    if let Ok(test_options) = std::env::var("RUNE_TEST_OPTIONS") {
+        for option in test_options.split(',').filter(|option| !option.is_empty()) {
+            options.parse_option(option)?;
+        }
+    }
+
     let mut warnings = Warnings::new();
     let mut sources = Sources::new();
     sources.insert_default(Source::new("main", source.to_owned()));
     let unit = Rc::new(RefCell::new(UnitBuilder::with_default_prelude()));
 
-    rune::compile(context, &mut sources, &unit, &mut warnings)?;
+    let mut visitor = NoopCompileVisitor::new();
+    rune::compile_with_options(
+        context,
+        &mut sources,
+        &unit,
+        &mut warnings,
+        &options,
+        &mut visitor,
+    )?;
 
     let unit = Rc::try_unwrap(unit).unwrap().into_inner();
     Ok((unit.into_unit(), warnings))
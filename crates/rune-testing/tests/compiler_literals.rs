@@ -39,3 +39,32 @@ fn test_number_literals() {
         }
     };
 }
+
+#[test]
+fn test_integer_suffixes() {
+    assert_parse!(r#"fn main() { 18446744073709551615u64 }"#);
+    assert_parse!(r#"fn main() { -170141183460469231731687303715884105728i128 }"#);
+    assert_parse!(r#"fn main() { 0xffffffffffffffffffffffffffffffffu128 }"#);
+    assert_parse!(r#"fn main() { -1000000000000000000000000000000000000000000n }"#);
+
+    assert_compile_error! {
+        r#"fn main() { -1u64 }"#,
+        ParseError { error: BadNumberOutOfBounds { span, .. }} => {
+            assert_eq!(span, Span::new(12, 17));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { 1.5u64 }"#,
+        ParseError { error: BadNumberLiteral { span, .. }} => {
+            assert_eq!(span, Span::new(12, 18));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { match 1 { 1u64 => true, _ => false } }"#,
+        MatchWideIntegerInPattern { span, .. } => {
+            assert_eq!(span, Span::new(22, 26));
+        }
+    };
+}
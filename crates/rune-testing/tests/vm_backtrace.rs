@@ -0,0 +1,82 @@
+use rune::termcolor::NoColor;
+use rune::EmitDiagnostics as _;
+use rune_testing::*;
+use runestick::{Context, Source, Vm, VmError};
+use std::sync::Arc;
+
+fn error(source: &str) -> Result<VmError> {
+    let context = Context::with_default_modules()?;
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    Ok(vm.execute(&["main"], ())?.complete().unwrap_err())
+}
+
+fn items(error: &VmError) -> Vec<String> {
+    error
+        .backtrace()
+        .iter()
+        .map(|frame| {
+            frame
+                .item()
+                .map(|item| item.to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[test]
+fn test_backtrace() -> Result<()> {
+    const SOURCE: &str = r#"
+    fn div(a, b) {
+        a / b
+    }
+
+    fn calc(n) {
+        div(10, n)
+    }
+
+    fn main() {
+        calc(0)
+    }
+    "#;
+
+    let error = error(SOURCE)?;
+    assert_eq!(items(&error), vec!["div", "calc", "main"]);
+
+    let (_, span) = error.backtrace()[0].span().expect("span");
+    assert_eq!(&SOURCE[span.start..span.end], "a / b");
+
+    let (_, span) = error.backtrace()[1].span().expect("span");
+    assert_eq!(&SOURCE[span.start..span.end], "div(10, n)");
+
+    let mut sources = rune::Sources::new();
+    sources.insert_default(Source::new("main", SOURCE));
+
+    let mut out = NoColor::new(Vec::new());
+    error.emit_diagnostics(&mut out, &sources)?;
+    let out = String::from_utf8(out.into_inner())?;
+
+    assert!(out.contains("division by zero"));
+    assert!(out.contains("in `div`"));
+    assert!(out.contains("called from `calc`"));
+    assert!(out.contains("called from `main`"));
+    Ok(())
+}
+
+#[test]
+fn test_backtrace_through_generator() -> Result<()> {
+    const SOURCE: &str = r#"
+    fn gen(n) {
+        yield 10 / n;
+    }
+
+    fn main() {
+        let g = gen(0);
+        g.next()
+    }
+    "#;
+
+    let error = error(SOURCE)?;
+    assert_eq!(items(&error), vec!["gen", "main"]);
+    Ok(())
+}
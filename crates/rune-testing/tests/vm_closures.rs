@@ -165,3 +165,30 @@ fn test_nested_async_closure() {
         }
     };
 }
+
+#[test]
+fn test_closures_in_literals_and_chains() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Wrapper { value }
+
+            impl Wrapper {
+                fn apply(self, f) {
+                    Wrapper { value: f(self.value) }
+                }
+            }
+
+            fn main() {
+                let n = 2;
+                let fns = [|x| x + n, |x| x * n];
+                let object = #{ f: || #{ n } };
+                let f = object.f;
+                let wrapper = Wrapper { value: 1 }.apply(|x| x + 1).apply(|x| x * 10);
+                (fns[1](fns[0](1)), f().n, wrapper.value)
+            }
+            "#
+        },
+        (6, 2, 20),
+    };
+}
@@ -0,0 +1,178 @@
+use rune_testing::*;
+
+#[test]
+fn test_hash_map() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, bool, i64) => r#"
+            use std::collections::HashMap;
+
+            enum Color { Red, Green }
+
+            fn main() {
+                let map = HashMap::new();
+                map.insert(1, 10);
+                map.insert("two", 20);
+                map.insert((3, 'c'), 30);
+                map.insert(Color::Red, 40);
+                map.insert(1, 11);
+
+                let name = String::from_str("tw");
+                name.push('o');
+
+                let sum = 0;
+
+                for entry in map {
+                    sum += entry.1;
+                }
+
+                let removed = map.remove(Color::Red).unwrap_or(0);
+
+                (
+                    map.get(1).unwrap_or(0) + map.get(name).unwrap_or(0),
+                    map.get((3, 'c')).unwrap_or(0),
+                    sum,
+                    map.contains_key(Color::Green),
+                    map.len() + removed,
+                )
+            }
+            "#
+        },
+        (31, 30, 101, false, 43),
+    };
+}
+
+#[test]
+fn test_hash_set() {
+    assert_eq! {
+        rune! {
+            (i64, bool, bool, i64) => r#"
+            use std::collections::HashSet;
+
+            fn main() {
+                let set = HashSet::new();
+                set.insert(b"foo");
+                set.insert(b"foo");
+                set.insert(1.5);
+                let inserted = set.insert(-0.0) && !set.insert(0.0);
+
+                let count = 0;
+
+                for value in set {
+                    count += 1;
+                }
+
+                (count, inserted, set.contains(1.5), set.len())
+            }
+            "#
+        },
+        (3, true, true, 3),
+    };
+}
+
+#[test]
+fn test_btree_map_and_vec_deque() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            use std::collections::BTreeMap;
+            use std::collections::VecDeque;
+
+            fn main() {
+                let map = BTreeMap::new();
+                map.insert(3, 30);
+                map.insert(1, 10);
+                map.insert(2, 20);
+
+                let keys = [];
+
+                for key in map.keys() {
+                    keys.push(key);
+                }
+
+                let deque = VecDeque::new();
+                deque.push_back(2);
+                deque.push_front(1);
+                deque.push_back(3);
+                deque.pop_back();
+
+                let values = [];
+
+                for value in deque {
+                    values.push(value);
+                }
+
+                (keys, values)
+            }
+            "#
+        },
+        (vec![1, 2, 3], vec![1, 2]),
+    };
+}
+
+#[test]
+fn test_collections_as_iterators() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<(i64, i64)>, Vec<i64>) => r#"
+            use std::collections::BTreeMap;
+            use std::collections::HashSet;
+            use std::collections::VecDeque;
+
+            fn main() {
+                let deque = VecDeque::new();
+                deque.push_back(1);
+                deque.push_back(2);
+
+                let map = BTreeMap::new();
+                map.insert(1, 10);
+                map.insert(2, 20);
+
+                let set = HashSet::new();
+                set.insert(3);
+
+                let chained = [0].iter().chain(deque).chain(set).collect();
+                let zipped = deque.iter().zip(map).map(|e| e.1).collect();
+
+                let extended = [];
+                extended.extend(deque);
+                extended.extend(set);
+
+                (chained, zipped, extended)
+            }
+            "#
+        },
+        (vec![0, 1, 2, 3], vec![(1, 10), (2, 20)], vec![1, 2, 3]),
+    };
+}
+
+#[test]
+fn test_unsupported_keys() {
+    assert_vm_error!(
+        r#"
+        use std::collections::HashMap;
+
+        fn main() {
+            let map = HashMap::new();
+            map.insert(#{}, 1);
+        }
+        "#,
+        BadArgument { error, .. } => {
+            assert!(matches!(error.kind(), UnsupportedKey { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        use std::collections::HashSet;
+
+        fn main() {
+            let set = HashSet::new();
+            set.insert((1, 0.0 / 0.0));
+        }
+        "#,
+        BadArgument { error, .. } => {
+            assert!(matches!(error.kind(), NanKey));
+        }
+    );
+}
@@ -0,0 +1,67 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Source, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn check(n) {
+    if n > 10 {
+        "big"
+    } else {
+        "small"
+    }
+}
+
+fn unused() {
+    1
+}
+
+fn main() {
+    check(1);
+    check(2)
+}
+"#;
+
+#[test]
+fn test_coverage() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let unit = Arc::new(unit);
+
+    let mut vm = Vm::new(Arc::new(context), unit.clone());
+    let coverage = vm.enable_coverage();
+    let value = vm.execute(&["main"], ())?.complete()?;
+    assert_eq!(String::from_value(value)?, "small");
+
+    let source = Source::new("main", SOURCE);
+    let reports = coverage.report(&unit, vec![(0, &source)]);
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+
+    // `"big"` is never reached, while `"small"` is reached twice.
+    assert_eq!(report.lines.get(&3), Some(&0));
+    assert_eq!(report.lines.get(&5), Some(&2));
+    assert_eq!(report.lines.get(&10), Some(&0));
+
+    assert_eq!(report.branches.len(), 1);
+    let branch = &report.branches[0];
+    assert_eq!(branch.line, 2);
+    assert!(branch.executed);
+    assert_eq!(branch.jumped + branch.not_jumped, 2);
+    assert!(branch.jumped == 0 || branch.not_jumped == 0);
+
+    let functions = report
+        .functions
+        .iter()
+        .map(|f| (f.name.as_str(), f.hits))
+        .collect::<Vec<_>>();
+    assert_eq!(functions, vec![("check", 2), ("unused", 0), ("main", 1)]);
+
+    let mut lcov = Vec::new();
+    coverage.write_lcov(&mut lcov, &unit, vec![(0, &source)])?;
+    let lcov = String::from_utf8(lcov)?;
+    assert!(lcov.contains("SF:main\n"));
+    assert!(lcov.contains("FNDA:0,unused\n"));
+    assert!(lcov.contains("DA:4,0\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+    Ok(())
+}
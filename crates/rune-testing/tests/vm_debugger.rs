@@ -0,0 +1,179 @@
+use rune_testing::*;
+use runestick::debugger::{Breakpoint, DebugStop, Step};
+use runestick::{Context, Debugger, Item, Source, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn add(a, b) {
+    let c = a + b;
+    c
+}
+
+fn main() {
+    let x = 1;
+    let y = add(x, 2);
+    y * 2
+}
+"#;
+
+const SHADOWING: &str = r#"
+fn main() {
+    let x = 1;
+    let y = x + 1;
+    let x = y * 2;
+    x
+}
+"#;
+
+const LOOP: &str = r#"
+fn f(n) {
+    while n > 0 {
+        n -= 1;
+    }
+
+    n
+}
+
+fn main() {
+    f(3) + f(2)
+}
+"#;
+
+fn debugger() -> Result<(Debugger, runestick::VmExecution)> {
+    debugger_for(SOURCE)
+}
+
+fn debugger_for(source: &str) -> Result<(Debugger, runestick::VmExecution)> {
+    let context = Context::with_default_modules()?;
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    let mut debugger = Debugger::new();
+    debugger.insert_source(0, &Source::new("main", source));
+    let execution = vm.execute(&["main"], ())?;
+    Ok((debugger, execution))
+}
+
+fn line(debugger: &Debugger, execution: &runestick::VmExecution) -> Option<usize> {
+    Some(debugger.location(execution.vm().ok()?)?.line)
+}
+
+#[test]
+fn test_debugger_function_breakpoint() -> Result<()> {
+    let (mut debugger, mut execution) = debugger()?;
+    let id = debugger.add_breakpoint(Breakpoint::function(&Item::of(&["add"])));
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Continue)?,
+        DebugStop::Breakpoint { id: hit } if hit == id
+    ));
+
+    assert_eq!(line(&debugger, &execution), Some(2));
+
+    let locals = Debugger::locals(execution.vm()?);
+    let names = locals.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["a", "b"]);
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Over)?,
+        DebugStop::Step
+    ));
+
+    assert_eq!(line(&debugger, &execution), Some(3));
+
+    let c = Debugger::local(execution.vm()?, "c").expect("local `c`");
+    assert!(matches!(c, Value::Integer(3)));
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Continue)?,
+        DebugStop::Complete {
+            value: Value::Integer(6)
+        }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_debugger_function_breakpoint_loop() -> Result<()> {
+    let (mut debugger, mut execution) = debugger_for(LOOP)?;
+    let id = debugger.add_breakpoint(Breakpoint::function(&Item::of(&["f"])));
+
+    // NB: the loop jumps back to the start of `f`, which isn't a new call.
+    for _ in 0..2 {
+        assert!(matches!(
+            debugger.resume(&mut execution, Step::Continue)?,
+            DebugStop::Breakpoint { id: hit } if hit == id
+        ));
+    }
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Continue)?,
+        DebugStop::Complete {
+            value: Value::Integer(0)
+        }
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_debugger_stepping() -> Result<()> {
+    let (mut debugger, mut execution) = debugger()?;
+    debugger.add_breakpoint(Breakpoint::line(0, 8));
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Continue)?,
+        DebugStop::Breakpoint { .. }
+    ));
+
+    assert_eq!(line(&debugger, &execution), Some(8));
+    assert!(Debugger::local(execution.vm()?, "x").is_some());
+    assert!(Debugger::local(execution.vm()?, "y").is_none());
+
+    debugger.resume(&mut execution, Step::Into)?;
+    assert_eq!(line(&debugger, &execution), Some(2));
+
+    debugger.resume(&mut execution, Step::Out)?;
+    assert_eq!(line(&debugger, &execution), Some(9));
+
+    let y = Debugger::local(execution.vm()?, "y").expect("local `y`");
+    assert!(matches!(y, Value::Integer(3)));
+    Ok(())
+}
+
+#[test]
+fn test_debugger_let_bindings() -> Result<()> {
+    let (mut debugger, mut execution) = debugger_for(SHADOWING)?;
+    debugger.add_breakpoint(Breakpoint::line(0, 3));
+
+    assert!(matches!(
+        debugger.resume(&mut execution, Step::Continue)?,
+        DebugStop::Breakpoint { .. }
+    ));
+
+    // NB: `y` isn't live until its initializer has been evaluated.
+    assert_eq!(line(&debugger, &execution), Some(3));
+    assert!(Debugger::local(execution.vm()?, "y").is_none());
+
+    let x = Debugger::local(execution.vm()?, "x").expect("local `x`");
+    assert!(matches!(x, Value::Integer(1)));
+
+    debugger.resume(&mut execution, Step::Over)?;
+    assert_eq!(line(&debugger, &execution), Some(4));
+
+    // NB: the second `x` doesn't shadow the first one until it's declared.
+    let x = Debugger::local(execution.vm()?, "x").expect("local `x`");
+    assert!(matches!(x, Value::Integer(1)));
+    let y = Debugger::local(execution.vm()?, "y").expect("local `y`");
+    assert!(matches!(y, Value::Integer(2)));
+
+    debugger.resume(&mut execution, Step::Over)?;
+    assert_eq!(line(&debugger, &execution), Some(5));
+
+    let locals = Debugger::locals(execution.vm()?);
+    let names = locals.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["x", "y"]);
+    assert!(matches!(locals[0].1, Value::Integer(4)));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use rune_testing::*;
+use runestick::{Context, Heap, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+struct Node { value, prev, next }
+
+fn list(n) {
+    let first = Node { value: 0, prev: None, next: None };
+    let last = first;
+
+    let value = 1;
+
+    while value < n {
+        let node = Node { value, prev: Some(last), next: None };
+        last.next = Some(node);
+        last = node;
+        value += 1;
+    }
+
+    first
+}
+
+fn main(n) {
+    let first = list(n);
+    let object = #{};
+    object.get = || object;
+    first.value
+}
+"#;
+
+fn vm(context: &Arc<Context>, heap: &Heap) -> Result<Vm> {
+    let (unit, _) = compile_source(context, SOURCE)?;
+    let mut vm = Vm::new(context.clone(), Arc::new(unit));
+    vm.set_heap(Some(heap.clone()));
+    Ok(vm)
+}
+
+#[test]
+fn test_collect_cycles() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let heap = Heap::new();
+
+    let output = vm(&context, &heap)?.call(&["main"], (4,))?;
+    assert!(matches!(output, Value::Integer(0)));
+
+    // NB: the nodes, the object and the closure.
+    assert_eq!(heap.tracked(), 6);
+    // NB: also includes the untracked options linking the nodes together, and
+    // the environment of the closure.
+    assert_eq!(heap.collect(), 15);
+    assert_eq!(heap.tracked(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_collect_reachable_from_output() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let heap = Heap::new();
+
+    let output = vm(&context, &heap)?.call(&["list"], (100,))?;
+
+    // NB: the list is reachable from the output, so nothing was collected.
+    assert_eq!(heap.tracked(), 100);
+    assert_eq!(heap.collect(), 0);
+    drop(output);
+    assert!(heap.collect() >= 100);
+    assert_eq!(heap.tracked(), 0);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use rune_testing::*;
+
+#[test]
+fn test_polymorphic_instance_fn() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            struct Foo { value }
+            struct Bar { value }
+
+            impl Foo { fn get(self) { self.value } }
+            impl Bar { fn get(self) { self.value * 10 } }
+
+            fn main() {
+                let values = [Foo { value: 1 }, Bar { value: 2 }, Foo { value: 3 }];
+                let sum = 0;
+
+                for value in values {
+                    sum = sum + value.get();
+                }
+
+                sum
+            }
+            "#
+        },
+        24,
+    };
+}
+
+#[test]
+fn test_polymorphic_native_instance_fn() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let values = [[1, 2], "abc", [1], #{a: 1, b: 2, c: 3, d: 4}];
+                let sum = 0;
+
+                for value in values {
+                    sum = sum + value.len();
+                }
+
+                sum
+            }
+            "#
+        },
+        10,
+    };
+}
+
+#[test]
+fn test_missing_instance_fn_after_cached() {
+    assert_vm_error!(
+        r#"
+        struct Foo { value }
+        struct Bar { value }
+
+        impl Foo { fn get(self) { self.value } }
+
+        fn main() {
+            for value in [Foo { value: 1 }, Bar { value: 2 }] {
+                value.get();
+            }
+        }
+        "#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
@@ -0,0 +1,163 @@
+use rune_testing::*;
+
+#[test]
+fn test_iter_adapters() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            fn main() {
+                [1, 2, 3, 4, 5, 6]
+                    .iter()
+                    .map(|n| n * 10)
+                    .filter(|n| n != 30)
+                    .skip(1)
+                    .take(3)
+                    .collect()
+            }
+            "#
+        },
+        vec![20, 40, 50],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<(i64, char)> => r#"
+            use std::iter::range;
+
+            fn main() {
+                range(0, 10)
+                    .filter_map(|n| if n % 3 == 0 { Some(n) } else { None })
+                    .rev()
+                    .zip("abc")
+                    .collect()
+            }
+            "#
+        },
+        vec![(9, 'a'), (6, 'b'), (3, 'c')],
+    };
+
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            use std::iter::range;
+
+            fn main() {
+                [1, 2]
+                    .iter()
+                    .chain(range(3, 100))
+                    .take_while(|n| n < 6)
+                    .flat_map(|n| [n, n])
+                    .enumerate()
+                    .map(|e| e.0 * e.1)
+                    .collect()
+            }
+            "#
+        },
+        vec![0, 1, 4, 6, 12, 15, 24, 28, 40, 45],
+    };
+}
+
+#[test]
+fn test_iter_reuse_closure() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, i64, bool) => r#"
+            fn main() {
+                let f = |n| n * 2;
+                let even = |n| n % 2 == 0;
+
+                let a = [1, 2].iter().map(f).collect();
+                let b = [3, 4].iter().map(f).filter(even).collect();
+                let c = [1, 2, 3].iter().filter(even).fold(0, |acc, n| acc + f(n));
+                let d = [2, 4].iter().all(even);
+
+                (a, b, c, d)
+            }
+            "#
+        },
+        (vec![2, 4], vec![6, 8], 4, true),
+    };
+}
+
+#[test]
+fn test_iter_consumers() {
+    assert_eq! {
+        rune! {
+            (i64, f64, i64, bool, bool, Option<i64>, Option<i64>, Option<String>, String) => r#"
+            use std::iter::iter;
+
+            fn main() {
+                let names = ["b", "c", "a"];
+
+                (
+                    [1, 2, 3].iter().sum(),
+                    iter((1.5, 2.5)).sum(),
+                    iter("hello").count(),
+                    [1, 2, 3].iter().any(|n| n == 2),
+                    [1, 2, 3].iter().all(|n| n < 3),
+                    [1, 2, 3].iter().find(|n| n > 1),
+                    [3, 1, 2].iter().min(),
+                    names.iter().max(),
+                    ['a', 'b'].iter().chain(["cd"]).collect_string(),
+                )
+            }
+            "#
+        },
+        (6, 4.0, 5, true, false, Some(2), Some(1), Some(String::from("c")), String::from("abcd")),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, i64, Option<i64>, Option<i64>, i64) => r#"
+            use std::iter::from_fn;
+
+            fn main() {
+                let object = #{a: 1, b: 2}.iter().map(|e| (e.0, e.1 * 10)).collect_object();
+
+                let state = #{n: 0};
+
+                let counter = from_fn(|| {
+                    state.n = state.n + 1;
+                    if state.n <= 3 { Some(state.n) } else { None }
+                });
+
+                let it = counter.peekable();
+                let peeked = it.peek();
+                let first = it.next();
+
+                let folded = [1, 2, 3].iter().fold(0, |acc, n| acc * 10 + n);
+
+                (object.a + object.b, it.count(), peeked, first, folded)
+            }
+            "#
+        },
+        (30, 2, Some(1), Some(1), 123),
+    };
+}
+
+#[test]
+fn test_iter_errors() {
+    assert_vm_error!(
+        r#"
+        fn main() {
+            [1, 2].iter().chain(42)
+        }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), UnsupportedIterator { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        use std::iter::from_fn;
+
+        fn main() {
+            from_fn(|| None).rev().next()
+        }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), NotDoubleEnded));
+        }
+    );
+}
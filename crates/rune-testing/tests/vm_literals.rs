@@ -38,3 +38,24 @@ fn test_octal() {
         -63,
     };
 }
+
+#[test]
+fn test_float() {
+    assert_eq! {
+        rune!(f64 => r#"fn main() { 2.5 }"#),
+        2.5,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"fn main() { -2.5 }"#),
+        -2.5,
+    };
+}
+
+#[test]
+fn test_method_on_integer_literal() {
+    assert_eq! {
+        rune!((f64, f64) => r#"fn main() { (1.to_float(), 1.5.floor()) }"#),
+        (1.0, 1.0),
+    };
+}
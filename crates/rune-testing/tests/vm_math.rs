@@ -0,0 +1,105 @@
+use rune_testing::*;
+
+#[test]
+fn test_math_constants() {
+    assert_eq! {
+        rune! {
+            (f64, f64, bool, bool, bool) => r#"
+            use std::math;
+
+            fn main() {
+                (
+                    math::PI,
+                    std::math::E,
+                    math::NAN.is_nan(),
+                    math::INFINITY > 1000000.0,
+                    math::NEG_INFINITY.is_infinite(),
+                )
+            }
+            "#
+        },
+        (std::f64::consts::PI, std::f64::consts::E, true, true, true),
+    };
+}
+
+#[test]
+fn test_float_methods() {
+    assert_eq! {
+        rune! {
+            (f64, f64, f64, f64, f64, f64, f64, f64, f64, bool) => r#"
+            fn main() {
+                (
+                    16.0.sqrt(),
+                    2.0.powf(0.5) * 2.0.powf(0.5),
+                    2.0.powi(10),
+                    1.0.exp().ln(),
+                    1000.0.log10(),
+                    (-2.5).floor() + 2.5.ceil(),
+                    2.5.round() + (-2.7).trunc() + (-1.5).abs(),
+                    0.0.cos() + 0.0.sin(),
+                    3.0.min(1.0).max(2.0).clamp(0.0, 1.5),
+                    float::from_bits(1.5.to_bits()) == 1.5,
+                )
+            }
+            "#
+        },
+        (4.0, 2.0000000000000004, 1024.0, 1.0, 3.0, 0.0, 2.5, 1.0, 1.5, true),
+    };
+}
+
+#[test]
+fn test_int_methods() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, i64, i64, u32, u32, String, String, i64) => r#"
+            fn unwrap(result) {
+                match result {
+                    Ok(value) => value,
+                    Err(_) => panic("failed to parse"),
+                }
+            }
+
+            fn main() {
+                (
+                    (-5).abs(),
+                    (3).min(7) + (3).max(7),
+                    (42).clamp(0, 10),
+                    (-7).signum(),
+                    (-7).rem_euclid(3),
+                    -7 % 3,
+                    (11).count_ones(),
+                    (1).leading_zeros(),
+                    (255).to_string_radix(16),
+                    (-10).to_string_radix(2),
+                    unwrap(int::parse_radix("-ff", 16)),
+                )
+            }
+            "#
+        },
+        (5, 10, 10, -1, 2, -1, 3, 63, String::from("ff"), String::from("-1010"), -255),
+    };
+}
+
+#[test]
+fn test_math_errors() {
+    assert_vm_error!(
+        r#"fn main() { (-9223372036854775807 - 1).abs() }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), Overflow));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { (1).rem_euclid(0) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), DivideByZero));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { (1).to_string_radix(37) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix must be in `2..=36`, but got `37`");
+        }
+    );
+}
@@ -29,3 +29,80 @@ fn test_option() {
         1,
     };
 }
+
+#[test]
+fn test_option_combinators() {
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>, Option<i64>, Option<i64>, i64) => r#"
+            fn main() {
+                let some = Some(2);
+
+                (
+                    some.map(|n| n * 10),
+                    some.and_then(|n| if n > 5 { Some(n) } else { None }),
+                    None.or_else(|| Some(3)),
+                    some.filter(|n| n % 2 == 0),
+                    Some(3).filter(|n| n % 2 == 0),
+                    some.unwrap() + some.expect("value"),
+                )
+            }
+            "#
+        },
+        (Some(20), None, Some(3), Some(2), None, 4),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, bool, Vec<i64>, i64) => r#"
+            fn main() {
+                let option = Some(1);
+                let taken = option.take();
+                let sum = 0;
+
+                for n in Some(5) {
+                    sum = sum + n;
+                }
+
+                for n in None {
+                    sum = sum + n;
+                }
+
+                (taken, option, None.ok_or("missing").is_err(), Some(7).iter().collect(), sum)
+            }
+            "#
+        },
+        (Some(1), None, true, vec![7], 5),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let f = |n| n * 2;
+                let even = |n| n % 2 == 0;
+
+                (Some(5).map(f), Some(6).map(f), Some(4).filter(even), Some(3).filter(even))
+            }
+            "#
+        },
+        (Some(10), Some(12), Some(4), None),
+    };
+}
+
+#[test]
+fn test_option_errors() {
+    assert_vm_error!(
+        r#"fn main() { None.expect("expected a value") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "expected a value");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { Some(1).map(|n| panic("in closure")) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "in closure");
+        }
+    );
+}
@@ -0,0 +1,90 @@
+use rune_testing::*;
+
+#[test]
+fn test_add_immediate() {
+    assert_eq! {
+        rune! {
+            (i64, f64, String) => r#"
+            fn main() {
+                let a = 41;
+                let b = 1.5;
+                let c = "foo";
+                (a + 1, b + 1.0, c + "bar")
+            }
+            "#
+        },
+        (42, 2.5, String::from("foobar")),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = 9223372036854775807;
+            a + 1
+        }
+        "#,
+        Overflow => {}
+    );
+}
+
+#[test]
+fn test_jump_if_not_lt() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            fn main() {
+                let n = 10;
+                let i = 0;
+
+                while i < n {
+                    i = i + 1;
+                }
+
+                let a = 1.5;
+                let b = 2.5;
+                let floats = if a < b { 1 } else { 0 };
+                (i, floats)
+            }
+            "#
+        },
+        (10, 1),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = 1;
+            let b = "foo";
+
+            if a < b {
+                1
+            }
+        }
+        "#,
+        UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(*op, "<");
+        }
+    );
+}
+
+#[test]
+fn test_pop_and_jump_if_not_integer() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn test(value) {
+                match value {
+                    1 => 10,
+                    2 => 20,
+                    _ => 30,
+                }
+            }
+
+            fn main() {
+                test(1) + test(2) + test(3) + test("1")
+            }
+            "#
+        },
+        90,
+    };
+}
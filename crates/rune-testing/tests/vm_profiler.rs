@@ -0,0 +1,99 @@
+use rune_testing::*;
+use runestick::{Context, Profiler, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn fib(n) {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1) + fib(n - 2)
+    }
+}
+
+fn main() {
+    let s = "hello";
+    fib(5) + s.len()
+}
+"#;
+
+#[test]
+fn test_profiler() -> Result<()> {
+    let context = Context::with_default_modules()?;
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    let mut profiler = Profiler::new();
+    let mut execution = vm.execute(&["main"], ())?;
+    let value = profiler.run(&mut execution)?;
+    assert!(matches!(value, Value::Integer(10)));
+
+    let functions = profiler.functions();
+
+    let fib = functions.iter().find(|f| f.name == "fib").expect("fib");
+    assert!(!fib.native);
+    assert_eq!(fib.calls, 15);
+    assert!(fib.instructions > 0);
+
+    let main = functions.iter().find(|f| f.name == "main").expect("main");
+    assert_eq!(main.calls, 1);
+    assert!(main.total_time >= fib.total_time);
+
+    let len = functions
+        .iter()
+        .find(|f| f.name == "std::string::String::len")
+        .expect("String::len");
+    assert!(len.native);
+    assert_eq!(len.calls, 1);
+
+    let mut folded = Vec::new();
+    profiler.write_folded(&mut folded)?;
+    let folded = String::from_utf8(folded)?;
+
+    let stacks = folded
+        .lines()
+        .filter_map(|line| line.rsplit_once(' ').map(|(stack, _)| stack))
+        .collect::<Vec<_>>();
+
+    assert!(stacks.contains(&"main"));
+    assert!(stacks.contains(&"main;fib;fib;fib;fib"));
+    assert!(stacks.contains(&"main;std::string::String::len"));
+    Ok(())
+}
+
+#[test]
+fn test_profiler_nested_start() -> Result<()> {
+    let source = r#"
+    fn inner() { 1 }
+    fn outer() { inner() + 1 }
+    fn main() { outer() + 1 }
+    "#;
+
+    let context = Context::with_default_modules()?;
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let mut execution = vm.execute(&["main"], ())?;
+
+    while execution.call_depth() < 3 {
+        assert!(execution.step()?.is_none());
+    }
+
+    let mut profiler = Profiler::new();
+    let value = profiler.run(&mut execution)?;
+    assert!(matches!(value, Value::Integer(3)));
+
+    let functions = profiler.functions();
+
+    for name in &["main", "outer", "inner"] {
+        let f = functions.iter().find(|f| f.name == *name).expect(name);
+        assert_eq!(f.calls, 1);
+    }
+
+    let mut folded = Vec::new();
+    profiler.write_folded(&mut folded)?;
+    let folded = String::from_utf8(folded)?;
+    assert!(folded
+        .lines()
+        .any(|line| line.starts_with("main;outer;inner ")));
+    Ok(())
+}
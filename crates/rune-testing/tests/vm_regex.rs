@@ -0,0 +1,130 @@
+use rune_testing::*;
+use runestick::{Context, FromValue, Vm};
+use std::sync::Arc;
+
+/// Run the given program with the `regex` module installed.
+fn run_regex<T>(source: &str) -> Result<T>
+where
+    T: FromValue,
+{
+    let mut context = Context::with_default_modules()?;
+    context.install(&rune_modules::regex::module()?)?;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    Ok(T::from_value(vm.call(&["main"], ())?)?)
+}
+
+#[test]
+fn test_regex_matching() -> Result<()> {
+    let output: (bool, bool, (i64, i64, String), Vec<String>, Vec<String>) = run_regex(
+        r#"
+        use regex::Regex;
+
+        fn main() {
+            let re = Regex::new("[0-9]+").unwrap();
+            let m = re.find("abc 123 def 45").unwrap();
+
+            (
+                re.is_match("a1"),
+                re.is_match("abc"),
+                (m.start(), m.end(), m.as_str()),
+                re.find_iter("1, 22 and 333").map(|m| m.as_str()).collect(),
+                Regex::new(", *").unwrap().split("a, b,c"),
+            )
+        }
+        "#,
+    )?;
+
+    assert_eq!(output.0, true);
+    assert_eq!(output.1, false);
+    assert_eq!(output.2, (4, 7, String::from("123")));
+    assert_eq!(output.3, vec!["1", "22", "333"]);
+    assert_eq!(output.4, vec!["a", "b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn test_regex_captures() -> Result<()> {
+    let output: (
+        Option<Vec<Option<String>>>,
+        Option<String>,
+        Option<String>,
+        bool,
+    ) = run_regex(
+        r#"
+        use regex::Regex;
+
+        fn main() {
+            let re = Regex::new("(?P<level>[A-Z]+)(?P<code>-[0-9]+)?: .*").unwrap();
+            let named = re.captures_named("WARN: low memory").unwrap();
+
+            (
+                re.captures("ERROR-2: disk full"),
+                named.level,
+                named.code,
+                re.captures("nothing to see").is_none(),
+            )
+        }
+        "#,
+    )?;
+
+    let groups = vec![
+        Some(String::from("ERROR-2: disk full")),
+        Some(String::from("ERROR")),
+        Some(String::from("-2")),
+    ];
+
+    assert_eq!(output.0, Some(groups));
+    assert_eq!(output.1, Some(String::from("WARN")));
+    assert_eq!(output.2, None);
+    assert_eq!(output.3, true);
+    Ok(())
+}
+
+#[test]
+fn test_regex_replace() -> Result<()> {
+    let output: (String, String, String, String) = run_regex(
+        r#"
+        use regex::Regex;
+
+        fn main() {
+            let re = Regex::new("([a-z])=([0-9])").unwrap();
+            let swap = |groups| `{groups[2].unwrap()}={groups[1].unwrap()}`;
+
+            (
+                re.replace("a=1 b=2", "$2"),
+                re.replace_all("a=1 b=2", "$1"),
+                re.replace_all("a=1 b=2", swap),
+                re.replace_all("c=3", swap),
+            )
+        }
+        "#,
+    )?;
+
+    assert_eq!(output.0, "1 b=2");
+    assert_eq!(output.1, "a b");
+    assert_eq!(output.2, "1=a 2=b");
+    assert_eq!(output.3, "3=c");
+    Ok(())
+}
+
+#[test]
+fn test_regex_errors() -> Result<()> {
+    let output: (bool, String) = run_regex(
+        r#"
+        use regex::Regex;
+
+        fn main() {
+            match Regex::new("(unclosed") {
+                Ok(_) => (false, ""),
+                Err(error) => (true, `{error}`),
+            }
+        }
+        "#,
+    )?;
+
+    assert!(output.0);
+    assert!(output.1.contains("unclosed group"));
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Function, Item, Reload, ReloadError, Vm};
+use std::sync::Arc;
+
+const OLD: &str = r#"
+fn add(n) { n + 1 }
+fn removed(n) { n }
+fn main() {
+    let base = 10;
+    let closure = |n| n + base;
+    [add, closure, removed]
+}
+"#;
+
+const NEW: &str = r#"
+fn add(n) { n + 2 }
+fn main() {
+    let base = 10;
+    let closure = |n| n * base;
+    [add, closure]
+}
+"#;
+
+fn call(value: &Value, n: i64) -> Result<i64> {
+    let function = value.clone().into_function()?;
+    let function = function.borrow_ref()?;
+    Ok(function.call::<_, i64>((n,))?)
+}
+
+#[test]
+fn test_reload_functions() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (old, _) = compile_source(&context, OLD)?;
+    let old = Arc::new(old);
+
+    let vm = Vm::new(context.clone(), old.clone());
+    let output = vm.call(&["main"], ())?;
+    let functions = output.clone().into_vec()?.borrow_ref()?.clone();
+
+    assert_eq!(call(&functions[0], 1)?, 2);
+    assert_eq!(call(&functions[1], 2)?, 12);
+    assert_eq!(call(&functions[2], 3)?, 3);
+
+    let (new, _) = compile_source(&context, NEW)?;
+    let new = Arc::new(new);
+
+    let mut reload = Reload::new(old, new);
+    reload.value(&output)?;
+
+    assert_eq!(reload.remapped(), 2);
+    assert_eq!(reload.orphans().len(), 1);
+    assert_eq!(reload.orphans()[0].item, Some(Item::of(&["removed"])));
+
+    assert_eq!(call(&functions[0], 1)?, 3);
+    assert_eq!(call(&functions[1], 2)?, 20);
+    // NB: orphaned functions keep running in the old unit.
+    assert_eq!(call(&functions[2], 3)?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_reload_vm() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (old, _) = compile_source(&context, OLD)?;
+    let old = Arc::new(old);
+    let (new, _) = compile_source(&context, NEW)?;
+    let new = Arc::new(new);
+
+    let mut vm = Vm::new(context, old.clone());
+    let mut reload = Reload::new(old, new.clone());
+    reload.vm(&mut vm)?;
+    assert!(Arc::ptr_eq(vm.unit(), &new));
+
+    let output = vm.call(&["add"], (1,))?;
+    assert_eq!(i64::from_value(output)?, 3);
+    Ok(())
+}
+
+#[test]
+fn test_reload_foreign_function() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (old, _) = compile_source(&context, OLD)?;
+    let (other, _) = compile_source(&context, OLD)?;
+    let (new, _) = compile_source(&context, NEW)?;
+
+    let vm = Vm::new(context.clone(), Arc::new(old));
+    let functions = Vec::<Function>::from_value(vm.call(&["main"], ())?)?;
+    let mut function = functions.into_iter().next().unwrap();
+
+    // NB: the function belongs to neither unit, so it's left alone.
+    let mut reload = Reload::new(Arc::new(other), Arc::new(new));
+    reload.function(&mut function)?;
+    assert_eq!(reload.remapped(), 0);
+    assert!(reload.orphans().is_empty());
+    assert_eq!(function.call::<_, i64>((1,))?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_reload_vm_not_at_function() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (old, _) = compile_source(&context, OLD)?;
+    let old = Arc::new(old);
+    let (new, _) = compile_source(&context, NEW)?;
+
+    let mut vm = Vm::new(context, old.clone());
+    vm.set_ip(1);
+
+    let mut reload = Reload::new(old.clone(), Arc::new(new));
+    let error = reload.vm(&mut vm).unwrap_err();
+    assert!(matches!(error, ReloadError::NotAtFunction { ip: 1 }));
+    assert!(Arc::ptr_eq(vm.unit(), &old));
+    Ok(())
+}
+
+#[test]
+fn test_reload_vm_missing_function() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (old, _) = compile_source(&context, OLD)?;
+    let old = Arc::new(old);
+    let (new, _) = compile_source(&context, NEW)?;
+
+    let vm = Vm::new(context, old.clone());
+    let mut execution = vm.execute(&["removed"], (1,))?;
+    let vm = execution.vm_mut()?;
+
+    let mut reload = Reload::new(old.clone(), Arc::new(new));
+    let error = reload.vm(vm).unwrap_err();
+    assert!(matches!(error, ReloadError::MissingFunction { .. }));
+    assert!(Arc::ptr_eq(vm.unit(), &old));
+    assert_eq!(reload.orphans()[0].item, Some(Item::of(&["removed"])));
+    Ok(())
+}
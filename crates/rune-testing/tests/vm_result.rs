@@ -29,3 +29,76 @@ fn test_result() {
         1,
     };
 }
+
+#[test]
+fn test_result_combinators() {
+    assert_eq! {
+        rune! {
+            (Result<i64, i64>, Result<i64, i64>, Result<i64, i64>, Result<i64, i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let ok = Ok(2);
+                let err = Err(1);
+
+                (
+                    ok.map(|n| n * 10),
+                    err.map_err(|e| e + 100),
+                    ok.and_then(|n| Err(n)),
+                    err.or_else(|e| Ok(e + 1)),
+                    ok.ok(),
+                    ok.err(),
+                )
+            }
+            "#
+        },
+        (Ok(20), Err(101), Err(2), Ok(2), Some(2), None),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, Vec<i64>, bool) => r#"
+            fn main() {
+                let err = Err("bad");
+
+                (
+                    Ok(1).unwrap() + Ok(1).expect("value"),
+                    err.unwrap_or(3),
+                    err.unwrap_or_else(|e| 4),
+                    Ok(5).iter().collect(),
+                    err.unwrap_or_default() is unit,
+                )
+            }
+            "#
+        },
+        (2, 3, 4, vec![5], true),
+    };
+
+    assert_eq! {
+        rune! {
+            (Result<i64, i64>, Result<i64, i64>, Result<i64, i64>) => r#"
+            fn main() {
+                let f = |n| n * 2;
+
+                (Ok(5).map(f), Ok(6).map(f), Err(7).map_err(f))
+            }
+            "#
+        },
+        (Ok(10), Ok(12), Err(14)),
+    };
+}
+
+#[test]
+fn test_result_errors() {
+    assert_vm_error!(
+        r#"fn main() { Err("bad").expect("expected a value") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "expected a value: \"bad\"");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { Ok(1).and_then(|n| n) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), Expected { .. }));
+        }
+    );
+}
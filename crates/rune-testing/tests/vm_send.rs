@@ -0,0 +1,116 @@
+use rune_testing::*;
+use runestick::{Context, Function, Vm, VmError, VmExecution};
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn test_send_types() {
+    assert_send::<Value>();
+    assert_sync::<Value>();
+    assert_send::<Vm>();
+    assert_send::<VmExecution>();
+    assert_send::<Function>();
+    assert_sync::<Function>();
+}
+
+#[test]
+fn test_vm_across_threads() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main(values) {
+            let out = 0;
+
+            for v in values {
+                out = out + v;
+            }
+
+            out
+        }
+        "#,
+    )?;
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let values = vec![1i64, 2, 3];
+
+    let value = thread::spawn(move || {
+        let mut execution = vm.execute(&["main"], (values,))?;
+        execution.complete()
+    })
+    .join()
+    .expect("thread panicked")?;
+
+    assert!(matches!(value, Value::Integer(6)));
+    Ok(())
+}
+
+#[test]
+fn test_shared_value_across_threads() -> Result<()> {
+    let value = Value::vec(vec![Value::Integer(1), Value::Integer(2)]);
+    let other = value.clone();
+
+    thread::spawn(move || {
+        other.into_vec()?.borrow_mut()?.push(Value::Integer(3));
+        Ok::<_, VmError>(())
+    })
+    .join()
+    .expect("thread panicked")?;
+
+    let vec = value.into_vec()?;
+    let vec = vec.borrow_ref()?;
+    assert_eq!(vec.len(), 3);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_function_in_task() -> Result<()> {
+    let function = rune! {
+        Function => r#"
+        fn main() {
+            |a, b| a + b
+        }
+        "#
+    };
+
+    let value = tokio::spawn(async move { function.call::<_, i64>((1i64, 2i64)) })
+        .await
+        .expect("task panicked")?;
+
+    assert_eq!(value, 3);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_async_execution_in_task() -> Result<()> {
+    let context = Context::with_default_modules()?;
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        async fn add(a, b) {
+            a + b
+        }
+
+        async fn main() {
+            add(1, 2).await + add(3, 4).await
+        }
+        "#,
+    )?;
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    let value = tokio::spawn(async move {
+        let mut execution = vm.execute(&["main"], ())?;
+        execution.async_complete().await
+    })
+    .await
+    .expect("task panicked")?;
+
+    assert!(matches!(value, Value::Integer(10)));
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use rune_testing::*;
+use runestick::{
+    Context, FromValue as _, GeneratorState, SnapshotError, Vm, VmExecution, VmSnapshot,
+};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn counter() {
+    yield 1;
+    yield 2;
+}
+
+fn main() {
+    let values = [1, 2];
+    let alias = values;
+    let gen = counter();
+    gen.next();
+    let a = yield values.len();
+    alias.push(a);
+    let f = |n| n + a;
+    let b = yield 0;
+    (values, gen.next(), f(b))
+}
+"#;
+
+/// Round trip the snapshot through a serialization format.
+fn round_trip(snapshot: &VmSnapshot) -> Result<VmSnapshot> {
+    let json = serde_json::to_string(snapshot)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+#[test]
+fn test_snapshot_restore() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let vm = Vm::new(context.clone(), Arc::new(unit));
+
+    let mut execution = vm.execute(&["main"], ())?;
+    let state = execution.resume()?;
+    assert!(matches!(state, GeneratorState::Yielded(Value::Integer(2))));
+
+    let snapshot = round_trip(&execution.vm()?.snapshot()?)?;
+    drop(execution);
+
+    // NB: a unit compiled from the same source is the same unit.
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    assert_eq!(snapshot.unit_hash(), unit.content_hash());
+
+    let mut vm = Vm::restore(context.clone(), Arc::new(unit), &snapshot)?;
+    vm.stack_mut().push(Value::Integer(3));
+
+    let mut execution = VmExecution::new(vm);
+    let state = execution.resume()?;
+    assert!(matches!(state, GeneratorState::Yielded(Value::Integer(0))));
+
+    // Snapshot again, now with a closure and a suspended generator.
+    let snapshot = round_trip(&execution.vm()?.snapshot()?)?;
+    let unit = execution.vm()?.unit().clone();
+    drop(execution);
+
+    let mut vm = Vm::restore(context, unit, &snapshot)?;
+    vm.stack_mut().push(Value::Integer(4));
+
+    let mut execution = VmExecution::new(vm);
+    let value = execution.complete()?;
+
+    let (values, next, sum) = <(Vec<i64>, Option<i64>, i64)>::from_value(value)?;
+    assert_eq!(values, vec![1, 2, 3]);
+    assert_eq!(next, Some(2));
+    assert_eq!(sum, 7);
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_unsupported() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main() {
+            let future = async { 1 };
+            yield 0;
+            future
+        }
+        "#,
+    )?;
+
+    let vm = Vm::new(context, Arc::new(unit));
+    let mut execution = vm.execute(&["main"], ())?;
+    execution.resume()?;
+
+    let error = execution.vm()?.snapshot().unwrap_err();
+    assert!(matches!(error, SnapshotError::UnsupportedValue { .. }));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_unit_mismatch() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+    let (unit, _) = compile_source(&context, SOURCE)?;
+    let vm = Vm::new(context.clone(), Arc::new(unit));
+    let snapshot = vm.snapshot()?;
+
+    let (other, _) = compile_source(&context, "fn main() { 42 }")?;
+    let error = Vm::restore(context, Arc::new(other), &snapshot).unwrap_err();
+    assert!(matches!(error, SnapshotError::UnitMismatch { .. }));
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use rune_testing::*;
+
+#[test]
+fn test_string_searching() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, Option<i64>, Option<i64>, String) => r#"
+            fn main() {
+                let s = String::from_str("hello world");
+
+                (
+                    s.starts_with("hello"),
+                    "hello world".ends_with('d'),
+                    s.contains("o w"),
+                    "hello".contains('x'),
+                    s.find('o'),
+                    "hello".find("world"),
+                    "a-b-c".replace('-', "+"),
+                )
+            }
+            "#
+        },
+        (true, true, true, false, Some(4), None, String::from("a+b+c")),
+    };
+}
+
+#[test]
+fn test_string_splitting() {
+    assert_eq! {
+        rune! {
+            (Vec<String>, Vec<String>, Vec<String>, Vec<char>) => r#"
+            fn main() {
+                let csv = String::from_str("a,b,,c");
+
+                (
+                    csv.split(',').collect(),
+                    "key = value = more".splitn(2, " = ").collect(),
+                    "  first\nsecond  \n".lines().map(|line| line.trim()).collect(),
+                    "abc".chars().rev().collect(),
+                )
+            }
+            "#
+        },
+        (
+            vec!["a".into(), "b".into(), "".into(), "c".into()],
+            vec!["key".into(), "value = more".into()],
+            vec!["first".into(), "second".into()],
+            vec!['c', 'b', 'a'],
+        ),
+    };
+}
+
+#[test]
+fn test_string_transforms_and_parsing() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, String, i64, f64, char, bool) => r#"
+            fn unwrap(result) {
+                match result {
+                    Ok(value) => value,
+                    Err(_) => panic("failed to parse"),
+                }
+            }
+
+            fn main() {
+                (
+                    "  padded ".trim_start(),
+                    "  padded ".trim_end(),
+                    "Hello".to_uppercase(),
+                    String::from_str("Hello").to_lowercase(),
+                    "ab".repeat(3),
+                    unwrap("42".parse_int()),
+                    unwrap("1.5".parse_float()),
+                    unwrap("x".parse_char()),
+                    "xy".parse_char().is_err(),
+                )
+            }
+            "#
+        },
+        (
+            String::from("padded "),
+            String::from("  padded"),
+            String::from("HELLO"),
+            String::from("hello"),
+            String::from("ababab"),
+            42,
+            1.5,
+            'x',
+            true,
+        ),
+    };
+}
+
+#[test]
+fn test_char_methods() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, Option<u32>, Option<u32>, Option<char>, Option<char>, i64) => r#"
+            fn main() {
+                (
+                    'a'.is_alphabetic(),
+                    '7'.is_numeric(),
+                    ' '.is_alphanumeric(),
+                    'f'.to_digit(16),
+                    'z'.to_digit(10),
+                    char::from_u32(0x41),
+                    char::from_u32(0xd800),
+                    'A'.to_int(),
+                )
+            }
+            "#
+        },
+        (true, true, false, Some(15), None, Some('A'), None, 65),
+    };
+}
+
+#[test]
+fn test_bad_arguments() {
+    assert_vm_error!(
+        r#"fn main() { std::char::from_digit(1, 40) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix must be in `2..=36`, but got `40`");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 'a'.to_digit(40) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix must be in `2..=36`, but got `40`");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { "ab".repeat(4611686018427387904) }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), Overflow));
+        }
+    );
+}
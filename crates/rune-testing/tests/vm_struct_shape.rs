@@ -0,0 +1,95 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Object, TypedObject, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_struct_field_order() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Foo { a, b, c }
+
+            fn main() {
+                let order = [];
+                let foo = Foo { c: { order.push(3); 3 }, a: { order.push(1); 1 }, b: 2 };
+                let Foo { a, b, c } = foo;
+                (a * 100 + b * 10 + c, order[0], order[1])
+            }
+            "#
+        },
+        (123, 3, 1),
+    };
+}
+
+#[test]
+fn test_struct_field_assign() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            struct Foo { a, b }
+            enum Bar { Baz { a, b } }
+
+            fn main() {
+                let foo = Foo { b: 2, a: 1 };
+                foo.b = foo.a + foo.b;
+
+                let baz = Bar::Baz { a: 10, b: 20 };
+                baz.a = 30;
+
+                match baz {
+                    Bar::Baz { a, b } => foo.b + a + b,
+                }
+            }
+            "#
+        },
+        53,
+    };
+}
+
+#[test]
+fn test_struct_missing_field() {
+    assert_vm_error!(
+        r#"
+        struct Foo { a }
+
+        fn main() {
+            let foo = Foo { a: 1 };
+            foo.b = 2;
+        }
+        "#,
+        MissingField { field, .. } => {
+            assert_eq!(field, "b");
+        }
+    );
+}
+
+#[test]
+fn test_struct_from_host() -> Result<()> {
+    let context = Arc::new(Context::with_default_modules()?);
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        struct Foo { a, b }
+
+        fn main(foo) {
+            let Foo { a, b } = foo;
+            a - b
+        }
+        "#,
+    )?;
+
+    let mut object = Object::new();
+    object.insert(String::from("b"), Value::Integer(1));
+    object.insert(String::from("a"), Value::Integer(10));
+
+    let hash = runestick::Hash::type_hash(["Foo"]);
+    let foo = Value::from(TypedObject::new(hash, object));
+
+    // NB: the struct has a different shape than the ones in the unit, so its
+    // fields are looked up by name.
+    let vm = Vm::new(context, Arc::new(unit));
+    let output = vm.call(["main"], (foo,))?;
+    assert_eq!(i64::from_value(output)?, 9);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use rune::{Options, Sources, Warnings};
+use runestick::{Any, Context, FromValue, Module, Source, Vm};
+use std::sync::Arc;
+
+#[derive(Debug, Default, Any)]
+struct Foo {
+    value: i64,
+}
+
+impl Foo {
+    fn add(&self, value: i64) -> i64 {
+        self.value + value
+    }
+
+    fn sub(&self, value: i64) -> i64 {
+        self.value - value
+    }
+
+    fn mul(&self, value: i64) -> i64 {
+        self.value * value
+    }
+
+    fn div(&self, value: i64) -> i64 {
+        self.value / value
+    }
+}
+
+#[test]
+fn vm_test_binary_protocols() {
+    let mut module = Module::empty();
+    module.ty(&["Foo"]).build::<Foo>().unwrap();
+    module.inst_fn(runestick::ADD, Foo::add).unwrap();
+    module.inst_fn(runestick::SUB, Foo::sub).unwrap();
+    module.inst_fn(runestick::MUL, Foo::mul).unwrap();
+    module.inst_fn(runestick::DIV, Foo::div).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let context = Arc::new(context);
+
+    let mut sources = Sources::new();
+
+    sources.insert_default(Source::new(
+        "test",
+        r#"
+        fn main(foo) {
+            [foo + 2, foo - 2, foo * 2, foo / 2]
+        }
+        "#,
+    ));
+
+    let unit = rune::load_sources(
+        &context,
+        &Options::default(),
+        &mut sources,
+        &mut Warnings::disabled(),
+    )
+    .unwrap();
+
+    let vm = Vm::new(context, Arc::new(unit));
+
+    let foo = Foo { value: 10 };
+    let output = vm.call(&["main"], (&foo,)).unwrap();
+    let output = Vec::<i64>::from_value(output).unwrap();
+    assert_eq!(output, vec![12, 8, 20, 5]);
+}
@@ -0,0 +1,153 @@
+use rune_testing::*;
+use runestick::{Context, FromValue, Vm};
+use std::sync::Arc;
+
+/// Run the given program to completion with the `time` module installed.
+async fn run_time<T>(source: &str) -> Result<T>
+where
+    T: FromValue,
+{
+    let mut context = Context::with_default_modules()?;
+    context.install(&rune_modules::time::module()?)?;
+
+    let (unit, _) = compile_source(&context, source)?;
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.execute(&["main"], ())?.async_complete().await?;
+    Ok(T::from_value(output)?)
+}
+
+#[tokio::test]
+async fn test_duration_protocols() -> Result<()> {
+    let output: (u128, u128, i64, i64, i64) = run_time(
+        r#"
+        use time::Duration;
+
+        fn main() {
+            let d = Duration::from_millis(1500);
+
+            (
+                (d * 3).as_millis(),
+                (d / 4).as_millis(),
+                d.cmp(Duration::from_secs(1)),
+                d.cmp(Duration::from_millis(1500)),
+                d.cmp(Duration::from_secs(2)),
+            )
+        }
+        "#,
+    )
+    .await?;
+
+    assert_eq!(output, (4500, 375, 1, 0, -1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_instant() -> Result<()> {
+    let output: (u128, i64, i64, i64, bool) = run_time(
+        r#"
+        use time::Duration;
+        use time::Instant;
+
+        async fn main() {
+            let start = Instant::now();
+            let later = start + Duration::from_secs(5);
+            time::delay_for(Duration::from_millis(10)).await;
+
+            (
+                later.duration_since(start).as_millis(),
+                later.cmp(start),
+                start.cmp(later),
+                (later - Duration::from_secs(5)).cmp(start),
+                start.elapsed().cmp(Duration::from_millis(10)) >= 0,
+            )
+        }
+        "#,
+    )
+    .await?;
+
+    assert_eq!(output, (5000, 1, -1, 0, true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timeout() -> Result<()> {
+    let output: (i64, String) = run_time(
+        r#"
+        use time::Duration;
+
+        async fn quick() {
+            42
+        }
+
+        async fn slow() {
+            time::delay_for(Duration::from_secs(10)).await;
+            42
+        }
+
+        async fn main() {
+            let ok = match time::timeout(Duration::from_secs(10), quick()).await {
+                Ok(value) => value,
+                Err(_) => 0,
+            };
+
+            let elapsed = match time::timeout(Duration::from_millis(10), slow()).await {
+                Ok(_) => "finished",
+                Err(error) => `{error}`,
+            };
+
+            (ok, elapsed)
+        }
+        "#,
+    )
+    .await?;
+
+    assert_eq!(output.0, 42);
+    assert_eq!(output.1, "deadline has elapsed");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interval() -> Result<()> {
+    let output: (i64, bool) = run_time(
+        r#"
+        use time::Duration;
+        use time::Instant;
+
+        async fn main() {
+            let start = Instant::now();
+            let interval = time::interval(Duration::from_millis(10));
+            let ticks = 0;
+
+            while ticks < 3 {
+                interval.tick().await;
+                ticks += 1;
+            }
+
+            // The first tick completes immediately.
+            (ticks, start.elapsed().cmp(Duration::from_millis(20)) >= 0)
+        }
+        "#,
+    )
+    .await?;
+
+    assert_eq!(output, (3, true));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interval_zero() -> Result<()> {
+    let error = run_time::<()>(
+        r#"
+        fn main() {
+            time::interval(time::Duration::from_secs(0));
+        }
+        "#,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("interval period must be non-zero"));
+    Ok(())
+}
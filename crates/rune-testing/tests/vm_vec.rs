@@ -0,0 +1,132 @@
+use rune_testing::*;
+
+#[test]
+fn test_vec_mutation() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, i64, bool, Option<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                v.insert(0, 0);
+                v.extend([4, 5, 6]);
+                let removed = v.remove(1);
+                v.swap(0, 1);
+                v.truncate(5);
+                v.reverse();
+                let tail = v.split_off(3);
+                (v, tail, removed, v.contains(4), v.index_of(3))
+            }
+            "#
+        },
+        (vec![5, 4, 3], vec![0, 2], 1, true, Some(2)),
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Option<i64>, Option<i64>, Option<i64>) => r#"
+            fn main() {
+                let v = [1, 1, 2, 3, 3, 3, 1, 4];
+                v.dedup();
+                v.retain(|n| n != 4);
+                (v, v.first(), v.last(), v.get(10))
+            }
+            "#
+        },
+        (vec![1, 2, 3, 1], Some(1), Some(1), None),
+    };
+}
+
+#[test]
+fn test_vec_sort() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<String>, Vec<(i64, i64)>, String) => r#"
+            fn main() {
+                let numbers = [3, -1, 2, 10, 0];
+                numbers.sort();
+
+                let names = ["bob", String::from_str("alice"), "carol"];
+                names.sort();
+
+                let pairs = [(2, 0), (1, 1), (2, 1), (1, 0)];
+                pairs.sort_by(|a, b| a.0 - b.0);
+
+                (numbers, names, pairs, names.join(", "))
+            }
+            "#
+        },
+        (
+            vec![-1, 0, 2, 3, 10],
+            vec![String::from("alice"), String::from("bob"), String::from("carol")],
+            vec![(1, 1), (1, 0), (2, 0), (2, 1)],
+            String::from("alice, bob, carol"),
+        ),
+    };
+}
+
+#[test]
+fn test_vec_reuse_closure() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let desc = |a, b| b - a;
+                let even = |n| n % 2 == 0;
+
+                let a = [1, 4, 2, 3];
+                a.sort_by(desc);
+                a.retain(even);
+
+                let b = [6, 5, 8, 7];
+                b.sort_by(desc);
+                b.retain(even);
+
+                (a, b)
+            }
+            "#
+        },
+        (vec![4, 2], vec![8, 6]),
+    };
+}
+
+#[test]
+fn test_vec_windows_and_chunks() {
+    assert_eq! {
+        rune! {
+            (Vec<Vec<i64>>, Vec<Vec<i64>>) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4, 5];
+                (v.windows(4).collect(), v.chunks(2).collect())
+            }
+            "#
+        },
+        (
+            vec![vec![1, 2, 3, 4], vec![2, 3, 4, 5]],
+            vec![vec![1, 2], vec![3, 4], vec![5]],
+        ),
+    };
+}
+
+#[test]
+fn test_vec_errors() {
+    assert_vm_error!(
+        r#"fn main() { [1, "two"].sort(); }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), UnsupportedOrdering { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { [1, 0.0 / 0.0].sort(); }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), UnsupportedOrdering { .. }));
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { [1, 2].remove(2); }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), IndexOutOfBounds { index: 2, len: 2 }));
+        }
+    );
+}
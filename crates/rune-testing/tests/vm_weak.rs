@@ -0,0 +1,75 @@
+use rune_testing::*;
+
+#[test]
+fn test_weak_upgrade() {
+    assert_eq! {
+        rune! {
+            (i64, bool) => r#"
+            use std::weak::Weak;
+
+            fn main() {
+                let value = [1, 2];
+                let weak = Weak::new(value);
+                let upgraded = weak.upgrade().unwrap_or([]).len();
+                drop(value);
+                (upgraded, weak.upgrade().is_none())
+            }
+            "#
+        },
+        (2, false),
+    };
+
+    assert_eq! {
+        rune! {
+            bool => r#"
+            use std::weak::Weak;
+
+            fn make() {
+                Weak::new(#{})
+            }
+
+            fn main() {
+                let weak = make();
+                weak.is_alive() || weak.upgrade().is_some()
+            }
+            "#
+        },
+        false,
+    };
+}
+
+#[test]
+fn test_weak_breaks_cycle() {
+    assert_eq! {
+        rune! {
+            bool => r#"
+            use std::weak::Weak;
+
+            struct Observer { subject }
+
+            fn main() {
+                let subject = #{ observers: [] };
+                let observer = Observer { subject: Weak::new(subject) };
+                subject.observers.push(observer);
+                let weak = Weak::new(subject);
+                subject = ();
+                weak.is_alive()
+            }
+            "#
+        },
+        false,
+    };
+}
+
+#[test]
+fn test_weak_unsupported() {
+    assert_vm_error!(
+        r#"
+        use std::weak::Weak;
+        fn main() { Weak::new(42) }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), UnsupportedWeak { .. }));
+        }
+    );
+}
@@ -0,0 +1,160 @@
+use rune_testing::*;
+use runestick::BigInt;
+
+#[test]
+fn test_wide_int_arithmetic() {
+    assert_eq! {
+        rune! {
+            (u64, i128, u128, BigInt, u64) => r#"
+            fn main() {
+                let a = 18446744073709551610u64 + 5;
+                let b = 10i128 * -3 - 170141183460469231731687303715884105727i128 / 2;
+                let c = u128::MAX % 1000;
+                let d = 2n.pow(100) - 1;
+                let e = 7u64;
+                e *= 6;
+                e %= 5u64;
+                (a, b, c, d, e)
+            }
+            "#
+        },
+        (
+            u64::MAX,
+            10i128 * -3 - i128::MAX / 2,
+            u128::MAX % 1000,
+            (BigInt::from(1) << 100) - 1,
+            2,
+        ),
+    };
+}
+
+#[test]
+fn test_wide_int_promotion() {
+    assert_eq! {
+        rune! {
+            (BigInt, bool, bool, bool, bool, String) => r#"
+            fn main() {
+                let big = u64::MAX + 1n;
+                (
+                    big * 2,
+                    1u64 == 1,
+                    -1 < 1u64,
+                    u128::MAX > u64::MAX,
+                    big > u64::MAX,
+                    `{u64::MAX} {i128::MIN} {big}`,
+                )
+            }
+            "#
+        },
+        (
+            BigInt::from(u64::MAX) * 2 + 2,
+            true,
+            true,
+            true,
+            true,
+            format!("{} {} {}", u64::MAX, i128::MIN, u128::from(u64::MAX) + 1),
+        ),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, u64, i128, u128, BigInt, f64) => r#"
+            fn main() {
+                (
+                    (42u64).to_int(),
+                    (42).to_u64(),
+                    (-42n).to_i128(),
+                    (42i128).to_u128(),
+                    (42u128).to_bigint(),
+                    (3u64).to_float(),
+                )
+            }
+            "#
+        },
+        (42, 42, -42, 42, BigInt::from(42), 3.0),
+    };
+}
+
+#[test]
+fn test_wide_int_equality() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, Option<i64>, Option<i64>, i64) => r#"
+            use std::collections::HashMap;
+
+            fn main() {
+                let m = HashMap::new();
+                m.insert(1u64, 10);
+                m.insert(2, 20);
+                m.insert(3n, 30);
+                m.insert(3i128, 31);
+
+                let a = match 1u64 { 1 => true, _ => false };
+                let b = match (2i128, 0u128) { (2, 0) => true, _ => false };
+
+                (
+                    1u64 == 1,
+                    a,
+                    b,
+                    m.get(1),
+                    m.get(2u128),
+                    m.len(),
+                )
+            }
+            "#
+        },
+        (true, true, true, Some(10), Some(20), 3),
+    };
+}
+
+#[test]
+fn test_wide_int_conversions() {
+    let output: (u64, i128, BigInt) = run(
+        &["main"],
+        (u64::MAX, i128::MIN, BigInt::from(u128::MAX) + 1),
+        r#"fn main(a, b, c) { (a, b, c) }"#,
+    )
+    .unwrap();
+
+    assert_eq!(output, (u64::MAX, i128::MIN, BigInt::from(u128::MAX) + 1));
+
+    let output: u32 = run(&["main"], (7u64,), r#"fn main(a) { a * 6 }"#).unwrap();
+    assert_eq!(output, 42);
+}
+
+#[test]
+fn test_wide_int_errors() {
+    assert_vm_error!(
+        r#"fn main() { u64::MAX + 1 }"#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 0u64 - 1 }"#,
+        Underflow => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 10n / 0 }"#,
+        DivideByZero => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 10u64 + -1 }"#,
+        ValueToIntegerCoercionError { to, .. } => {
+            assert_eq!(*to, "u64");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 10u64 + 1i128 }"#,
+        UnsupportedBinaryOperation { op: "+", .. } => {}
+    );
+
+    assert_vm_error!(
+        r#"fn main() { (2n.pow(64)).to_u64() }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), ValueToIntegerCoercionError { .. }));
+        }
+    );
+}
@@ -3,15 +3,40 @@
 use crate::collections::HashMap;
 use crate::unit_builder::UnitBuilderError;
 use runestick::{Hash, Inst, Label, Span};
+use std::ops::Range;
 
 #[derive(Debug, Clone)]
 pub enum AssemblyInst {
-    Jump { label: Label },
-    JumpIf { label: Label },
-    JumpIfNot { label: Label },
-    JumpIfBranch { branch: i64, label: Label },
-    PopAndJumpIfNot { count: usize, label: Label },
-    Raw { raw: Inst },
+    Jump {
+        label: Label,
+    },
+    JumpIf {
+        label: Label,
+    },
+    JumpIfNot {
+        label: Label,
+    },
+    JumpIfBranch {
+        branch: i64,
+        label: Label,
+    },
+    PopAndJumpIfNot {
+        count: usize,
+        label: Label,
+    },
+    JumpIfNotLt {
+        lhs: usize,
+        rhs: usize,
+        label: Label,
+    },
+    PopAndJumpIfNotInteger {
+        integer: i64,
+        count: usize,
+        label: Label,
+    },
+    Raw {
+        raw: Inst,
+    },
 }
 
 /// Helper structure to build instructions and maintain certain invariants.
@@ -31,6 +56,9 @@ pub struct Assembly {
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
     pub(crate) required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    /// Named variables, with their stack offset and the range of instructions
+    /// for which they are live.
+    pub(crate) variables: Vec<(String, usize, Range<usize>)>,
 }
 
 impl Assembly {
@@ -44,6 +72,20 @@ impl Assembly {
             comments: Default::default(),
             label_count,
             required_functions: Default::default(),
+            variables: Default::default(),
+        }
+    }
+
+    /// The position of the next instruction in the assembly.
+    pub(crate) fn pos(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Record that the named variable at the given stack offset is live for
+    /// the instructions in the range `start..end`.
+    pub(crate) fn variable(&mut self, name: &str, offset: usize, start: usize, end: usize) {
+        if start < end {
+            self.variables.push((name.to_owned(), offset, start..end));
         }
     }
 
@@ -29,6 +29,8 @@ impl LitNumber {
 /// parse_all::<ast::LitNumber>("42.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42e10").unwrap();
+/// parse_all::<ast::LitNumber>("42u64").unwrap();
+/// parse_all::<ast::LitNumber>("42n").unwrap();
 /// ```
 impl Parse for LitNumber {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -82,6 +84,13 @@ impl<'a> Resolve<'a> for LitNumber {
 
         if text.is_fractional {
             let number = f64::from_str(string).map_err(err_span(span))?;
+
+            let number = if text.is_negative {
+                number.neg()
+            } else {
+                number
+            };
+
             return Ok(ast::Number::Float(number));
         }
 
@@ -92,20 +101,33 @@ impl<'a> Resolve<'a> for LitNumber {
             ast::NumberBase::Decimal => (0, 10),
         };
 
+        // NB: none of the suffixes are valid digits, even in hex.
+        let (string, suffix) = match SUFFIXES.iter().find(|(s, _)| string.ends_with(s)) {
+            Some((s, suffix)) => (&string[..string.len() - s.len()], Some(*suffix)),
+            None => (string, None),
+        };
+
         let number = num::BigUint::from_str_radix(&string[s..], radix).map_err(err_span(span))?;
+        let number = num::BigInt::from(number);
 
         let number = if text.is_negative {
-            num::BigInt::from(number).neg().to_i64()
+            number.neg()
         } else {
-            number.to_i64()
+            number
         };
 
-        let number = match number {
-            Some(n) => n,
-            None => return Err(ParseError::BadNumberOutOfBounds { span }),
+        let number = match suffix {
+            None | Some(Suffix::I64) => number.to_i64().map(ast::Number::Integer),
+            Some(Suffix::U64) => number.to_u64().map(ast::Number::U64),
+            Some(Suffix::I128) => number.to_i128().map(ast::Number::I128),
+            Some(Suffix::U128) => number.to_u128().map(ast::Number::U128),
+            Some(Suffix::BigInt) => Some(ast::Number::BigInt(number)),
         };
 
-        return Ok(ast::Number::Integer(number));
+        return match number {
+            Some(number) => Ok(number),
+            None => Err(ParseError::BadNumberOutOfBounds { span }),
+        };
 
         fn err_span<E>(span: Span) -> impl Fn(E) -> ParseError {
             move |_| ParseError::BadNumberLiteral { span }
@@ -113,6 +135,25 @@ impl<'a> Resolve<'a> for LitNumber {
     }
 }
 
+/// The type suffix of an integer literal.
+#[derive(Debug, Clone, Copy)]
+enum Suffix {
+    I64,
+    U64,
+    I128,
+    U128,
+    BigInt,
+}
+
+/// Supported integer suffixes.
+const SUFFIXES: &[(&str, Suffix)] = &[
+    ("i128", Suffix::I128),
+    ("u128", Suffix::U128),
+    ("i64", Suffix::I64),
+    ("u64", Suffix::U64),
+    ("n", Suffix::BigInt),
+];
+
 impl IntoTokens for LitNumber {
     fn into_tokens(&self, _: &mut crate::MacroContext, stream: &mut crate::TokenStream) {
         stream.push(self.token);
@@ -18,12 +18,20 @@ impl crate::IntoTokens for Token {
 }
 
 /// A resolved number literal.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Number {
     /// A float literal number.
     Float(f64),
     /// An integer literal number.
     Integer(i64),
+    /// An unsigned 64-bit integer literal, like `10u64`.
+    U64(u64),
+    /// A signed 128-bit integer literal, like `10i128`.
+    I128(i128),
+    /// An unsigned 128-bit integer literal, like `10u128`.
+    U128(u128),
+    /// An arbitrary precision integer literal, like `10n`.
+    BigInt(num::BigInt),
 }
 
 impl Number {
@@ -55,6 +63,10 @@ impl fmt::Display for Number {
         match self {
             Self::Float(n) => write!(f, "{}", n),
             Self::Integer(n) => write!(f, "{}", n),
+            Self::U64(n) => write!(f, "{}u64", n),
+            Self::I128(n) => write!(f, "{}i128", n),
+            Self::U128(n) => write!(f, "{}u128", n),
+            Self::BigInt(n) => write!(f, "{}n", n),
         }
     }
 }
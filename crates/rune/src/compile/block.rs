@@ -15,7 +15,7 @@ impl Compile<(&ast::Block, &[CompileMetaCapture])> for Compiler<'_> {
         log::trace!("ExprBlock (procedure) => {:?}", self.source.source(span));
 
         let scope = self.scopes.last(span)?.child();
-        let guard = self.scopes.push(scope);
+        let guard = self.scopes.push(scope, self.asm.pos());
 
         for capture in captures {
             self.scopes.new_var(&capture.ident, span)?;
@@ -40,7 +40,7 @@ impl Compile<(&ast::Block, Needs)> for Compiler<'_> {
         let span = block.span();
 
         let new_scope = self.scopes.child(span)?;
-        let scopes_count = self.scopes.push(new_scope);
+        let scopes_count = self.scopes.push(new_scope, self.asm.pos());
 
         let mut last = None::<(&ast::Expr, bool)>;
 
@@ -70,6 +70,7 @@ impl Compile<(&ast::Block, Needs)> for Compiler<'_> {
         };
 
         let scope = self.scopes.pop(scopes_count, span)?;
+        scope.debug_variables(self.asm);
 
         if needs.value() {
             if produced {
@@ -158,18 +158,18 @@ fn compile_assign_binop(
                         compiler.compile((rhs, Needs::Value))?;
                         compiler.scopes.decl_anon(rhs.span())?;
 
-                        compiler.asm.push(Inst::String { slot: index }, span);
-                        compiler.scopes.decl_anon(span)?;
-
                         compiler.compile((&*field_access.expr, Needs::Value))?;
-                        compiler.asm.push(Inst::IndexSet, span);
-                        compiler.scopes.undecl_anon(2, span)?;
+                        compiler
+                            .asm
+                            .push(Inst::ObjectSlotIndexSet { slot: index }, span);
+                        compiler.scopes.undecl_anon(1, span)?;
                         true
                     }
                     ast::ExprField::LitNumber(field) => {
                         let span = field.span();
                         let number = field.resolve(compiler.storage, &*compiler.source)?;
                         let index = number
+                            .clone()
                             .into_tuple_index()
                             .ok_or_else(|| CompileError::UnsupportedTupleIndex { number, span })?;
 
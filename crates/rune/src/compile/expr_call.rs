@@ -12,7 +12,7 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
         log::trace!("ExprCall => {:?}", self.source.source(span));
 
         let scope = self.scopes.child(span)?;
-        let guard = self.scopes.push(scope);
+        let guard = self.scopes.push(scope, self.asm.pos());
 
         let args = expr_call.args.items.len();
 
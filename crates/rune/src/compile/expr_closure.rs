@@ -48,13 +48,14 @@ impl Compile<(ast::ExprClosure, &[CompileMetaCapture])> for Compiler<'_> {
 
         self.compile((&*expr_closure.body, Needs::Value))?;
 
+        let scope = self.scopes.pop_last(span)?;
+        scope.debug_variables(self.asm);
+
         if count != 0 {
             self.asm.push(Inst::Clean { count }, span);
         }
 
         self.asm.push(Inst::Return, span);
-
-        self.scopes.pop_last(span)?;
         Ok(())
     }
 }
@@ -31,7 +31,7 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
                 format!("into_iter (offset: {})", iter_offset),
             );
 
-            let loop_scope_expected = self.scopes.push(loop_scope);
+            let loop_scope_expected = self.scopes.push(loop_scope, self.asm.pos());
             (iter_offset, loop_scope_expected)
         };
 
@@ -47,9 +47,10 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
         let binding_offset = {
             self.asm.push(Inst::Unit, expr_for.iter.span());
             let name = expr_for.var.resolve(&self.storage, &*self.source)?;
+            let pos = self.asm.pos();
             self.scopes
                 .last_mut(span)?
-                .decl_var(name.as_ref(), expr_for.var.span())
+                .decl_var(name.as_ref(), expr_for.var.span(), pos)
         };
 
         // Declare storage for memoized `next` instance fn.
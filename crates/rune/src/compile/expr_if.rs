@@ -37,7 +37,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
 
         self.asm.label(then_label)?;
 
-        let expected = self.scopes.push(then_scope);
+        let expected = self.scopes.push(then_scope, self.asm.pos());
         self.compile((&*expr_if.block, needs))?;
         self.clean_last_scope(span, expected, needs)?;
 
@@ -52,7 +52,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
 
             self.asm.label(label)?;
 
-            let scopes = self.scopes.push(scope);
+            let scopes = self.scopes.push(scope, self.asm.pos());
             self.compile((&*branch.block, needs))?;
             self.clean_last_scope(span, scopes, needs)?;
 
@@ -14,7 +14,7 @@ impl Compile<(&ast::ExprIndexGet, Needs)> for Compiler<'_> {
         log::trace!("ExprIndexGet => {:?}", self.source.source(span));
 
         let scope = self.scopes.last(span)?.child();
-        let guard = self.scopes.push(scope);
+        let guard = self.scopes.push(scope, self.asm.pos());
 
         self.compile((&*expr_index_get.index, Needs::Value))?;
         self.scopes.decl_anon(span)?;
@@ -37,7 +37,7 @@ impl Compile<(&ast::ExprLet, Needs)> for Compiler<'_> {
             self.asm.label(ok_label)?;
         }
 
-        let _ = self.scopes.push(scope);
+        let _ = self.scopes.push(scope, self.asm.pos());
 
         // If a value is needed for a let expression, it is evaluated as a unit.
         if needs.value() {
@@ -11,7 +11,7 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
         log::trace!("ExprMatch => {:?}", self.source.source(span));
 
         let new_scope = self.scopes.child(span)?;
-        let expected_scopes = self.scopes.push(new_scope);
+        let expected_scopes = self.scopes.push(new_scope, self.asm.pos());
 
         self.compile((&*expr_match.expr, Needs::Value))?;
         // Offset of the expression.
@@ -37,9 +37,9 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
             let scope = if let Some((_, condition)) = &branch.condition {
                 let span = condition.span();
 
-                let parent_guard = self.scopes.push(scope);
+                let parent_guard = self.scopes.push(scope, self.asm.pos());
                 let scope = self.scopes.child(span)?;
-                let guard = self.scopes.push(scope);
+                let guard = self.scopes.push(scope, self.asm.pos());
 
                 self.compile((&**condition, Needs::Value))?;
                 self.clean_last_scope(span, guard, Needs::Value)?;
@@ -75,7 +75,7 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
 
             self.asm.label(*label)?;
 
-            let expected = self.scopes.push(scope.clone());
+            let expected = self.scopes.push(scope.clone(), self.asm.pos());
             self.compile((&*branch.body, needs))?;
             self.clean_last_scope(span, expected, needs)?;
 
@@ -58,7 +58,7 @@ impl Compile<(&ast::ExprSelect, Needs)> for Compiler<'_> {
                         let item = self.convert_path_to_item(&path.path)?;
 
                         if let Some(local) = item.as_local() {
-                            scope.decl_var(local, span);
+                            scope.decl_var(local, span, self.asm.pos());
                             break;
                         }
                     }
@@ -75,7 +75,7 @@ impl Compile<(&ast::ExprSelect, Needs)> for Compiler<'_> {
             }
 
             // Set up a new scope with the binding.
-            let expected = self.scopes.push(scope);
+            let expected = self.scopes.push(scope, self.asm.pos());
             self.compile((&*branch.body, needs))?;
             self.clean_last_scope(span, expected, needs)?;
             self.asm.jump(end_label, span);
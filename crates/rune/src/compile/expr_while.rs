@@ -30,7 +30,7 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         self.asm.jump(end_label, span);
         self.asm.label(then_label)?;
 
-        let expected = self.scopes.push(then_scope);
+        let expected = self.scopes.push(then_scope, self.asm.pos());
         self.compile((&*expr_while.body, Needs::None))?;
         self.clean_last_scope(span, expected, Needs::None)?;
 
@@ -39,8 +39,9 @@ impl Compile<(ast::ItemFn, bool)> for Compiler<'_> {
         }
 
         if fn_decl.body.statements.is_empty() {
-            let total_var_count = self.scopes.last(span)?.total_var_count;
-            self.locals_pop(total_var_count, span);
+            let scope = self.scopes.pop_last(span)?;
+            scope.debug_variables(self.asm);
+            self.locals_pop(scope.total_var_count, span);
             self.asm.push(Inst::ReturnUnit, span);
             return Ok(());
         }
@@ -48,18 +49,19 @@ impl Compile<(ast::ItemFn, bool)> for Compiler<'_> {
         if !fn_decl.body.produces_nothing() {
             self.compile((&fn_decl.body, Needs::Value))?;
 
-            let total_var_count = self.scopes.last(span)?.total_var_count;
-            self.locals_clean(total_var_count, span);
+            let scope = self.scopes.pop_last(span)?;
+            scope.debug_variables(self.asm);
+            self.locals_clean(scope.total_var_count, span);
             self.asm.push(Inst::Return, span);
         } else {
             self.compile((&fn_decl.body, Needs::None))?;
 
-            let total_var_count = self.scopes.last(span)?.total_var_count;
-            self.locals_pop(total_var_count, span);
+            let scope = self.scopes.pop_last(span)?;
+            scope.debug_variables(self.asm);
+            self.locals_pop(scope.total_var_count, span);
             self.asm.push(Inst::ReturnUnit, span);
         }
 
-        self.scopes.pop_last(span)?;
         Ok(())
     }
 }
@@ -25,6 +25,20 @@ impl Compile<(&ast::LitNumber, Needs)> for Compiler<'_> {
             ast::Number::Integer(number) => {
                 self.asm.push(Inst::Integer { number }, span);
             }
+            ast::Number::U64(number) => {
+                self.asm.push(Inst::U64 { number }, span);
+            }
+            ast::Number::I128(number) => {
+                self.asm.push(Inst::I128 { number }, span);
+            }
+            ast::Number::U128(number) => {
+                self.asm.push(Inst::U128 { number }, span);
+            }
+            ast::Number::BigInt(number) => {
+                let bytes = number.to_signed_bytes_le();
+                let slot = self.unit.borrow_mut().new_static_bytes(&bytes)?;
+                self.asm.push(Inst::BigInt { slot }, span);
+            }
         }
 
         Ok(())
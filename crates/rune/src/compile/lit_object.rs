@@ -41,6 +41,8 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             }
         }
 
+        let mut offsets = Vec::new();
+
         for assign in lit_object.assignments.iter() {
             let span = assign.span();
 
@@ -51,15 +53,20 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                 // side effects (without creating an object).
                 if !needs.value() {
                     self.asm.push(Inst::Pop, span);
+                    continue;
                 }
             } else {
                 let key = assign.key.resolve(&self.storage, &*self.source)?;
                 let var = self.scopes.get_var(&*key, span)?;
 
-                if needs.value() {
-                    var.copy(&mut self.asm, span, format!("name `{}`", key));
+                if !needs.value() {
+                    continue;
                 }
+
+                var.copy(&mut self.asm, span, format!("name `{}`", key));
             }
+
+            offsets.push(self.scopes.decl_anon(span)?);
         }
 
         // No need to encode an object since the value is not needed.
@@ -68,6 +75,43 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             return Ok(());
         }
 
+        self.scopes.undecl_anon(offsets.len(), span)?;
+
+        // NB: the fields of structs are always stored sorted by name, so that
+        // all instances of a struct share the same shape regardless of the
+        // order they are assigned in.
+        let (keys, clean) = match &lit_object.ident {
+            ast::LitObjectIdent::Named(..) => {
+                let mut sorted = keys.iter().zip(offsets).collect::<Vec<_>>();
+                sorted.sort_by(|a, b| a.0.cmp(b.0));
+
+                // Fields are evaluated in the order they are assigned, so if
+                // that doesn't match the sorted order they are copied into
+                // place and the evaluated values are cleaned up afterwards.
+                let clean = if sorted.iter().map(|(key, _)| *key).ne(keys.iter()) {
+                    for (key, offset) in &sorted {
+                        self.asm.push_with_comment(
+                            Inst::Copy { offset: *offset },
+                            span,
+                            format!("field `{}`", key),
+                        );
+                    }
+
+                    Some(sorted.len())
+                } else {
+                    None
+                };
+
+                let sorted = sorted
+                    .into_iter()
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>();
+
+                (sorted, clean)
+            }
+            ast::LitObjectIdent::Anonymous(..) => (keys, None),
+        };
+
         let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
 
         match &lit_object.ident {
@@ -128,6 +172,10 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             }
         }
 
+        if let Some(count) = clean {
+            self.asm.push(Inst::Clean { count }, span);
+        }
+
         Ok(())
     }
 }
@@ -24,7 +24,7 @@ impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
         }
 
         let scope = self.scopes.child(span)?;
-        let expected = self.scopes.push(scope);
+        let expected = self.scopes.push(scope, self.asm.pos());
 
         for c in template.components.iter() {
             match c {
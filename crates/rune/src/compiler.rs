@@ -6,6 +6,7 @@ use crate::error::CompileResult;
 use crate::index_scopes::IndexScopes;
 use crate::items::Items;
 use crate::loops::Loops;
+use crate::peephole;
 use crate::query::{Build, BuildEntry, Query};
 use crate::scopes::{Scope, ScopeGuard, Scopes};
 use crate::traits::Compile as _;
@@ -198,6 +199,10 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
             compiler.contexts.push(span);
             compiler.compile((f.ast, false))?;
 
+            if options.peephole {
+                peephole::optimize(&mut asm);
+            }
+
             unit.borrow_mut()
                 .new_function(source_id, item, count, asm, f.call, args)?;
         }
@@ -227,6 +232,10 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
 
             compiler.compile((f.ast, true))?;
 
+            if options.peephole {
+                peephole::optimize(&mut asm);
+            }
+
             unit.borrow_mut().new_instance_function(
                 source_id,
                 item,
@@ -250,6 +259,10 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
             compiler.contexts.push(span);
             compiler.compile((c.ast, &c.captures[..]))?;
 
+            if options.peephole {
+                peephole::optimize(&mut asm);
+            }
+
             unit.borrow_mut()
                 .new_function(source_id, item, count, asm, c.call, args)?;
         }
@@ -259,6 +272,10 @@ fn compile_entry(args: CompileEntryArgs<'_>) -> Result<(), CompileError> {
             compiler.contexts.push(span);
             compiler.compile((&async_block.ast, &async_block.captures[..]))?;
 
+            if options.peephole {
+                peephole::optimize(&mut asm);
+            }
+
             unit.borrow_mut().new_function(
                 source_id,
                 item,
@@ -471,6 +488,14 @@ impl<'a> Compiler<'a> {
                     self.asm
                         .push_with_comment(Inst::Fn { hash }, span, format!("fn `{}`", item));
                 }
+                CompileMeta::Constant { type_of, item, .. } => {
+                    let hash = **type_of;
+                    self.asm.push_with_comment(
+                        Inst::Call { hash, args: 0 },
+                        span,
+                        format!("const `{}`", item),
+                    );
+                }
                 meta => {
                     return Err(CompileError::UnsupportedValue {
                         span,
@@ -708,6 +733,10 @@ impl<'a> Compiler<'a> {
 
         let keys = self.unit.borrow_mut().new_static_object_keys(&keys[..])?;
 
+        // NB: the slot of the shape of the struct being matched, and the keys
+        // of the shape.
+        let mut shape = None;
+
         let type_check = match &pat_object.ident {
             ast::LitObjectIdent::Named(path) => {
                 let span = path.span();
@@ -759,6 +788,13 @@ impl<'a> Compiler<'a> {
                     }
                 }
 
+                // NB: structs are constructed with their fields sorted by
+                // name, see the compilation of object literals.
+                let mut sorted = fields.iter().cloned().collect::<Vec<_>>();
+                sorted.sort();
+
+                let slot = self.unit.borrow_mut().new_static_object_keys(&sorted)?;
+                shape = Some((slot, sorted));
                 type_check
             }
             ast::LitObjectIdent::Anonymous(..) => TypeCheck::Object,
@@ -782,8 +818,24 @@ impl<'a> Compiler<'a> {
         for ((item, _), slot) in pat_object.fields.iter().zip(string_slots) {
             let span = item.span();
 
+            let inst = match &shape {
+                Some((shape, sorted)) => {
+                    let key = item.key.resolve(&self.storage, &*self.source)?;
+
+                    match sorted.iter().position(|field| *field == *key) {
+                        Some(index) => Inst::ShapeIndexGetAt {
+                            offset,
+                            slot: *shape,
+                            index,
+                        },
+                        None => Inst::ObjectSlotIndexGetAt { offset, slot },
+                    }
+                }
+                None => Inst::ObjectSlotIndexGetAt { offset, slot },
+            };
+
             let load = move |asm: &mut Assembly| {
-                asm.push(Inst::ObjectSlotIndexGetAt { offset, slot }, span);
+                asm.push(inst, span);
             };
 
             if let Some((_, pat)) = &item.binding {
@@ -800,7 +852,7 @@ impl<'a> Compiler<'a> {
 
             load(&mut self.asm);
             let name = ident.resolve(&self.storage, &*self.source)?;
-            scope.decl_var(name.as_ref(), span);
+            scope.decl_var(name.as_ref(), span, self.asm.pos());
         }
 
         Ok(())
@@ -882,7 +934,7 @@ impl<'a> Compiler<'a> {
                 };
 
                 load(&mut self.asm);
-                scope.decl_var(&ident, span);
+                scope.decl_var(&ident, span, self.asm.pos());
                 return Ok(false);
             }
             ast::Pat::PatIgnore(..) => {
@@ -912,6 +964,7 @@ impl<'a> Compiler<'a> {
                     ast::Number::Float(..) => {
                         return Err(CompileError::MatchFloatInPattern { span });
                     }
+                    _ => return Err(CompileError::MatchWideIntegerInPattern { span }),
                 };
 
                 load(&mut self.asm);
@@ -951,6 +1004,7 @@ impl<'a> Compiler<'a> {
         needs: Needs,
     ) -> CompileResult<()> {
         let scope = self.scopes.pop(expected, span)?;
+        scope.debug_variables(self.asm);
 
         if needs.value() {
             self.locals_clean(scope.local_var_count, span);
@@ -154,10 +154,12 @@ impl EmitDiagnostics for VmError {
             files.add(source.name(), source.as_str());
         }
 
-        let (error, unwound) = self.into_unwound();
+        let (error, backtrace) = self.into_backtrace();
 
-        let (unit, ip) = match unwound {
-            Some((unit, ip)) => (unit, ip),
+        let mut frames = backtrace.iter();
+
+        let first = match frames.next() {
+            Some(first) => first,
             None => {
                 writeln!(
                     out,
@@ -169,16 +171,13 @@ impl EmitDiagnostics for VmError {
             }
         };
 
-        let debug_info = match unit.debug_info() {
-            Some(debug_info) => debug_info,
-            None => {
-                writeln!(out, "virtual machine error: {} (no debug info)", error)?;
-                return Ok(());
-            }
-        };
+        if first.unit.debug_info().is_none() {
+            writeln!(out, "virtual machine error: {} (no debug info)", error)?;
+            return Ok(());
+        }
 
-        let debug_inst = match debug_info.instruction_at(ip) {
-            Some(debug_inst) => debug_inst,
+        let (source_id, span) = match first.span() {
+            Some(span) => span,
             None => {
                 writeln!(
                     out,
@@ -192,19 +191,43 @@ impl EmitDiagnostics for VmError {
 
         let config = codespan_reporting::term::Config::default();
 
-        let mut labels = Vec::new();
+        let labels =
+            vec![Label::primary(source_id, span.start..span.end).with_message(error.to_string())];
 
-        let source_id = debug_inst.source_id;
-        let span = debug_inst.span;
+        let mut notes = Vec::new();
 
-        labels
-            .push(Label::primary(source_id, span.start..span.end).with_message(error.to_string()));
+        if let Some(item) = first.item() {
+            notes.push(format!("in `{}`", item));
+        }
 
         let diagnostic = Diagnostic::error()
             .with_message("virtual machine error")
-            .with_labels(labels);
+            .with_labels(labels)
+            .with_notes(notes);
 
         term::emit(out, &config, &files, &diagnostic)?;
+
+        for frame in frames {
+            let message = match frame.item() {
+                Some(item) => format!("called from `{}`", item),
+                None => String::from("called from unknown function"),
+            };
+
+            let (source_id, span) = match frame.span() {
+                Some(span) => span,
+                None => {
+                    writeln!(out, "{} (at {})", message, frame.ip)?;
+                    continue;
+                }
+            };
+
+            let labels = vec![Label::secondary(source_id, span.start..span.end)];
+
+            let diagnostic = Diagnostic::note().with_message(message).with_labels(labels);
+
+            term::emit(out, &config, &files, &diagnostic)?;
+        }
+
         Ok(())
     }
 }
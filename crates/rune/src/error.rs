@@ -740,6 +740,12 @@ pub enum CompileError {
         /// Where the float was used.
         span: Span,
     },
+    /// Attempting to use an integer wider than `int` in a match pattern.
+    #[error("integers wider than `int` cannot be used in patterns")]
+    MatchWideIntegerInPattern {
+        /// Where the integer was used.
+        span: Span,
+    },
     /// Attempting to create an object with a duplicate object key.
     #[error("duplicate key in literal object")]
     DuplicateObjectKey {
@@ -865,6 +871,7 @@ impl CompileError {
             Self::BreakOutsideOfLoop { span, .. } => span,
             Self::ReturnLocalReferences { span, .. } => span,
             Self::MatchFloatInPattern { span, .. } => span,
+            Self::MatchWideIntegerInPattern { span, .. } => span,
             Self::DuplicateObjectKey { span, .. } => span,
             Self::LitObjectMissingField { span, .. } => span,
             Self::LitObjectNotField { span, .. } => span,
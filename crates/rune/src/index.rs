@@ -461,11 +461,17 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::LitByte(..) => (),
             ast::Expr::LitChar(..) => (),
             ast::Expr::LitNumber(..) => (),
-            ast::Expr::LitObject(..) => (),
+            ast::Expr::LitObject(lit_object) => {
+                self.index(lit_object)?;
+            }
             ast::Expr::LitStr(..) => (),
             ast::Expr::LitByteStr(..) => (),
-            ast::Expr::LitTuple(..) => (),
-            ast::Expr::LitVec(..) => (),
+            ast::Expr::LitTuple(lit_tuple) => {
+                self.index(lit_tuple)?;
+            }
+            ast::Expr::LitVec(lit_vec) => {
+                self.index(lit_vec)?;
+            }
             // NB: macros have nothing to index, they don't export language
             // items.
             ast::Expr::MacroCall(expr_call_macro) => {
@@ -834,6 +840,23 @@ impl Index<ast::ExprSelect> for Indexer<'_> {
 
 impl Index<ast::ExprCall> for Indexer<'_> {
     fn index(&mut self, expr_call: &ast::ExprCall) -> CompileResult<()> {
+        // NB: instance function calls compile the receiver before the
+        // arguments, so it has to be indexed in the same order for closures to
+        // be matched up with the right bodies.
+        if let ast::Expr::ExprFieldAccess(ast::ExprFieldAccess {
+            expr_field: ast::ExprField::Ident(..),
+            ..
+        }) = &*expr_call.expr
+        {
+            self.index(&*expr_call.expr)?;
+
+            for (expr, _) in expr_call.args.items.iter() {
+                self.index(expr)?;
+            }
+
+            return Ok(());
+        }
+
         for (expr, _) in expr_call.args.items.iter() {
             self.index(expr)?;
         }
@@ -843,6 +866,46 @@ impl Index<ast::ExprCall> for Indexer<'_> {
     }
 }
 
+impl Index<ast::LitObject> for Indexer<'_> {
+    fn index(&mut self, lit_object: &ast::LitObject) -> CompileResult<()> {
+        for assign in &lit_object.assignments {
+            if let Some((_, expr)) = &assign.assign {
+                self.index(expr)?;
+            } else {
+                match &assign.key {
+                    ast::LitObjectKey::Ident(ident) => {
+                        let ident = ident.resolve(&self.storage, &*self.source)?;
+                        self.scopes.mark_use(ident.as_ref());
+                    }
+                    ast::LitObjectKey::LitStr(..) => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitTuple> for Indexer<'_> {
+    fn index(&mut self, lit_tuple: &ast::LitTuple) -> CompileResult<()> {
+        for (expr, _) in &lit_tuple.items {
+            self.index(expr)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::LitVec> for Indexer<'_> {
+    fn index(&mut self, lit_vec: &ast::LitVec) -> CompileResult<()> {
+        for expr in &lit_vec.items {
+            self.index(expr)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Index<ast::LitTemplate> for Indexer<'_> {
     fn index(&mut self, lit_template: &ast::LitTemplate) -> CompileResult<()> {
         let template = lit_template.resolve(&self.storage, &*self.source)?;
@@ -134,12 +134,12 @@ impl<'a> Lexer<'a> {
             match c {
                 c if char::is_alphanumeric(c) => (),
                 '.' if !is_fractional => {
-                    is_fractional = true;
-
                     // char immediately following a dot should be numerical.
                     if !it.next().map(|(_, c)| c.is_numeric()).unwrap_or_default() {
                         break self.cursor + n;
                     }
+
+                    is_fractional = true;
                 }
                 _ => break self.cursor + n,
             }
@@ -184,6 +184,7 @@ mod macro_context;
 mod macros;
 mod options;
 mod parser;
+mod peephole;
 mod query;
 mod quote;
 mod scopes;
@@ -218,7 +219,7 @@ pub use crate::storage::Storage;
 pub use crate::token_stream::{IntoTokens, TokenStream, TokenStreamIter};
 pub use crate::traits::{Parse, Peek, Resolve};
 pub use crate::warning::{Warning, WarningKind, Warnings};
-pub use compiler::compile;
+pub use compiler::{compile, compile_with_options};
 pub use unit_builder::{ImportEntry, ImportKey, LinkerError, LinkerErrors, UnitBuilder};
 
 #[cfg(feature = "diagnostics")]
@@ -243,6 +244,8 @@ pub fn default_context() -> Result<runestick::Context, runestick::ContextError>
         context.install(&rune_modules::process::module()?)?;
         context.install(&rune_modules::fs::module()?)?;
         context.install(&rune_modules::signal::module()?)?;
+        context.install(&rune_modules::rand::module()?)?;
+        context.install(&rune_modules::regex::module()?)?;
     }
 
     Ok(context)
@@ -10,6 +10,8 @@ pub struct Options {
     pub(crate) debug_info: bool,
     /// Support (experimental) macros.
     pub(crate) macros: bool,
+    /// Perform peephole optimizations of the generated instructions.
+    pub(crate) peephole: bool,
     /// Support (experimental) bytecode caching.
     pub bytecode: bool,
 }
@@ -37,6 +39,9 @@ impl Options {
             Some("macros") => {
                 self.macros = it.next() != Some("false");
             }
+            Some("peephole") => {
+                self.peephole = it.next() != Some("false");
+            }
             Some("bytecode") => {
                 self.bytecode = it.next() != Some("false");
             }
@@ -67,6 +72,13 @@ impl Options {
         self.macros = enabled;
     }
 
+    /// Set if peephole optimizations are enabled or not. Defaults to `true`.
+    /// This removes redundant instructions and fuses common sequences of
+    /// instructions into more efficient ones.
+    pub fn peephole(&mut self, enabled: bool) {
+        self.peephole = enabled;
+    }
+
     /// Set if bytecode caching is enabled or not. Defaults to `false`.
     pub fn bytecode(&mut self, enabled: bool) {
         self.bytecode = enabled;
@@ -80,6 +92,7 @@ impl Default for Options {
             memoize_instance_fn: true,
             debug_info: true,
             macros: false,
+            peephole: true,
             bytecode: false,
         }
     }
@@ -0,0 +1,207 @@
+//! A peephole optimizer for assembly.
+//!
+//! The optimizer runs over the assembly of a single function before it's
+//! translated into instructions. Since jumps at this stage refer to labels
+//! instead of relative offsets, removing or fusing instructions only requires
+//! the positions of labels, comments and variables to be updated.
+
+use crate::assembly::{Assembly, AssemblyInst};
+use crate::collections::{HashMap, HashSet};
+use runestick::{Inst, Label, Span};
+use std::mem;
+
+/// Optimize the given assembly in place.
+pub(crate) fn optimize(asm: &mut Assembly) {
+    thread_jumps(asm);
+    while fuse(asm) {}
+    thread_jumps(asm);
+}
+
+/// Retarget jumps which lead to an unconditional jump, to the target of that
+/// jump instead.
+fn thread_jumps(asm: &mut Assembly) {
+    for pos in 0..asm.instructions.len() {
+        let label = match label_mut(&mut asm.instructions[pos].0) {
+            Some(label) => *label,
+            None => continue,
+        };
+
+        let target = follow_jumps(asm, label);
+
+        if let Some(label) = label_mut(&mut asm.instructions[pos].0) {
+            *label = target;
+        }
+    }
+}
+
+/// Follow the chain of unconditional jumps starting at the given label.
+fn follow_jumps(asm: &Assembly, mut label: Label) -> Label {
+    // NB: bounded since unconditional jumps might form a cycle.
+    for _ in 0..asm.instructions.len() {
+        let pos = match asm.labels.get(&label) {
+            Some(pos) => *pos,
+            None => break,
+        };
+
+        match asm.instructions.get(pos) {
+            Some((AssemblyInst::Jump { label: next }, _)) if *next != label => {
+                label = *next;
+            }
+            _ => break,
+        }
+    }
+
+    label
+}
+
+/// Perform a single pass removing and fusing instructions, returning `true`
+/// if anything was changed.
+fn fuse(asm: &mut Assembly) -> bool {
+    let targets = asm.labels.values().copied().collect::<HashSet<_>>();
+    let old = mem::take(&mut asm.instructions);
+
+    let mut instructions = Vec::with_capacity(old.len());
+    // The new position of every old position, including the end of the
+    // assembly.
+    let mut positions = Vec::with_capacity(old.len() + 1);
+    let mut changed = false;
+    let mut pos = 0;
+
+    while pos < old.len() {
+        let (len, replacement) = match peephole(&old[pos..], pos, &asm.labels) {
+            // NB: we can't touch a sequence which is jumped into.
+            Some((len, replacement)) if !(pos + 1..pos + len).any(|p| targets.contains(&p)) => {
+                (len, replacement)
+            }
+            _ => (1, Some(old[pos].clone())),
+        };
+
+        if len > 1 || replacement.is_none() {
+            changed = true;
+        }
+
+        for _ in 0..len {
+            positions.push(instructions.len());
+        }
+
+        instructions.extend(replacement);
+        pos += len;
+    }
+
+    positions.push(instructions.len());
+    asm.instructions = instructions;
+
+    if !changed {
+        return false;
+    }
+
+    for pos in asm.labels.values_mut() {
+        *pos = positions[*pos];
+    }
+
+    asm.labels_rev = mem::take(&mut asm.labels_rev)
+        .into_iter()
+        .map(|(pos, label)| (positions[pos], label))
+        .collect();
+
+    let mut old_comments = mem::take(&mut asm.comments).into_iter().collect::<Vec<_>>();
+    old_comments.sort_by_key(|(pos, _)| *pos);
+
+    for (pos, comments) in old_comments {
+        asm.comments
+            .entry(positions[pos])
+            .or_default()
+            .extend(comments);
+    }
+
+    for (_, _, range) in &mut asm.variables {
+        range.start = positions[range.start];
+        range.end = positions[range.end];
+    }
+
+    asm.variables
+        .retain(|(_, _, range)| range.start < range.end);
+    true
+}
+
+/// Match a sequence of instructions starting at `pos` which can be simplified,
+/// returning the number of instructions matched and what to replace them with.
+fn peephole(
+    window: &[(AssemblyInst, Span)],
+    pos: usize,
+    labels: &HashMap<Label, usize>,
+) -> Option<(usize, Option<(AssemblyInst, Span)>)> {
+    use AssemblyInst::*;
+
+    let inst = |n: usize| window.get(n).map(|(inst, _)| inst);
+    let span = |n: usize| window[n].1;
+
+    let raw = |n: usize| match inst(n) {
+        Some(Raw { raw }) => Some(*raw),
+        _ => None,
+    };
+
+    let result = match (raw(0), raw(1), raw(2), inst(3)) {
+        (Some(Inst::Copy { .. }), Some(Inst::Pop), _, _) => (2, None),
+        (Some(Inst::Copy { offset }), Some(Inst::Integer { number }), Some(Inst::Add), _) => {
+            let raw = Inst::AddImmediate { offset, number };
+            (3, Some((Raw { raw }, span(2))))
+        }
+        (
+            Some(Inst::Copy { offset: lhs }),
+            Some(Inst::Copy { offset: rhs }),
+            Some(Inst::Lt),
+            Some(JumpIfNot { label }),
+        ) => {
+            let label = *label;
+            (4, Some((JumpIfNotLt { lhs, rhs, label }, span(2))))
+        }
+        _ => match (raw(0), inst(0)?, inst(1)?) {
+            (Some(Inst::EqInteger { integer }), _, PopAndJumpIfNot { count, label }) => {
+                let (count, label) = (*count, *label);
+                let inst = PopAndJumpIfNotInteger {
+                    integer,
+                    count,
+                    label,
+                };
+
+                (2, Some((inst, span(0))))
+            }
+            (Some(Inst::EqInteger { integer }), _, JumpIfNot { label }) => {
+                let inst = PopAndJumpIfNotInteger {
+                    integer,
+                    count: 0,
+                    label: *label,
+                };
+
+                (2, Some((inst, span(0))))
+            }
+            // A conditional jump over an unconditional jump is inverted.
+            (_, JumpIf { label: then }, Jump { label }) if labels.get(then) == Some(&(pos + 2)) => {
+                (2, Some((JumpIfNot { label: *label }, span(0))))
+            }
+            (_, JumpIfNot { label: then }, Jump { label })
+                if labels.get(then) == Some(&(pos + 2)) =>
+            {
+                (2, Some((JumpIf { label: *label }, span(0))))
+            }
+            _ => return None,
+        },
+    };
+
+    Some(result)
+}
+
+/// Access the label of an instruction which jumps.
+fn label_mut(inst: &mut AssemblyInst) -> Option<&mut Label> {
+    match inst {
+        AssemblyInst::Jump { label }
+        | AssemblyInst::JumpIf { label }
+        | AssemblyInst::JumpIfNot { label }
+        | AssemblyInst::JumpIfBranch { label, .. }
+        | AssemblyInst::PopAndJumpIfNot { label, .. }
+        | AssemblyInst::JumpIfNotLt { label, .. }
+        | AssemblyInst::PopAndJumpIfNotInteger { label, .. } => Some(label),
+        AssemblyInst::Raw { .. } => None,
+    }
+}
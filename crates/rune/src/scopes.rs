@@ -10,6 +10,10 @@ pub(crate) struct Var {
     pub(crate) offset: usize,
     /// Token assocaited with the variable.
     span: Span,
+    /// The instruction position at which the variable becomes live, if known.
+    ///
+    /// This is only used to generate debug information.
+    pos: Option<usize>,
 }
 
 impl Var {
@@ -52,6 +56,11 @@ pub(crate) struct Scope {
     pub(crate) total_var_count: usize,
     /// The number of variables local to this scope.
     pub(crate) local_var_count: usize,
+    /// Named variables that have been shadowed by another variable with the
+    /// same name in this scope, in the order they were declared.
+    shadowed: Vec<(String, Var)>,
+    /// The instruction position at which the scope was last pushed.
+    pos: usize,
 }
 
 impl Scope {
@@ -62,6 +71,8 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: 0,
             local_var_count: 0,
+            shadowed: Vec::new(),
+            pos: 0,
         }
     }
 
@@ -72,6 +83,8 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: self.total_var_count,
             local_var_count: 0,
+            shadowed: Vec::new(),
+            pos: 0,
         }
     }
 
@@ -79,7 +92,11 @@ impl Scope {
     pub(crate) fn new_var(&mut self, name: &str, span: Span) -> CompileResult<usize> {
         let offset = self.total_var_count;
 
-        let local = Var { offset, span };
+        let local = Var {
+            offset,
+            span,
+            pos: None,
+        };
 
         self.total_var_count += 1;
         self.local_var_count += 1;
@@ -95,13 +112,22 @@ impl Scope {
         Ok(offset)
     }
 
-    /// Insert a new local, and return the old one if there's a conflict.
-    pub(crate) fn decl_var(&mut self, name: &str, span: Span) -> usize {
+    /// Declare a new local which is live from the given instruction position,
+    /// shadowing any existing local with the same name.
+    pub(crate) fn decl_var(&mut self, name: &str, span: Span, pos: usize) -> usize {
         let offset = self.total_var_count;
 
         log::trace!("decl {} => {}", name, offset);
 
-        self.locals.insert(name.to_owned(), Var { offset, span });
+        let var = Var {
+            offset,
+            span,
+            pos: Some(pos),
+        };
+
+        if let Some(old) = self.locals.insert(name.to_owned(), var) {
+            self.shadowed.push((name.to_owned(), old));
+        }
 
         self.total_var_count += 1;
         self.local_var_count += 1;
@@ -148,6 +174,59 @@ impl Scope {
 
         None
     }
+
+    /// Mark the scope as live from the given instruction position.
+    ///
+    /// Variables which were declared before this without a position, like the
+    /// arguments of a function, become live at this position.
+    fn live_at(&mut self, pos: usize) {
+        self.pos = pos;
+
+        let vars = self
+            .locals
+            .values_mut()
+            .chain(self.shadowed.iter_mut().map(|(_, var)| var));
+
+        for var in vars {
+            if var.pos.is_none() {
+                var.pos = Some(pos);
+            }
+        }
+    }
+
+    /// Record debug information on all named variables in the scope, where the
+    /// scope ends at the current instruction of the given assembly.
+    pub(crate) fn debug_variables(&self, asm: &mut Assembly) {
+        let end = asm.pos();
+
+        for (n, (name, var)) in self.shadowed.iter().enumerate() {
+            let start = var.pos.unwrap_or(self.pos);
+
+            // NB: a shadowed variable ends where the variable shadowing it
+            // starts.
+            let shadowed_by = self.shadowed[n + 1..]
+                .iter()
+                .map(|(other, var)| (other, var))
+                .chain(self.locals.get_key_value(name))
+                .find(|(other, _)| *other == name);
+
+            let end = match shadowed_by {
+                Some((_, other)) => other.pos.unwrap_or(end),
+                None => end,
+            };
+
+            asm.variable(name, var.offset, start, end);
+        }
+
+        // NB: locals are ordered by offset so that the unit is the same every
+        // time the same source is compiled.
+        let mut locals = self.locals.iter().collect::<Vec<_>>();
+        locals.sort_by_key(|(_, var)| var.offset);
+
+        for (name, var) in locals {
+            asm.variable(name, var.offset, var.pos.unwrap_or(self.pos), end);
+        }
+    }
 }
 
 /// A guard returned from [push][Scopes::push].
@@ -216,8 +295,10 @@ impl Scopes {
             .ok_or_else(|| CompileError::internal("missing head of locals", span))?)
     }
 
-    /// Push a scope and return an index.
-    pub(crate) fn push(&mut self, scope: Scope) -> ScopeGuard {
+    /// Push a scope which is live from the given instruction position and
+    /// return an index.
+    pub(crate) fn push(&mut self, mut scope: Scope, pos: usize) -> ScopeGuard {
+        scope.live_at(pos);
         self.scopes.push(scope);
         ScopeGuard(self.scopes.len())
     }
@@ -47,7 +47,7 @@ impl Sources {
     }
 
     /// Iterate over all sources in order by index.
-    pub(crate) fn iter(&self) -> impl Iterator<Item = &Source> {
+    pub fn iter(&self) -> impl Iterator<Item = &Source> {
         self.sources.iter().map(|s| &**s)
     }
 }
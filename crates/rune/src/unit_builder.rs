@@ -8,7 +8,7 @@ use crate::ast;
 use crate::collections::HashMap;
 use crate::error::CompileResult;
 use crate::{Resolve as _, Storage};
-use runestick::debug::{DebugArgs, DebugSignature};
+use runestick::debug::{DebugArgs, DebugSignature, DebugVariable};
 use runestick::{
     Call, CompileMeta, Component, Context, DebugInfo, DebugInst, Hash, Inst, Item, Label, Names,
     Source, Span, StaticString, Type, Unit, UnitFn, UnitTypeInfo,
@@ -288,6 +288,22 @@ impl UnitBuilder {
             ImportKey::component("float"),
             ImportEntry::of(&["std", "float"]),
         );
+        this.imports.insert(
+            ImportKey::component("u64"),
+            ImportEntry::of(&["std", "u64"]),
+        );
+        this.imports.insert(
+            ImportKey::component("i128"),
+            ImportEntry::of(&["std", "i128"]),
+        );
+        this.imports.insert(
+            ImportKey::component("u128"),
+            ImportEntry::of(&["std", "u128"]),
+        );
+        this.imports.insert(
+            ImportKey::component("bigint"),
+            ImportEntry::of(&["std", "bigint"]),
+        );
         this.imports.insert(
             ImportKey::component("Object"),
             ImportEntry::of(&["std", "object", "Object"]),
@@ -688,6 +704,7 @@ impl UnitBuilder {
             CompileMeta::Closure { item, .. } => item.clone(),
             CompileMeta::AsyncBlock { item, .. } => item.clone(),
             CompileMeta::Macro { item, .. } => item.clone(),
+            CompileMeta::Constant { item, .. } => item.clone(),
         };
 
         if let Some(existing) = self.meta.insert(item, meta.clone()) {
@@ -767,9 +784,9 @@ impl UnitBuilder {
             });
         }
 
-        self.debug_info_mut()
-            .functions
-            .insert(instance_fn, signature);
+        let debug = self.debug_info_mut();
+        debug.functions.insert(hash, signature.clone());
+        debug.functions.insert(instance_fn, signature);
         self.functions_rev.insert(offset, hash);
         self.add_assembly(source_id, assembly)?;
         Ok(())
@@ -785,6 +802,18 @@ impl UnitBuilder {
 
         self.required_functions.extend(assembly.required_functions);
 
+        let base = self.instructions.len();
+        let debug = self.debug.get_or_insert_with(Default::default);
+
+        for (name, offset, range) in assembly.variables {
+            debug.variables.push(DebugVariable {
+                name,
+                offset,
+                start: base + range.start,
+                end: base + range.end,
+            });
+        }
+
         for (pos, (inst, span)) in assembly.instructions.into_iter().enumerate() {
             let mut comment = None;
             let label = assembly.labels_rev.get(&pos).copied();
@@ -817,6 +846,25 @@ impl UnitBuilder {
                     self.instructions
                         .push(Inst::PopAndJumpIfNot { count, offset });
                 }
+                AssemblyInst::JumpIfNotLt { lhs, rhs, label } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions
+                        .push(Inst::JumpIfNotLt { lhs, rhs, offset });
+                }
+                AssemblyInst::PopAndJumpIfNotInteger {
+                    integer,
+                    count,
+                    label,
+                } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::PopAndJumpIfNotInteger {
+                        integer,
+                        count,
+                        offset,
+                    });
+                }
                 AssemblyInst::Raw { raw } => {
                     self.instructions.push(raw);
                 }
@@ -1,9 +1,9 @@
-use std::cell::Cell;
 use std::fmt;
 use std::future::Future;
 use std::marker;
 use std::ops;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicIsize, Ordering};
 use std::task::{Context, Poll};
 use thiserror::Error;
 
@@ -128,20 +128,22 @@ impl fmt::Display for Snapshot {
 /// `(1 << 62) - 1` uses.
 ///
 /// ```
-#[derive(Clone)]
-pub(crate) struct Access(Cell<isize>);
+///
+/// The flags are atomic, so access can be checked and acquired from multiple
+/// threads at the same time.
+pub(crate) struct Access(AtomicIsize);
 
 impl Access {
     /// Construct a new default access.
     pub(crate) const fn new(is_ref: bool) -> Self {
         let initial = if is_ref { 1 } else { 0 };
-        Self(Cell::new(initial))
+        Self(AtomicIsize::new(initial))
     }
 
     /// Test if access is guarding a reference.
     #[inline]
     pub(crate) fn is_ref(&self) -> bool {
-        self.0.get() & FLAG != 0
+        self.0.load(Ordering::Relaxed) & FLAG != 0
     }
 
     /// Test if we have shared access without modifying the internal count.
@@ -172,26 +174,32 @@ impl Access {
         &self,
         kind: AccessKind,
     ) -> Result<RawSharedGuard, NotAccessibleRef> {
-        if let AccessKind::Owned = kind {
-            if self.is_ref() {
-                return Err(NotAccessibleRef(Snapshot(self.0.get())));
+        let mut current = self.0.load(Ordering::Relaxed);
+
+        loop {
+            if let AccessKind::Owned = kind {
+                if self.is_ref() {
+                    return Err(NotAccessibleRef(Snapshot(current)));
+                }
             }
-        }
 
-        let state = self.get();
+            let state = current >> 1;
 
-        if state == MAX_USES {
-            std::process::abort();
-        }
+            if state == MAX_USES {
+                std::process::abort();
+            }
 
-        let n = state.wrapping_sub(1);
+            let n = state.wrapping_sub(1);
 
-        if n >= 0 {
-            return Err(NotAccessibleRef(Snapshot(self.0.get())));
-        }
+            if n >= 0 {
+                return Err(NotAccessibleRef(Snapshot(current)));
+            }
 
-        self.set(n);
-        Ok(RawSharedGuard { access: self })
+            match self.update(current, n) {
+                Ok(()) => return Ok(RawSharedGuard { access: self }),
+                Err(actual) => current = actual,
+            }
+        }
     }
 
     /// Mark that we want exclusive access to the given access token.
@@ -204,21 +212,24 @@ impl Access {
         &self,
         kind: AccessKind,
     ) -> Result<RawExclusiveGuard, NotAccessibleMut> {
+        let current = self.0.load(Ordering::Relaxed);
+
         if let AccessKind::Owned = kind {
             if self.is_ref() {
-                return Err(NotAccessibleMut(Snapshot(self.0.get())));
+                return Err(NotAccessibleMut(Snapshot(current)));
             }
         }
 
-        let state = self.get();
-        let n = state.wrapping_add(1);
+        let n = (current >> 1).wrapping_add(1);
 
         if n != 1 {
-            return Err(NotAccessibleMut(Snapshot(self.0.get())));
+            return Err(NotAccessibleMut(Snapshot(current)));
         }
 
-        self.set(n);
-        Ok(RawExclusiveGuard { access: self })
+        match self.update(current, n) {
+            Ok(()) => Ok(RawExclusiveGuard { access: self }),
+            Err(actual) => Err(NotAccessibleMut(Snapshot(actual))),
+        }
     }
 
     /// Mark that we want to mark the given access as "taken".
@@ -230,56 +241,61 @@ impl Access {
     /// The returned guard must not outlive the access token that created it.
     #[inline]
     pub(crate) unsafe fn take(&self, kind: AccessKind) -> Result<RawTakeGuard, NotAccessibleTake> {
+        let current = self.0.load(Ordering::Relaxed);
+
         if let AccessKind::Owned = kind {
             if self.is_ref() {
-                return Err(NotAccessibleTake(Snapshot(self.0.get())));
+                return Err(NotAccessibleTake(Snapshot(current)));
             }
         }
 
-        let state = self.get();
-
-        if state != 0 {
-            return Err(NotAccessibleTake(Snapshot(self.0.get())));
+        if current >> 1 != 0 {
+            return Err(NotAccessibleTake(Snapshot(current)));
         }
 
-        self.set(TAKEN);
-        Ok(RawTakeGuard { access: self })
+        match self.update(current, TAKEN) {
+            Ok(()) => Ok(RawTakeGuard { access: self }),
+            Err(actual) => Err(NotAccessibleTake(Snapshot(actual))),
+        }
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_shared(&self) {
-        let b = self.get().wrapping_add(1);
-        debug_assert!(b <= 0);
-        self.set(b);
+        let b = self.0.fetch_add(1 << 1, Ordering::Release) >> 1;
+        debug_assert!(b < 0);
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_exclusive(&self) {
-        let b = self.get().wrapping_sub(1);
-        debug_assert!(b == 0);
-        self.set(b);
+        let b = self.0.fetch_sub(1 << 1, Ordering::Release) >> 1;
+        debug_assert!(b == 1);
     }
 
     /// Untake the current access.
     #[inline]
     fn release_take(&self) {
-        let b = self.get();
+        let b = self.0.fetch_and(FLAG, Ordering::Release) >> 1;
         debug_assert!(b == TAKEN);
-        self.set(0);
     }
 
     /// Get the current value of the flag.
     #[inline]
     fn get(&self) -> isize {
-        self.0.get() >> 1
+        self.0.load(Ordering::Acquire) >> 1
     }
 
-    /// Set the current value of the flag.
+    /// Try to update the flag from the `current` raw value to the given value,
+    /// returning the actual raw value if it has been modified since it was
+    /// read.
     #[inline]
-    fn set(&self, value: isize) {
-        self.0.set(self.0.get() & FLAG | value << 1);
+    fn update(&self, current: isize, value: isize) -> Result<(), isize> {
+        let new = current & FLAG | value << 1;
+
+        self.0
+            .compare_exchange(current, new, Ordering::Acquire, Ordering::Relaxed)
+            .map(|_| ())
     }
 }
 
@@ -313,6 +329,11 @@ pub struct BorrowRef<'a, T: ?Sized + 'a> {
     _marker: marker::PhantomData<&'a T>,
 }
 
+// Safety: a borrow behaves like a reference to the borrowed data, and access
+// is released atomically.
+unsafe impl<T: ?Sized + Sync> Send for BorrowRef<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for BorrowRef<'_, T> {}
+
 impl<'a, T: ?Sized> BorrowRef<'a, T> {
     /// Construct a new raw reference guard.
     ///
@@ -399,6 +420,11 @@ pub struct BorrowMut<'a, T: ?Sized> {
     _marker: marker::PhantomData<&'a mut T>,
 }
 
+// Safety: a borrow behaves like a mutable reference to the borrowed data, and
+// access is released atomically.
+unsafe impl<T: ?Sized + Send> Send for BorrowMut<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for BorrowMut<'_, T> {}
+
 impl<'a, T: ?Sized> BorrowMut<'a, T> {
     /// Construct a new raw reference guard.
     ///
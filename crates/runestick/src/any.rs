@@ -14,7 +14,10 @@
 ///     name: String,
 /// }
 /// ```
-pub trait Any: std::any::Any {
+///
+/// Since values can be sent between threads, types stored in the VM must be
+/// both `Send` and `Sync`.
+pub trait Any: std::any::Any + Send + Sync {
     /// The name of the type.
     const NAME: &'static str;
 }
@@ -16,6 +16,11 @@ pub struct AnyObj {
     data: *const (),
 }
 
+// Safety: all constructors require the wrapped data to implement [Any], which
+// requires it to be both `Send` and `Sync`.
+unsafe impl Send for AnyObj {}
+unsafe impl Sync for AnyObj {}
+
 impl fmt::Debug for AnyObj {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "AnyObj({})", self.type_name())
@@ -80,7 +85,8 @@ impl AnyObj {
     ///
     /// ### Safety
     /// The caller must ensure that the vtable matches up with the data pointer
-    /// provided. This is primarily public for use in a C ffi.
+    /// provided, and that the data is safe to send and share between threads.
+    /// This is primarily public for use in a C ffi.
     pub unsafe fn new_raw(vtable: &'static AnyObjVtable, data: *const ()) -> Self {
         Self { vtable, data }
     }
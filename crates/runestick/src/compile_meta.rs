@@ -82,6 +82,13 @@ pub enum CompileMeta {
         /// The item of the macro.
         item: Item,
     },
+    /// A constant provided by a native module.
+    Constant {
+        /// The value type associated with this meta item.
+        type_of: Type,
+        /// The item of the constant.
+        item: Item,
+    },
 }
 
 impl CompileMeta {
@@ -97,6 +104,7 @@ impl CompileMeta {
             CompileMeta::Closure { item, .. } => item,
             CompileMeta::AsyncBlock { item, .. } => item,
             CompileMeta::Macro { item, .. } => item,
+            CompileMeta::Constant { item, .. } => item,
         }
     }
 
@@ -112,6 +120,7 @@ impl CompileMeta {
             Self::Closure { type_of, .. } => Some(*type_of),
             Self::AsyncBlock { type_of, .. } => Some(*type_of),
             Self::Macro { .. } => None,
+            Self::Constant { type_of, .. } => Some(*type_of),
         }
     }
 }
@@ -146,6 +155,9 @@ impl fmt::Display for CompileMeta {
             Self::Macro { item, .. } => {
                 write!(fmt, "macro {}", item)?;
             }
+            Self::Constant { item, .. } => {
+                write!(fmt, "const {}", item)?;
+            }
         }
 
         Ok(())
@@ -0,0 +1,445 @@
+//! Hashing of the serialized content of a value.
+//!
+//! This is used to identify things like units in a way which is stable across
+//! processes, which rules out hashing the in-memory representation since hash
+//! maps don't have a stable iteration order.
+
+use crate::Hash;
+use serde::ser;
+use std::fmt;
+use std::hash::Hasher as _;
+use twox_hash::XxHash64;
+
+/// Calculate the hash of the serialized content of the given value.
+///
+/// Maps are hashed independently of the order of their entries.
+pub(crate) fn content_hash<T>(value: &T) -> Result<Hash, ContentHashError>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut hasher = ContentHasher::default();
+    value.serialize(&mut hasher)?;
+    Ok(Hash::new(hasher.finish()))
+}
+
+/// Error raised when the content of a value can't be hashed.
+#[derive(Debug)]
+pub(crate) struct ContentHashError(String);
+
+impl fmt::Display for ContentHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for ContentHashError {}
+
+impl ser::Error for ContentHashError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+/// Tags written before each kind of value, to make sure that different
+/// structures with the same content hash differently.
+#[repr(u8)]
+enum Tag {
+    Bool = 1,
+    Integer,
+    Float,
+    Char,
+    Str,
+    Bytes,
+    None,
+    Some,
+    Unit,
+    Variant,
+    Seq,
+    Map,
+    Struct,
+}
+
+#[derive(Default)]
+struct ContentHasher {
+    hasher: XxHash64,
+}
+
+impl ContentHasher {
+    fn tag(&mut self, tag: Tag) {
+        self.hasher.write_u8(tag as u8);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+}
+
+/// A compound value being hashed.
+struct Compound<'a> {
+    hasher: &'a mut ContentHasher,
+    /// Combined hashes of the entries of a map, if this is a map.
+    entries: u64,
+    /// The number of entries in a map, if this is a map.
+    len: u64,
+    /// The hasher of the current map entry.
+    entry: Option<ContentHasher>,
+}
+
+impl<'a> Compound<'a> {
+    fn new(hasher: &'a mut ContentHasher) -> Self {
+        Self {
+            hasher,
+            entries: 0,
+            len: 0,
+            entry: None,
+        }
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut ContentHasher {
+    type Ok = ();
+    type Error = ContentHashError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.tag(Tag::Bool);
+        self.hasher.write_u8(v as u8);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.tag(Tag::Integer);
+        self.hasher.write_i64(v);
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        self.tag(Tag::Integer);
+        self.hasher.write_i128(v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.tag(Tag::Integer);
+        self.hasher.write_u64(v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> {
+        self.tag(Tag::Integer);
+        self.hasher.write_u128(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.tag(Tag::Float);
+        self.hasher.write_u64(v.to_bits());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> {
+        self.tag(Tag::Char);
+        self.hasher.write_u32(v as u32);
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.tag(Tag::Str);
+        self.hasher.write_usize(v.len());
+        self.hasher.write(v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.tag(Tag::Bytes);
+        self.hasher.write_usize(v.len());
+        self.hasher.write(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.tag(Tag::None);
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.tag(Tag::Some);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.tag(Tag::Unit);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Self::Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.tag(Tag::Variant);
+        self.hasher.write_u32(variant_index);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.tag(Tag::Variant);
+        self.hasher.write_u32(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.tag(Tag::Seq);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.tag(Tag::Seq);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.tag(Tag::Struct);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.tag(Tag::Variant);
+        self.hasher.write_u32(variant_index);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.tag(Tag::Map);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.tag(Tag::Struct);
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        variant_index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.tag(Tag::Variant);
+        self.hasher.write_u32(variant_index);
+        Ok(Compound::new(self))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(&mut *self.hasher)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.hasher.tag(Tag::Seq);
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = ContentHashError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let mut entry = ContentHasher::default();
+        key.serialize(&mut entry)?;
+        self.entry = Some(entry);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let mut entry = self
+            .entry
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value serialized without a key"))?;
+
+        value.serialize(&mut entry)?;
+
+        // NB: entries are combined with a commutative operation, since the
+        // order of entries in a map isn't stable.
+        self.entries = self.entries.wrapping_add(entry.finish());
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.hasher.hasher.write_u64(self.len);
+        self.hasher.hasher.write_u64(self.entries);
+        self.hasher.tag(Tag::Map);
+        Ok(())
+    }
+}
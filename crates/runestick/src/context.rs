@@ -1,4 +1,5 @@
 use crate::collections::{HashMap, HashSet};
+use crate::content_hash;
 use crate::module::{
     ModuleAssociatedFn, ModuleFn, ModuleInternalEnum, ModuleMacro, ModuleType, ModuleUnitType,
 };
@@ -97,11 +98,12 @@ pub enum ContextError {
 }
 
 /// A function handler.
-pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Sync;
+pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync;
 
 /// A (type erased) macro handler.
-pub(crate) type Macro =
-    dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error> + Sync;
+pub(crate) type Macro = dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error>
+    + Send
+    + Sync;
 
 /// Information on a specific type.
 #[derive(Debug, Clone)]
@@ -241,9 +243,13 @@ impl Context {
         this.install(&crate::modules::core::module()?)?;
         this.install(&crate::modules::generator::module()?)?;
         this.install(&crate::modules::bytes::module()?)?;
+        this.install(&crate::modules::collections::module()?)?;
         this.install(&crate::modules::string::module()?)?;
+        this.install(&crate::modules::char::module()?)?;
         this.install(&crate::modules::int::module()?)?;
         this.install(&crate::modules::float::module()?)?;
+        this.install(&crate::modules::wide_int::module()?)?;
+        this.install(&crate::modules::math::module()?)?;
         this.install(&crate::modules::test::module()?)?;
         this.install(&crate::modules::iter::module()?)?;
         this.install(&crate::modules::vec::module()?)?;
@@ -254,6 +260,7 @@ impl Context {
         this.install(&crate::modules::stream::module()?)?;
         this.install(&crate::modules::io::module()?)?;
         this.install(&crate::modules::fmt::module()?)?;
+        this.install(&crate::modules::weak::module()?)?;
         this.has_default_modules = true;
         Ok(this)
     }
@@ -300,6 +307,11 @@ impl Context {
         self.macros.get(&hash)
     }
 
+    /// Lookup the signature of the function with the given hash.
+    pub fn lookup_signature(&self, hash: Hash) -> Option<&ContextSignature> {
+        self.functions_info.get(&hash)
+    }
+
     /// Access the meta for the given language item.
     pub fn lookup_meta(&self, name: &Item) -> Option<CompileMeta> {
         self.meta.get(name).cloned()
@@ -325,6 +337,32 @@ impl Context {
         })
     }
 
+    /// Calculate a fingerprint of everything installed in the context.
+    ///
+    /// The fingerprint is stable across processes, and changes whenever a
+    /// function, macro or type is added, removed or changes signature. It's
+    /// used to tell if a unit was compiled against a different context.
+    pub fn fingerprint(&self) -> Hash {
+        let mut entries = Vec::new();
+
+        for hash in self.functions.keys() {
+            let signature = self.functions_info.get(hash).map(ToString::to_string);
+            entries.push((0u8, *hash, signature));
+        }
+
+        for hash in self.macros.keys() {
+            entries.push((1u8, *hash, None));
+        }
+
+        for (hash, ty) in &self.types {
+            entries.push((2u8, *hash, Some(ty.to_string())));
+        }
+
+        entries.sort();
+        // NB: nothing in the entries fails to serialize.
+        content_hash::content_hash(&entries).expect("entries can always be hashed")
+    }
+
     /// Install the specified module.
     pub fn install(&mut self, module: &Module) -> Result<(), ContextError> {
         for (type_of, ty) in &module.types {
@@ -335,6 +373,10 @@ impl Context {
             self.install_function(&module, name, f)?;
         }
 
+        for (name, c) in &module.constants {
+            self.install_constant(module, name, c)?;
+        }
+
         for (name, m) in &module.macros {
             self.install_macro(&module, name, m)?;
         }
@@ -460,6 +502,43 @@ impl Context {
         Ok(())
     }
 
+    /// Install a constant and check for duplicates.
+    fn install_constant(
+        &mut self,
+        module: &Module,
+        name: &Item,
+        c: &ModuleFn,
+    ) -> Result<(), ContextError> {
+        let name = module.path.join(name);
+        self.names.insert(&name);
+
+        let hash = Hash::type_hash(&name);
+
+        let signature = ContextSignature::Function {
+            path: name.clone(),
+            args: c.args,
+        };
+
+        if let Some(old) = self.functions_info.insert(hash, signature) {
+            return Err(ContextError::ConflictingFunction {
+                signature: old,
+                hash,
+            });
+        }
+
+        self.functions.insert(hash, c.handler.clone());
+
+        self.install_meta(
+            name.clone(),
+            CompileMeta::Constant {
+                type_of: Type::from(hash),
+                item: name,
+            },
+        )?;
+
+        Ok(())
+    }
+
     /// Install a function and check for duplicates.
     fn install_macro(
         &mut self,
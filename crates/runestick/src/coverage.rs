@@ -0,0 +1,268 @@
+//! Line and branch coverage recorded while running a virtual machine.
+
+use crate::collections::HashMap;
+use crate::source;
+use crate::{Inst, Source, Unit};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A handle to coverage being recorded by a virtual machine.
+///
+/// The handle is cheap to clone, and all clones refer to the same recorded
+/// coverage. See [Vm::enable_coverage][crate::Vm::enable_coverage].
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Number of times each instruction has been executed, by instruction
+    /// pointer.
+    instructions: Vec<usize>,
+    /// Number of times each conditional jump jumped and didn't jump, by
+    /// instruction pointer.
+    branches: HashMap<usize, [usize; 2]>,
+}
+
+impl Coverage {
+    /// Construct a new empty coverage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that the instruction at the given instruction pointer was
+    /// executed.
+    pub(crate) fn hit(&self, ip: usize) {
+        let mut inner = self.lock();
+
+        if inner.instructions.len() <= ip {
+            inner.instructions.resize(ip + 1, 0);
+        }
+
+        inner.instructions[ip] += 1;
+    }
+
+    /// Record the outcome of the conditional jump at the given instruction
+    /// pointer.
+    pub(crate) fn branch(&self, ip: usize, jumped: bool) {
+        let mut inner = self.lock();
+        let branch = inner.branches.entry(ip).or_default();
+        branch[if jumped { 0 } else { 1 }] += 1;
+    }
+
+    /// Get the number of times the instruction at the given instruction
+    /// pointer was executed.
+    pub fn instruction_hits(&self, ip: usize) -> usize {
+        self.lock()
+            .instructions
+            .get(ip)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the number of times the conditional jump at the given instruction
+    /// pointer jumped and didn't jump, in that order.
+    pub fn branch_hits(&self, ip: usize) -> (usize, usize) {
+        let [jumped, not_jumped] = self.lock().branches.get(&ip).copied().unwrap_or_default();
+        (jumped, not_jumped)
+    }
+
+    /// Map the recorded coverage back to lines in the given sources, using
+    /// the debug info of the given unit.
+    ///
+    /// Every instruction in the unit which belongs to one of the sources is
+    /// included, so code which never ran is reported as such.
+    pub fn report<'a, I>(&self, unit: &Unit, sources: I) -> Vec<SourceCoverage>
+    where
+        I: IntoIterator<Item = (usize, &'a Source)>,
+    {
+        let debug = match unit.debug_info() {
+            Some(debug) => debug,
+            None => return Vec::new(),
+        };
+
+        let mut reports = Vec::new();
+        let mut lookup = HashMap::new();
+
+        for (source_id, source) in sources {
+            lookup.insert(source_id, (reports.len(), source.line_starts()));
+
+            reports.push(SourceCoverage {
+                source_id,
+                name: source.name().to_owned(),
+                path: source.path().map(ToOwned::to_owned),
+                lines: BTreeMap::new(),
+                branches: Vec::new(),
+                functions: Vec::new(),
+            });
+        }
+
+        for (ip, inst) in unit.iter_instructions().enumerate() {
+            let debug_inst = match debug.instruction_at(ip) {
+                Some(debug_inst) => debug_inst,
+                None => continue,
+            };
+
+            let (index, line_starts) = match lookup.get(&debug_inst.source_id) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let report = &mut reports[*index];
+            let line = source::line_of(line_starts, debug_inst.span.start);
+            let hits = self.instruction_hits(ip);
+
+            let line_hits = report.lines.entry(line).or_default();
+            *line_hits = usize::max(*line_hits, hits);
+
+            if let Inst::JumpIf { .. }
+            | Inst::JumpIfNot { .. }
+            | Inst::JumpIfNotLt { .. }
+            | Inst::PopAndJumpIfNot { .. }
+            | Inst::PopAndJumpIfNotInteger { .. } = inst
+            {
+                let (jumped, not_jumped) = self.branch_hits(ip);
+
+                report.branches.push(BranchCoverage {
+                    line,
+                    ip,
+                    executed: hits > 0,
+                    jumped,
+                    not_jumped,
+                });
+            }
+
+            if let Some((_, signature)) = debug.function_at(ip) {
+                report.functions.push(FunctionCoverage {
+                    name: signature.path.to_string(),
+                    line,
+                    hits,
+                });
+            }
+        }
+
+        reports
+    }
+
+    /// Write the recorded coverage for the given sources in the LCOV tracefile
+    /// format.
+    pub fn write_lcov<'a, O, I>(&self, mut out: O, unit: &Unit, sources: I) -> io::Result<()>
+    where
+        O: io::Write,
+        I: IntoIterator<Item = (usize, &'a Source)>,
+    {
+        for report in self.report(unit, sources) {
+            writeln!(out, "TN:")?;
+
+            match &report.path {
+                Some(path) => writeln!(out, "SF:{}", path.display())?,
+                None => writeln!(out, "SF:{}", report.name)?,
+            }
+
+            for f in &report.functions {
+                writeln!(out, "FN:{},{}", f.line + 1, f.name)?;
+            }
+
+            for f in &report.functions {
+                writeln!(out, "FNDA:{},{}", f.hits, f.name)?;
+            }
+
+            writeln!(out, "FNF:{}", report.functions.len())?;
+            writeln!(
+                out,
+                "FNH:{}",
+                report.functions.iter().filter(|f| f.hits > 0).count()
+            )?;
+
+            let mut branches_hit = 0;
+
+            for b in &report.branches {
+                for (n, taken) in [b.jumped, b.not_jumped].iter().enumerate() {
+                    if !b.executed {
+                        writeln!(out, "BRDA:{},{},{},-", b.line + 1, b.ip, n)?;
+                        continue;
+                    }
+
+                    if *taken > 0 {
+                        branches_hit += 1;
+                    }
+
+                    writeln!(out, "BRDA:{},{},{},{}", b.line + 1, b.ip, n, taken)?;
+                }
+            }
+
+            writeln!(out, "BRF:{}", report.branches.len() * 2)?;
+            writeln!(out, "BRH:{}", branches_hit)?;
+
+            for (line, hits) in &report.lines {
+                writeln!(out, "DA:{},{}", line + 1, hits)?;
+            }
+
+            writeln!(out, "LF:{}", report.lines.len())?;
+            writeln!(
+                out,
+                "LH:{}",
+                report.lines.values().filter(|hits| **hits > 0).count()
+            )?;
+            writeln!(out, "end_of_record")?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // NB: recording coverage can't panic while holding the lock, so it is
+        // never poisoned in a way which matters.
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(error) => error.into_inner(),
+        }
+    }
+}
+
+/// Coverage for a single source.
+#[derive(Debug, Clone)]
+pub struct SourceCoverage {
+    /// The id of the source.
+    pub source_id: usize,
+    /// The name of the source.
+    pub name: String,
+    /// The path of the source, if it has one.
+    pub path: Option<PathBuf>,
+    /// The number of times each line with instructions on it was executed,
+    /// where the first line is `0`.
+    pub lines: BTreeMap<usize, usize>,
+    /// Coverage of each conditional jump in the source.
+    pub branches: Vec<BranchCoverage>,
+    /// Coverage of each function in the source.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+/// Coverage of a single conditional jump.
+#[derive(Debug, Clone)]
+pub struct BranchCoverage {
+    /// The line of the jump, where the first line is `0`.
+    pub line: usize,
+    /// The instruction pointer of the jump.
+    pub ip: usize,
+    /// If the jump was ever executed.
+    pub executed: bool,
+    /// The number of times the jump was taken.
+    pub jumped: usize,
+    /// The number of times the jump was not taken.
+    pub not_jumped: usize,
+}
+
+/// Coverage of a single function.
+#[derive(Debug, Clone)]
+pub struct FunctionCoverage {
+    /// The name of the function.
+    pub name: String,
+    /// The line the function starts on, where the first line is `0`.
+    pub line: usize,
+    /// The number of times the function was called.
+    pub hits: usize,
+}
@@ -14,6 +14,8 @@ pub struct DebugInfo {
     pub functions: HashMap<Hash, DebugSignature>,
     /// Reverse lookup of a function.
     pub functions_rev: HashMap<usize, Hash>,
+    /// Named variables and the ranges of instructions they are live for.
+    pub variables: Vec<DebugVariable>,
 }
 
 impl DebugInfo {
@@ -28,6 +30,26 @@ impl DebugInfo {
         let signature = self.functions.get(&hash)?;
         Some((hash, signature))
     }
+
+    /// Get the function which contains the given instruction pointer.
+    pub fn function_containing(&self, ip: usize) -> Option<(Hash, &DebugSignature)> {
+        let (_, hash) = self
+            .functions_rev
+            .iter()
+            .filter(|(offset, _)| **offset <= ip)
+            .max_by_key(|(offset, _)| **offset)?;
+
+        let signature = self.functions.get(hash)?;
+        Some((*hash, signature))
+    }
+
+    /// Iterate over all named variables which are live at the given
+    /// instruction pointer.
+    pub fn variables_at(&self, ip: usize) -> impl Iterator<Item = &DebugVariable> + '_ {
+        self.variables
+            .iter()
+            .filter(move |v| v.start <= ip && ip < v.end)
+    }
 }
 
 /// Debug information for every instruction.
@@ -43,8 +65,23 @@ pub struct DebugInst {
     pub label: Option<DebugLabel>,
 }
 
-/// Debug information on function arguments.
+/// Debug information on a named variable.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct DebugVariable {
+    /// The name of the variable.
+    pub name: String,
+    /// The offset of the variable relative to the bottom of the stack of the
+    /// function it is declared in.
+    pub offset: usize,
+    /// The first instruction at which the variable is live.
+    pub start: usize,
+    /// The instruction right after the last instruction at which the variable
+    /// is live.
+    pub end: usize,
+}
+
+/// Debug information on function arguments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DebugArgs {
     /// A tuple, with the given number of arguments.
     TupleArgs(usize),
@@ -53,7 +90,7 @@ pub enum DebugArgs {
 }
 
 /// A description of a function signature.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugSignature {
     /// The path of the function.
     pub path: Item,
@@ -0,0 +1,371 @@
+//! A debugger for driving a virtual machine through breakpoints and stepping.
+
+use crate::collections::HashMap;
+use crate::source;
+use crate::{Hash, IntoHash, Source, Value, Vm, VmError, VmExecution};
+
+/// A breakpoint which can be added to a [Debugger].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Break when execution enters the given line, where the first line is
+    /// `0`.
+    Line {
+        /// The source id of the line.
+        source_id: usize,
+        /// The line to break on.
+        line: usize,
+    },
+    /// Break when the function with the given hash is entered.
+    Function {
+        /// The hash of the function.
+        hash: Hash,
+    },
+}
+
+impl Breakpoint {
+    /// Construct a breakpoint on the given line in the given source.
+    pub fn line(source_id: usize, line: usize) -> Self {
+        Self::Line { source_id, line }
+    }
+
+    /// Construct a breakpoint on the function with the given name.
+    pub fn function<N>(name: N) -> Self
+    where
+        N: IntoHash,
+    {
+        Self::Function {
+            hash: name.into_hash(),
+        }
+    }
+}
+
+/// How to step through the program when resuming it through a [Debugger].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Continue until a breakpoint is hit or the execution completes.
+    Continue,
+    /// Step a single instruction.
+    Instruction,
+    /// Step until execution reaches a different line, entering any functions
+    /// being called.
+    Into,
+    /// Step until execution reaches a different line in the current function,
+    /// or the function returns.
+    Over,
+    /// Step until the current function returns.
+    Out,
+}
+
+/// The reason why a [Debugger] stopped.
+#[derive(Debug)]
+pub enum DebugStop {
+    /// A breakpoint with the given id was hit.
+    Breakpoint {
+        /// The id of the breakpoint.
+        id: usize,
+    },
+    /// The requested step completed.
+    Step,
+    /// The execution completed with the given value.
+    Complete {
+        /// The value produced by the execution.
+        value: Value,
+    },
+}
+
+/// A location in the source code of a program, as it is being debugged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugLocation {
+    /// The source id of the location.
+    pub source_id: usize,
+    /// The line of the location, where the first line is `0`.
+    pub line: usize,
+}
+
+/// A single frame in the call stack of a virtual machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DebugFrame {
+    /// The instruction pointer of the frame. For frames other than the
+    /// innermost one this is the instruction which performed the call.
+    pub ip: usize,
+    /// The bottom of the stack for the frame.
+    pub stack_bottom: usize,
+}
+
+/// A debugger for an execution.
+///
+/// The debugger uses the debug information of the unit being executed to map
+/// instructions to lines and functions, and to look up named variables.
+#[derive(Debug, Default)]
+pub struct Debugger {
+    /// The byte offset of each line, by source id.
+    lines: HashMap<usize, Vec<usize>>,
+    /// Registered breakpoints, by id.
+    breakpoints: Vec<Option<Breakpoint>>,
+}
+
+impl Debugger {
+    /// Construct a new debugger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a source with the given id, making it possible to resolve line
+    /// information for it.
+    pub fn insert_source(&mut self, source_id: usize, source: &Source) {
+        self.lines.insert(source_id, source.line_starts());
+    }
+
+    /// Add a breakpoint and return its id.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        let id = self.breakpoints.len();
+        self.breakpoints.push(Some(breakpoint));
+        id
+    }
+
+    /// Remove the breakpoint with the given id, returning it if it existed.
+    pub fn remove_breakpoint(&mut self, id: usize) -> Option<Breakpoint> {
+        self.breakpoints.get_mut(id)?.take()
+    }
+
+    /// Iterate over all registered breakpoints and their ids.
+    pub fn breakpoints(&self) -> impl Iterator<Item = (usize, &Breakpoint)> + '_ {
+        self.breakpoints
+            .iter()
+            .enumerate()
+            .filter_map(|(id, b)| Some((id, b.as_ref()?)))
+    }
+
+    /// Get the source location of the current instruction of the given
+    /// virtual machine.
+    ///
+    /// This requires that the unit has debug info, and that the source it
+    /// belongs to has been registered with
+    /// [insert_source][Self::insert_source].
+    pub fn location(&self, vm: &Vm) -> Option<DebugLocation> {
+        self.location_at(vm, vm.ip())
+    }
+
+    /// Get the source location of the instruction at the given instruction
+    /// pointer.
+    pub fn location_at(&self, vm: &Vm, ip: usize) -> Option<DebugLocation> {
+        let inst = vm.unit().debug_info()?.instruction_at(ip)?;
+        let lines = self.lines.get(&inst.source_id)?;
+
+        Some(DebugLocation {
+            source_id: inst.source_id,
+            line: source::line_of(lines, inst.span.start),
+        })
+    }
+
+    /// Get the call stack of the given virtual machine, starting with the
+    /// innermost frame.
+    pub fn frames(vm: &Vm) -> Vec<DebugFrame> {
+        let mut frames = vec![DebugFrame {
+            ip: vm.ip(),
+            stack_bottom: vm.stack().stack_bottom(),
+        }];
+
+        for frame in vm.call_frames().iter().rev() {
+            frames.push(DebugFrame {
+                ip: frame.ip(),
+                stack_bottom: frame.stack_bottom(),
+            });
+        }
+
+        frames
+    }
+
+    /// Get all named variables which are live in the current function of the
+    /// given virtual machine, together with their values.
+    pub fn locals(vm: &Vm) -> Vec<(String, Value)> {
+        Self::frame_locals(
+            vm,
+            DebugFrame {
+                ip: vm.ip(),
+                stack_bottom: vm.stack().stack_bottom(),
+            },
+        )
+    }
+
+    /// Get all named variables which are live in the given frame, together
+    /// with their values.
+    pub fn frame_locals(vm: &Vm, frame: DebugFrame) -> Vec<(String, Value)> {
+        let debug = match vm.unit().debug_info() {
+            Some(debug) => debug,
+            None => return Vec::new(),
+        };
+
+        let mut locals = Vec::new();
+
+        for var in debug.variables_at(frame.ip) {
+            if let Some(value) = vm.stack().get(frame.stack_bottom + var.offset) {
+                locals.push((var.name.clone(), value.clone()));
+            }
+        }
+
+        locals.sort_by(|a, b| a.0.cmp(&b.0));
+        locals
+    }
+
+    /// Get the value of the named variable which is live in the current
+    /// function of the given virtual machine.
+    pub fn local(vm: &Vm, name: &str) -> Option<Value> {
+        let debug = vm.unit().debug_info()?;
+
+        // NB: later declarations shadow earlier ones.
+        let var = debug
+            .variables_at(vm.ip())
+            .filter(|var| var.name == name)
+            .max_by_key(|var| var.start)?;
+
+        let stack = vm.stack();
+        stack.get(stack.stack_bottom() + var.offset).cloned()
+    }
+
+    /// Resume the given execution, stopping according to the given `step` or
+    /// when a breakpoint is hit.
+    ///
+    /// If any async instructions are encountered, this will error.
+    pub fn resume(
+        &mut self,
+        execution: &mut VmExecution,
+        step: Step,
+    ) -> Result<DebugStop, VmError> {
+        let mut state = StepState::new(self, execution, step)?;
+
+        loop {
+            if let Some(value) = execution.step()? {
+                return Ok(DebugStop::Complete { value });
+            }
+
+            if let Some(stop) = state.check(self, execution)? {
+                return Ok(stop);
+            }
+        }
+    }
+
+    /// Resume the given execution with support for async instructions,
+    /// stopping according to the given `step` or when a breakpoint is hit.
+    pub async fn async_resume(
+        &mut self,
+        execution: &mut VmExecution,
+        step: Step,
+    ) -> Result<DebugStop, VmError> {
+        let mut state = StepState::new(self, execution, step)?;
+
+        loop {
+            if let Some(value) = execution.async_step().await? {
+                return Ok(DebugStop::Complete { value });
+            }
+
+            if let Some(stop) = state.check(self, execution)? {
+                return Ok(stop);
+            }
+        }
+    }
+
+    /// Find the first breakpoint which is hit at the current instruction.
+    ///
+    /// Line breakpoints are only hit when the line is `entered`, and function
+    /// breakpoints only when the function was just `called`, so that jumping
+    /// back to the start of a line or function doesn't hit them again.
+    fn breakpoint_hit(
+        &self,
+        vm: &Vm,
+        location: Option<DebugLocation>,
+        entered: bool,
+        called: bool,
+    ) -> Option<usize> {
+        let debug = vm.unit().debug_info()?;
+
+        for (id, breakpoint) in self.breakpoints() {
+            match breakpoint {
+                Breakpoint::Line { source_id, line } => {
+                    if !entered {
+                        continue;
+                    }
+
+                    if let Some(location) = location {
+                        if location.source_id == *source_id && location.line == *line {
+                            return Some(id);
+                        }
+                    }
+                }
+                Breakpoint::Function { hash } => {
+                    if called && debug.functions_rev.get(&vm.ip()) == Some(hash) {
+                        return Some(id);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// The state of a single resume operation.
+struct StepState {
+    step: Step,
+    /// The call depth where the step started.
+    depth: usize,
+    /// The location where the step started.
+    location: Option<DebugLocation>,
+    /// The call depth of the previous instruction.
+    last_depth: usize,
+    /// The location of the previous instruction.
+    last_location: Option<DebugLocation>,
+}
+
+impl StepState {
+    fn new(debugger: &Debugger, execution: &VmExecution, step: Step) -> Result<Self, VmError> {
+        let depth = execution.call_depth();
+        let location = debugger.location(execution.vm()?);
+
+        Ok(Self {
+            step,
+            depth,
+            location,
+            last_depth: depth,
+            last_location: location,
+        })
+    }
+
+    /// Check if execution should stop at the current instruction.
+    fn check(
+        &mut self,
+        debugger: &Debugger,
+        execution: &VmExecution,
+    ) -> Result<Option<DebugStop>, VmError> {
+        let vm = execution.vm()?;
+        let depth = execution.call_depth();
+        let location = debugger.location(vm);
+
+        let entered =
+            location.is_some() && (location != self.last_location || depth != self.last_depth);
+        let called = depth > self.last_depth;
+
+        self.last_depth = depth;
+        self.last_location = location;
+
+        if let Some(id) = debugger.breakpoint_hit(vm, location, entered, called) {
+            return Ok(Some(DebugStop::Breakpoint { id }));
+        }
+
+        let stop = match self.step {
+            Step::Continue => false,
+            Step::Instruction => true,
+            Step::Into => entered && (location != self.location || depth != self.depth),
+            Step::Over => {
+                depth < self.depth || depth == self.depth && entered && location != self.location
+            }
+            Step::Out => depth < self.depth,
+        };
+
+        if stop {
+            return Ok(Some(DebugStop::Step));
+        }
+
+        Ok(None)
+    }
+}
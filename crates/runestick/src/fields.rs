@@ -0,0 +1,147 @@
+use crate::{Object, Shape, Value};
+use std::borrow;
+use std::fmt;
+use std::hash;
+use std::sync::Arc;
+
+/// The fields of a struct, stored in slots laid out according to a [Shape].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Fields, Shape, Value};
+/// use std::sync::Arc;
+///
+/// let shape = Arc::new(Shape::new(vec![String::from("a"), String::from("b")]));
+/// let fields = Fields::new(shape, vec![Value::Integer(1), Value::Integer(2)]);
+///
+/// assert!(matches!(fields.get("b"), Some(Value::Integer(2))));
+/// assert!(matches!(fields.get_index(0), Some(Value::Integer(1))));
+/// ```
+#[derive(Clone)]
+pub struct Fields {
+    shape: Arc<Shape>,
+    values: Box<[Value]>,
+}
+
+impl Fields {
+    /// Construct fields out of a shape, and values in the slot order of the
+    /// shape.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the number of fields in
+    /// the shape.
+    pub fn new(shape: Arc<Shape>, values: Vec<Value>) -> Self {
+        assert_eq!(
+            shape.len(),
+            values.len(),
+            "number of values must match the shape"
+        );
+
+        Self {
+            shape,
+            values: values.into_boxed_slice(),
+        }
+    }
+
+    /// Get the shape of the fields.
+    pub fn shape(&self) -> &Arc<Shape> {
+        &self.shape
+    }
+
+    /// Get the number of fields.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Test if there are no fields.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the value of the given field.
+    pub fn get<Q>(&self, key: &Q) -> Option<&Value>
+    where
+        Q: ?Sized + hash::Hash + Eq,
+        String: borrow::Borrow<Q>,
+    {
+        self.values.get(self.shape.index(key)?)
+    }
+
+    /// Get the value of the given field mutably.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut Value>
+    where
+        Q: ?Sized + hash::Hash + Eq,
+        String: borrow::Borrow<Q>,
+    {
+        self.values.get_mut(self.shape.index(key)?)
+    }
+
+    /// Get the value of the field in the given slot.
+    pub fn get_index(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)
+    }
+
+    /// Get the value of the field in the given slot mutably.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+        self.values.get_mut(index)
+    }
+
+    /// Test if the given field exists.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + hash::Hash + Eq,
+        String: borrow::Borrow<Q>,
+    {
+        self.shape.index(key).is_some()
+    }
+
+    /// Iterate over the names and values of the fields, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> + '_ {
+        self.shape.keys().iter().zip(self.values.iter())
+    }
+
+    /// Get the values of the fields, in slot order.
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Get the values of the fields mutably, in slot order.
+    pub fn values_mut(&mut self) -> &mut [Value] {
+        &mut self.values
+    }
+
+    /// Copy the fields into an object.
+    pub fn to_object(&self) -> Object {
+        let mut object = Object::with_capacity(self.len());
+
+        for (key, value) in self.iter() {
+            object.insert(key.clone(), value.clone());
+        }
+
+        object
+    }
+}
+
+impl fmt::Debug for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Construct fields from an object, with a new shape where the fields are
+/// sorted by name.
+impl From<Object> for Fields {
+    fn from(object: Object) -> Self {
+        let mut entries = object.into_iter().collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+        Self {
+            shape: Arc::new(Shape::new(keys)),
+            values: values.into_boxed_slice(),
+        }
+    }
+}
@@ -20,7 +20,7 @@ pub trait UnsafeFromValue: Sized {
     ///
     /// Must only be dropped *after* the value returned from this function is
     /// no longer live.
-    type Guard: 'static;
+    type Guard: 'static + Send;
 
     /// Convert the given reference using unsafe assumptions to a value.
     ///
@@ -397,41 +397,34 @@ impl FromValue for char {
     }
 }
 
-impl FromValue for i64 {
-    fn from_value(value: Value) -> Result<Self, VmError> {
-        Ok(value.into_integer()?)
-    }
-}
-
 macro_rules! impl_number {
-    ($ty:ty, $variant:ident) => {
+    ($ty:ty) => {
         impl FromValue for $ty {
             fn from_value(value: Value) -> Result<Self, VmError> {
-                use std::convert::TryInto as _;
-                let integer = value.into_integer()?;
-
-                match integer.try_into() {
-                    Ok(number) => Ok(number),
-                    Err(..) => Err($crate::VmError::from(
-                        $crate::VmErrorKind::ValueToIntegerCoercionError {
-                            from: $crate::VmIntegerRepr::I64(integer),
-                            to: std::any::type_name::<Self>(),
-                        },
-                    )),
-                }
+                crate::numeric::from_value(value)
             }
         }
     };
 }
 
-impl_number!(u32, U32);
-impl_number!(u64, U64);
-impl_number!(u128, U128);
-impl_number!(usize, Usize);
-impl_number!(i8, I8);
-impl_number!(i32, I32);
-impl_number!(i128, I128);
-impl_number!(isize, Isize);
+impl_number!(u32);
+impl_number!(u64);
+impl_number!(u128);
+impl_number!(usize);
+impl_number!(i8);
+impl_number!(i32);
+impl_number!(i64);
+impl_number!(i128);
+impl_number!(isize);
+
+impl FromValue for crate::BigInt {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        match crate::numeric::to_bigint(&value) {
+            Some(number) => Ok(number),
+            None => Err(VmError::expected::<Self>(value.type_info()?)),
+        }
+    }
+}
 
 impl FromValue for f64 {
     fn from_value(value: Value) -> Result<Self, VmError> {
@@ -98,9 +98,9 @@ impl Function {
     }
 
     /// Create a function pointer from a handler.
-    pub(crate) fn from_handler(handler: Arc<Handler>) -> Self {
+    pub(crate) fn from_handler(hash: Hash, handler: Arc<Handler>) -> Self {
         Self {
-            inner: Inner::FnHandler(FnHandler { handler }),
+            inner: Inner::FnHandler(FnHandler { hash, handler }),
         }
     }
 
@@ -164,6 +164,73 @@ impl Function {
         }
     }
 
+    /// Get the kind of the function, which is used when snapshotting it.
+    pub(crate) fn kind(&self) -> FunctionKind<'_> {
+        match &self.inner {
+            Inner::FnHandler(handler) => FunctionKind::Handler { hash: handler.hash },
+            Inner::FnOffset(fn_offset) => FunctionKind::Offset {
+                context: &fn_offset.context,
+                unit: &fn_offset.unit,
+                offset: fn_offset.offset,
+                call: fn_offset.call,
+                args: fn_offset.args,
+                environment: None,
+            },
+            Inner::FnClosureOffset(closure) => FunctionKind::Offset {
+                context: &closure.fn_offset.context,
+                unit: &closure.fn_offset.unit,
+                offset: closure.fn_offset.offset,
+                call: closure.fn_offset.call,
+                args: closure.fn_offset.args,
+                environment: Some(&closure.environment),
+            },
+            Inner::FnTuple(tuple) => FunctionKind::Tuple {
+                hash: tuple.hash,
+                args: tuple.args,
+            },
+            Inner::FnVariantTuple(tuple) => FunctionKind::VariantTuple {
+                enum_hash: tuple.enum_hash,
+                hash: tuple.hash,
+                args: tuple.args,
+            },
+        }
+    }
+
+    /// Access the unit a function at an offset belongs to, and the offset it's
+    /// located at.
+    pub(crate) fn unit_offset(&self) -> Option<(&Arc<Unit>, usize)> {
+        match &self.inner {
+            Inner::FnOffset(fn_offset) => Some((&fn_offset.unit, fn_offset.offset)),
+            Inner::FnClosureOffset(closure) => {
+                Some((&closure.fn_offset.unit, closure.fn_offset.offset))
+            }
+            _ => None,
+        }
+    }
+
+    /// Access the captured environment of a closure.
+    pub(crate) fn environment(&self) -> Option<&Shared<Tuple>> {
+        match &self.inner {
+            Inner::FnClosureOffset(closure) => Some(&closure.environment),
+            _ => None,
+        }
+    }
+
+    /// Move a function at an offset to the given offset in another unit.
+    ///
+    /// This does nothing for functions which don't reside in a unit.
+    pub(crate) fn relocate(&mut self, unit: Arc<Unit>, offset: usize, call: Call) {
+        let fn_offset = match &mut self.inner {
+            Inner::FnOffset(fn_offset) => fn_offset,
+            Inner::FnClosureOffset(closure) => &mut closure.fn_offset,
+            _ => return,
+        };
+
+        fn_offset.unit = unit;
+        fn_offset.offset = offset;
+        fn_offset.call = call;
+    }
+
     #[inline]
     fn check_args(actual: usize, expected: usize) -> Result<(), VmError> {
         if actual != expected {
@@ -209,6 +276,29 @@ impl fmt::Debug for Function {
     }
 }
 
+/// The kind of a function, borrowed from it.
+pub(crate) enum FunctionKind<'a> {
+    /// A native function.
+    Handler { hash: Hash },
+    /// A function at an offset in a unit, which might be a closure.
+    Offset {
+        context: &'a Arc<Context>,
+        unit: &'a Arc<Unit>,
+        offset: usize,
+        call: Call,
+        args: usize,
+        environment: Option<&'a Shared<Tuple>>,
+    },
+    /// A tuple constructor.
+    Tuple { hash: Hash, args: usize },
+    /// A tuple variant constructor.
+    VariantTuple {
+        enum_hash: Hash,
+        hash: Hash,
+        args: usize,
+    },
+}
+
 #[derive(Debug)]
 enum Inner {
     /// A native function handler.
@@ -231,6 +321,8 @@ enum Inner {
 }
 
 struct FnHandler {
+    /// The hash of the function in the context.
+    hash: Hash,
     /// The function handler.
     handler: Arc<Handler>,
 }
@@ -297,9 +389,14 @@ impl FnOffset {
 
         let mut new_stack = vm.stack_mut().drain_stack_top(args)?.collect::<Stack>();
         extra.into_stack(&mut new_stack)?;
-        let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
-        vm.set_ip(self.offset);
-        Ok(Some(VmCall::new(self.call, vm)))
+        let mut new_vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
+        new_vm.set_ip(self.offset);
+
+        if Arc::ptr_eq(vm.unit(), &self.unit) {
+            new_vm.set_coverage(vm.coverage().cloned());
+        }
+
+        Ok(Some(VmCall::new(self.call, new_vm)))
     }
 }
 
@@ -9,7 +9,7 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 /// dyn future alias.
-type DynFuture = dyn future::Future<Output = Result<Value, VmError>> + 'static;
+type DynFuture = dyn future::Future<Output = Result<Value, VmError>> + 'static + Send;
 
 /// A type-erased future that can only be unsafely polled in combination with
 /// the virtual machine that created it.
@@ -21,7 +21,7 @@ impl Future {
     /// Construct a new wrapped future.
     pub fn new<T, O>(future: T) -> Self
     where
-        T: 'static + future::Future<Output = Result<O, VmError>>,
+        T: 'static + Send + future::Future<Output = Result<O, VmError>>,
         O: ToValue,
     {
         Self {
@@ -40,6 +40,10 @@ impl Future {
     }
 }
 
+// Safety: the wrapped future is only ever accessed through a mutable
+// reference, so sharing a reference to it between threads is harmless.
+unsafe impl Sync for Future {}
+
 impl future::Future for Future {
     type Output = Result<Value, VmError>;
 
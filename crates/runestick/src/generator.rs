@@ -20,6 +20,17 @@ impl Generator {
         }
     }
 
+    /// Construct a generator from its raw parts.
+    pub(crate) fn from_parts(execution: Option<VmExecution>, first: bool) -> Self {
+        Self { execution, first }
+    }
+
+    /// Get the execution of the generator, unless it has completed, and if it
+    /// has been resumed yet.
+    pub(crate) fn parts(&self) -> (Option<&VmExecution>, bool) {
+        (self.execution.as_ref(), self.first)
+    }
+
     /// Get the next value produced by this stream.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<Value>, VmError> {
@@ -0,0 +1,391 @@
+//! Collection of reference cycles between values.
+//!
+//! Values are reference counted, which means that values referencing each
+//! other in a cycle are never freed. A [Heap] keeps track of allocated values
+//! without keeping them alive, and can find and break cycles among them which
+//! are no longer reachable from anywhere else.
+
+use crate::collections::HashMap;
+use crate::{
+    AccessError, Fields, Function, GeneratorState, Object, Shared, Tuple, TupleVariant,
+    TypedObject, TypedTuple, Value, VariantObject, Weak,
+};
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A heap of tracked values, which can be used to collect reference cycles.
+///
+/// Collection works by trial deletion: every reference between tracked values,
+/// and values reachable from them, is subtracted from their reference counts.
+/// Values which still have references left are referenced from somewhere else,
+/// like the stack of a virtual machine or the host. Anything which isn't
+/// reachable from such a value is garbage, and is reclaimed by clearing it.
+///
+/// Collection must not run concurrently with other threads using the tracked
+/// values, since their reference counts might change during collection. The
+/// heap is therefore never collected automatically, and it's up to the
+/// embedder to call [collect][Heap::collect] at a point where that's safe, like
+/// in between calls into a virtual machine.
+///
+/// Only references through the built-in containers like vectors, objects and
+/// closures are followed. Cycles which pass through native [Any][crate::Any]
+/// values, like a `HashMap` containing itself, are never reclaimed.
+///
+/// The handle is cheap to clone, and all clones refer to the same heap. See
+/// [Vm::set_heap][crate::Vm::set_heap].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Heap, Object, Shared, Value};
+///
+/// let heap = Heap::new();
+///
+/// let object = Value::Object(Shared::new(Object::new()));
+/// object.clone().into_object().unwrap().borrow_mut().unwrap().insert(String::from("this"), object.clone());
+/// heap.track(&object);
+///
+/// assert_eq!(heap.collect(), 0);
+/// drop(object);
+/// assert_eq!(heap.collect(), 1);
+/// assert_eq!(heap.tracked(), 0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Heap {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Heap {
+    /// Construct a new heap which is only collected when [collect] is called.
+    ///
+    /// [collect]: Heap::collect
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the given value, if it's a value which can be part of a cycle.
+    pub fn track(&self, value: &Value) {
+        let node = match Node::from_value(value) {
+            Some(node) => node,
+            None => return,
+        };
+
+        let mut inner = self.lock();
+
+        // NB: prune values which have been freed before growing, so that the
+        // tracked values don't grow indefinitely.
+        if inner.values.len() == inner.values.capacity() {
+            inner.values.retain(WeakNode::is_alive);
+        }
+
+        inner.values.push(node.downgrade());
+    }
+
+    /// Get the number of tracked values which are still alive.
+    pub fn tracked(&self) -> usize {
+        self.lock()
+            .values
+            .iter()
+            .filter(|value| value.is_alive())
+            .count()
+    }
+
+    /// Collect all reference cycles among tracked values which are no longer
+    /// reachable, returning the number of values which were reclaimed.
+    ///
+    /// This includes values which weren't tracked themselves, but were only
+    /// reachable through tracked values.
+    pub fn collect(&self) -> usize {
+        let roots = {
+            let mut inner = self.lock();
+            inner.values.retain(WeakNode::is_alive);
+            inner
+                .values
+                .iter()
+                .filter_map(WeakNode::upgrade)
+                .collect::<Vec<_>>()
+        };
+
+        collect(roots)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // NB: the heap is never left in an inconsistent state, so it's fine
+        // to ignore poisoning.
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(error) => error.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Values being tracked.
+    values: Vec<WeakNode>,
+}
+
+/// Perform a collection over the values reachable from the given values.
+fn collect(roots: Vec<Node>) -> usize {
+    let mut graph = Graph::default();
+
+    for node in roots {
+        graph.insert(node);
+    }
+
+    let mut children = Vec::new();
+    let mut n = 0;
+
+    while n < graph.nodes.len() {
+        children.clear();
+
+        // NB: values which are currently being modified can't be inspected,
+        // so they are treated as being referenced from elsewhere.
+        if graph.nodes[n].children(&mut children).is_err() {
+            graph.pinned[n] = true;
+            children.clear();
+        }
+
+        for child in children.drain(..) {
+            let index = graph.insert(child);
+            graph.internal[index] += 1;
+            graph.edges[n].push(index);
+        }
+
+        n += 1;
+    }
+
+    let mut reachable = vec![false; graph.nodes.len()];
+    let mut queue = Vec::new();
+
+    for (index, node) in graph.nodes.iter().enumerate() {
+        // NB: one reference is held by the graph itself.
+        if graph.pinned[index] || node.strong_count() > graph.internal[index] + 1 {
+            reachable[index] = true;
+            queue.push(index);
+        }
+    }
+
+    while let Some(index) = queue.pop() {
+        for &child in &graph.edges[index] {
+            if !reachable[child] {
+                reachable[child] = true;
+                queue.push(child);
+            }
+        }
+    }
+
+    let mut trash = Vec::new();
+    let mut collected = 0;
+
+    for (index, node) in graph.nodes.iter().enumerate() {
+        if !reachable[index] && node.clear(&mut trash).is_ok() {
+            collected += 1;
+        }
+    }
+
+    // NB: values are dropped once all cycles have been broken.
+    drop(trash);
+    collected
+}
+
+/// The graph of values being collected.
+#[derive(Default)]
+struct Graph {
+    /// Values in the graph.
+    nodes: Vec<Node>,
+    /// Index of each value in the graph, by address.
+    index: HashMap<usize, usize>,
+    /// Number of references to each value from other values in the graph.
+    internal: Vec<usize>,
+    /// Values which must be considered reachable.
+    pinned: Vec<bool>,
+    /// Values referenced by each value.
+    edges: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// Insert the given value, returning its index.
+    fn insert(&mut self, node: Node) -> usize {
+        let len = self.nodes.len();
+        let index = *self.index.entry(node.addr()).or_insert(len);
+
+        if index == len {
+            self.nodes.push(node);
+            self.internal.push(0);
+            self.pinned.push(false);
+            self.edges.push(Vec::new());
+        }
+
+        index
+    }
+}
+
+macro_rules! nodes {
+    ($($variant:ident($ty:ty),)*) => {
+        /// A value which might be part of a cycle.
+        enum Node {
+            $($variant(Shared<$ty>),)*
+        }
+
+        impl Node {
+            /// Construct a node from a value, if it can be part of a cycle.
+            fn from_value(value: &Value) -> Option<Self> {
+                match value {
+                    $(Value::$variant(value) => Some(Self::$variant(value.clone())),)*
+                    _ => None,
+                }
+            }
+
+            fn addr(&self) -> usize {
+                match self {
+                    $(Self::$variant(value) => value.addr(),)*
+                }
+            }
+
+            fn strong_count(&self) -> usize {
+                match self {
+                    $(Self::$variant(value) => value.strong_count(),)*
+                }
+            }
+
+            fn downgrade(&self) -> WeakNode {
+                match self {
+                    $(Self::$variant(value) => WeakNode::$variant(value.downgrade()),)*
+                }
+            }
+        }
+
+        /// A weak reference to a value which might be part of a cycle.
+        #[derive(Debug)]
+        enum WeakNode {
+            $($variant(Weak<$ty>),)*
+        }
+
+        impl WeakNode {
+            fn upgrade(&self) -> Option<Node> {
+                match self {
+                    $(Self::$variant(value) => Some(Node::$variant(value.upgrade()?)),)*
+                }
+            }
+
+            fn is_alive(&self) -> bool {
+                match self {
+                    $(Self::$variant(value) => value.is_alive(),)*
+                }
+            }
+        }
+    }
+}
+
+nodes! {
+    Vec(Vec<Value>),
+    Tuple(Tuple),
+    Object(Object),
+    GeneratorState(GeneratorState),
+    Option(Option<Value>),
+    Result(Result<Value, Value>),
+    TypedTuple(TypedTuple),
+    TupleVariant(TupleVariant),
+    TypedObject(TypedObject),
+    VariantObject(VariantObject),
+    Function(Function),
+}
+
+impl Node {
+    /// Collect the values directly referenced by this value.
+    fn children(&self, out: &mut Vec<Node>) -> Result<(), AccessError> {
+        match self {
+            Self::Vec(vec) => values(out, vec.borrow_ref()?.iter()),
+            Self::Tuple(tuple) => values(out, tuple.borrow_ref()?.iter()),
+            Self::Object(object) => values(out, object.borrow_ref()?.iter().map(|(_, v)| v)),
+            Self::GeneratorState(state) => match &*state.borrow_ref()? {
+                GeneratorState::Yielded(value) | GeneratorState::Complete(value) => {
+                    values(out, Some(value))
+                }
+            },
+            Self::Option(option) => values(out, option.borrow_ref()?.as_ref()),
+            Self::Result(result) => match &*result.borrow_ref()? {
+                Ok(value) | Err(value) => values(out, Some(value)),
+            },
+            Self::TypedTuple(tuple) => values(out, tuple.borrow_ref()?.tuple.iter()),
+            Self::TupleVariant(tuple) => values(out, tuple.borrow_ref()?.tuple.iter()),
+            Self::TypedObject(object) => values(out, object.borrow_ref()?.fields.values()),
+            Self::VariantObject(object) => values(out, object.borrow_ref()?.fields.values()),
+            Self::Function(function) => {
+                if let Some(environment) = function.borrow_ref()?.environment() {
+                    out.push(Self::Tuple(environment.clone()));
+                }
+            }
+        }
+
+        return Ok(());
+
+        fn values<'a>(out: &mut Vec<Node>, values: impl IntoIterator<Item = &'a Value>) {
+            out.extend(values.into_iter().filter_map(Node::from_value));
+        }
+    }
+
+    /// Clear the values referenced by this value, moving them into `trash`.
+    ///
+    /// The captured environment of a function is a value of its own, so it's
+    /// cleared separately.
+    fn clear(&self, trash: &mut Vec<Value>) -> Result<(), AccessError> {
+        match self {
+            Self::Vec(vec) => trash.append(&mut *vec.borrow_mut()?),
+            Self::Tuple(tuple) => {
+                let tuple = mem::replace(&mut *tuple.borrow_mut()?, Tuple::from(Vec::new()));
+                trash.extend(tuple.into_inner().into_vec());
+            }
+            Self::Object(object) => clear_object(trash, &mut *object.borrow_mut()?),
+            Self::GeneratorState(state) => {
+                let state = mem::replace(
+                    &mut *state.borrow_mut()?,
+                    GeneratorState::Complete(Value::Unit),
+                );
+
+                match state {
+                    GeneratorState::Yielded(value) | GeneratorState::Complete(value) => {
+                        trash.push(value)
+                    }
+                }
+            }
+            Self::Option(option) => trash.extend(option.borrow_mut()?.take()),
+            Self::Result(result) => {
+                match mem::replace(&mut *result.borrow_mut()?, Ok(Value::Unit)) {
+                    Ok(value) | Err(value) => trash.push(value),
+                }
+            }
+            Self::TypedTuple(tuple) => clear_tuple(trash, &mut tuple.borrow_mut()?.tuple),
+            Self::TupleVariant(tuple) => clear_tuple(trash, &mut tuple.borrow_mut()?.tuple),
+            Self::TypedObject(object) => clear_fields(trash, &mut object.borrow_mut()?.fields),
+            Self::VariantObject(object) => clear_fields(trash, &mut object.borrow_mut()?.fields),
+            Self::Function(..) => (),
+        }
+
+        return Ok(());
+
+        fn clear_tuple(trash: &mut Vec<Value>, tuple: &mut Box<[Value]>) {
+            trash.extend(mem::take(tuple).into_vec());
+        }
+
+        fn clear_fields(trash: &mut Vec<Value>, fields: &mut Fields) {
+            // NB: the fields of a struct are fixed by its shape, so the values
+            // are replaced instead of removed.
+            trash.extend(
+                fields
+                    .values_mut()
+                    .iter_mut()
+                    .map(|v| mem::replace(v, Value::Unit)),
+            );
+        }
+
+        fn clear_object(trash: &mut Vec<Value>, object: &mut Object) {
+            trash.extend(
+                mem::replace(object, Object::new())
+                    .into_iter()
+                    .map(|(_, v)| v),
+            );
+        }
+    }
+}
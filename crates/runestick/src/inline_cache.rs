@@ -0,0 +1,170 @@
+//! Inline caches used by the virtual machine to speed up lookups which depend
+//! on the type of a value, like instance function calls, getters and fields.
+
+use crate::context::Handler;
+use crate::{Call, Hash, Shape, Type};
+use std::fmt;
+use std::sync::Arc;
+
+/// The resolved target of a lookup.
+#[derive(Clone)]
+pub(crate) enum Target {
+    /// A function in the unit.
+    Offset {
+        /// Offset of the function.
+        offset: usize,
+        /// The way the function is called.
+        call: Call,
+        /// The number of arguments the function takes.
+        args: usize,
+    },
+    /// A native function in the context.
+    Handler(Arc<Handler>),
+    /// The slot of a field in a struct.
+    Field {
+        /// The shape of the struct.
+        ///
+        /// NB: this keeps the shape alive, so that its address can't be
+        /// reused by a different shape while cached.
+        shape: Arc<Shape>,
+        /// The slot index of the field.
+        index: usize,
+    },
+}
+
+/// A single cached lookup.
+#[derive(Clone)]
+struct Entry {
+    /// The type of the receiver the lookup was performed for.
+    type_of: Type,
+    /// The name being looked up, like the name of an instance function.
+    hash: Hash,
+    /// The resolved target.
+    target: Target,
+}
+
+/// Monomorphic caches, one for each instruction performing a lookup.
+///
+/// Entries are keyed by the instruction pointer of the instruction performing
+/// the lookup, so the cache is only valid for the unit it was populated for
+/// and must be cleared if the unit changes. An entry is only used if both the
+/// type of the receiver and the name being looked up matches, otherwise it's
+/// resolved again and replaced.
+#[derive(Clone)]
+pub(crate) struct InlineCache {
+    entries: Vec<Option<Entry>>,
+}
+
+impl InlineCache {
+    /// Construct a new empty cache.
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Get the target for the instruction at the given instruction pointer,
+    /// resolving and caching it with `resolve` if it isn't cached for the
+    /// given type and name.
+    ///
+    /// Targets which can't be resolved are not cached.
+    #[inline]
+    pub(crate) fn get_or_resolve<F>(
+        &mut self,
+        ip: usize,
+        type_of: Type,
+        hash: Hash,
+        resolve: F,
+    ) -> Option<&Target>
+    where
+        F: FnOnce() -> Option<Target>,
+    {
+        let entry = self.entry(ip);
+
+        match entry {
+            Some(entry) if entry.type_of == type_of && entry.hash == hash => (),
+            _ => {
+                let target = resolve()?;
+                *entry = Some(Entry {
+                    type_of,
+                    hash,
+                    target,
+                });
+            }
+        }
+
+        entry.as_ref().map(|entry| &entry.target)
+    }
+
+    /// Get the slot index of the field named `key` with the given hash, in a
+    /// struct of the given type and shape.
+    ///
+    /// Since shapes are shared between instances of a type, this is a cheap
+    /// pointer comparison for instructions which keep seeing the same type.
+    #[inline]
+    pub(crate) fn field_index(
+        &mut self,
+        ip: usize,
+        type_of: Type,
+        hash: Hash,
+        shape: &Arc<Shape>,
+        key: &str,
+    ) -> Option<usize> {
+        let entry = self.entry(ip);
+
+        if let Some(Entry {
+            type_of: cached_type_of,
+            hash: cached_hash,
+            target:
+                Target::Field {
+                    shape: cached_shape,
+                    index,
+                },
+        }) = entry
+        {
+            if *cached_type_of == type_of
+                && *cached_hash == hash
+                && Arc::ptr_eq(cached_shape, shape)
+            {
+                return Some(*index);
+            }
+        }
+
+        let index = shape.index(key)?;
+
+        *entry = Some(Entry {
+            type_of,
+            hash,
+            target: Target::Field {
+                shape: shape.clone(),
+                index,
+            },
+        });
+
+        Some(index)
+    }
+
+    /// Clear all cached entries.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Get the entry for the instruction at the given instruction pointer.
+    #[inline]
+    fn entry(&mut self, ip: usize) -> &mut Option<Entry> {
+        if ip >= self.entries.len() {
+            self.entries.resize_with(ip + 1, || None);
+        }
+
+        &mut self.entries[ip]
+    }
+}
+
+impl fmt::Debug for InlineCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.iter().filter(|entry| entry.is_some()).count();
+        f.debug_struct("InlineCache")
+            .field("entries", &entries)
+            .finish()
+    }
+}
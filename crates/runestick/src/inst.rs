@@ -103,6 +103,23 @@ pub enum Inst {
         /// The frame offset to assign to.
         offset: usize,
     },
+    /// Add a literal integer to a copy of the value at the given frame offset
+    /// and push the result.
+    ///
+    /// This is produced by the peephole optimizer out of a `Copy`, `Integer`,
+    /// `Add` sequence.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>
+    /// ```
+    AddImmediate {
+        /// The frame offset of the value to add to.
+        offset: usize,
+        /// The number to add.
+        number: i64,
+    },
     /// Subtract two things.
     ///
     /// This is the result of an `<a> - <b>` expression.
@@ -316,6 +333,42 @@ pub enum Inst {
         /// The static string slot corresponding to the index to fetch.
         slot: usize,
     },
+    /// Set the given index of an object on the top of the stack. Errors if
+    /// the target is a struct which doesn't have the given field.
+    ///
+    /// The index is identifier by a static string slot, which is provided as an
+    /// argument.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <object>
+    /// <value>
+    /// => *noop*
+    /// ```
+    ObjectSlotIndexSet {
+        /// The static string slot corresponding to the index to set.
+        slot: usize,
+    },
+    /// Get a field by its slot index out of a struct from the given variable
+    /// slot, where the shape of the struct is known.
+    ///
+    /// The shape is identified by a static object keys slot. Structs with a
+    /// different shape have the field looked up by name instead.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>
+    /// ```
+    ShapeIndexGetAt {
+        /// The slot offset to get the struct from.
+        offset: usize,
+        /// The static object keys slot of the shape of the struct.
+        slot: usize,
+        /// The slot index of the field in the shape.
+        index: usize,
+    },
     /// Perform an index set operation.
     ///
     /// # Operation
@@ -332,6 +385,27 @@ pub enum Inst {
         /// The number to push.
         number: i64,
     },
+    /// Push a literal unsigned 64-bit integer.
+    U64 {
+        /// The number to push.
+        number: u64,
+    },
+    /// Push a literal signed 128-bit integer.
+    I128 {
+        /// The number to push.
+        number: i128,
+    },
+    /// Push a literal unsigned 128-bit integer.
+    U128 {
+        /// The number to push.
+        number: u128,
+    },
+    /// Push a literal arbitrary precision integer, which is stored as a
+    /// static byte string in its two's complement little-endian form.
+    BigInt {
+        /// The static byte string slot of the number.
+        slot: usize,
+    },
     /// Push a literal float into a slot.
     Float {
         /// The number to push.
@@ -546,6 +620,27 @@ pub enum Inst {
         /// The offset to jump.
         offset: isize,
     },
+    /// Compare the values at the two given frame offsets, and jump to `offset`
+    /// relative to the current instruction pointer unless `lhs` is less than
+    /// `rhs`.
+    ///
+    /// This is produced by the peephole optimizer out of a `Copy`, `Copy`,
+    /// `Lt`, `JumpIfNot` sequence.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// *nothing*
+    /// => *nothing*
+    /// ```
+    JumpIfNotLt {
+        /// The frame offset of the left-hand side.
+        lhs: usize,
+        /// The frame offset of the right-hand side.
+        rhs: usize,
+        /// Offset to jump to.
+        offset: isize,
+    },
     /// Push a unit value onto the stack.
     ///
     /// # Operation
@@ -949,6 +1044,27 @@ pub enum Inst {
         /// The integer to test against.
         integer: i64,
     },
+    /// Test if the top of the stack is a specific integer, and if it isn't
+    /// pop the given number of entries on the stack and jump to the given
+    /// offset.
+    ///
+    /// This is produced by the peephole optimizer out of an `EqInteger`
+    /// followed by a `PopAndJumpIfNot`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => *noop*
+    /// ```
+    PopAndJumpIfNotInteger {
+        /// The integer to test against.
+        integer: i64,
+        /// The number of entries to pop if the test fails.
+        count: usize,
+        /// The offset to jump if the test fails.
+        offset: isize,
+    },
     /// Compare the top of the stack against a static string slot.
     ///
     /// # Operation
@@ -1055,6 +1171,9 @@ impl fmt::Display for Inst {
             Self::AddAssign { offset } => {
                 write!(fmt, "add-assign {}", offset)?;
             }
+            Self::AddImmediate { offset, number } => {
+                write!(fmt, "add-immediate {}, {}", offset, number)?;
+            }
             Self::Sub => {
                 write!(fmt, "sub")?;
             }
@@ -1115,12 +1234,34 @@ impl fmt::Display for Inst {
             Self::ObjectSlotIndexGetAt { offset, slot } => {
                 write!(fmt, "object-slot-index-get-at {}, {}", offset, slot)?;
             }
+            Self::ObjectSlotIndexSet { slot } => {
+                write!(fmt, "object-slot-index-set {}", slot)?;
+            }
+            Self::ShapeIndexGetAt {
+                offset,
+                slot,
+                index,
+            } => {
+                write!(fmt, "shape-index-get-at {}, {}, {}", offset, slot, index)?;
+            }
             Self::IndexSet => {
                 write!(fmt, "index-set")?;
             }
             Self::Integer { number } => {
                 write!(fmt, "integer {}", number)?;
             }
+            Self::U64 { number } => {
+                write!(fmt, "u64 {}", number)?;
+            }
+            Self::I128 { number } => {
+                write!(fmt, "i128 {}", number)?;
+            }
+            Self::U128 { number } => {
+                write!(fmt, "u128 {}", number)?;
+            }
+            Self::BigInt { slot } => {
+                write!(fmt, "bigint {}", slot)?;
+            }
             Self::Float { number } => {
                 write!(fmt, "float {}", number)?;
             }
@@ -1187,6 +1328,9 @@ impl fmt::Display for Inst {
             Self::JumpIfBranch { branch, offset } => {
                 write!(fmt, "jump-if-branch {}, {}", branch, offset)?;
             }
+            Self::JumpIfNotLt { lhs, rhs, offset } => {
+                write!(fmt, "jump-if-not-lt {}, {}, {}", lhs, rhs, offset)?;
+            }
             Self::Unit => {
                 write!(fmt, "unit")?;
             }
@@ -1290,6 +1434,17 @@ impl fmt::Display for Inst {
             Self::EqInteger { integer } => {
                 write!(fmt, "eq-integer {}", integer)?;
             }
+            Self::PopAndJumpIfNotInteger {
+                integer,
+                count,
+                offset,
+            } => {
+                write!(
+                    fmt,
+                    "pop-and-jump-if-not-integer {}, {}, {}",
+                    integer, count, offset
+                )?;
+            }
             Self::EqStaticString { slot } => {
                 write!(fmt, "eq-static-string {}", slot)?;
             }
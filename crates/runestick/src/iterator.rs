@@ -0,0 +1,750 @@
+use crate::modules::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use crate::{AnyObj, FromValue, Function, Generator, Object, Shared, Value, VmError, VmErrorKind};
+use std::cmp;
+use std::fmt;
+use std::iter;
+
+/// The source of an [Iterator].
+trait IteratorTrait: Send + Sync {
+    /// Get the next value from the iterator.
+    fn next(&mut self) -> Result<Option<Value>, VmError>;
+
+    /// Get the next value from the back of the iterator.
+    ///
+    /// Only supported by iterators which are double-ended.
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Err(VmError::from(VmErrorKind::NotDoubleEnded))
+    }
+}
+
+/// A lazy iterator over values, corresponding to the `std::iter::Iterator`
+/// type.
+///
+/// Iterators can be constructed from any value which can be iterated over
+/// natively, like vectors, tuples, objects, strings, options, generators and
+/// the collections in `std::collections`, and are extended through lazy adapters like [map][Iterator::map] and
+/// [filter][Iterator::filter]. Functions passed into adapters are called as
+/// the iterator is being advanced.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Iterator, Value};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let value = Value::vec(vec![Value::Integer(1), Value::Integer(2)]);
+/// let mut it = Iterator::from_value(value)?;
+///
+/// assert!(matches!(it.next()?, Some(Value::Integer(1))));
+/// assert!(matches!(it.next()?, Some(Value::Integer(2))));
+/// assert!(it.next()?.is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Iterator {
+    iter: Box<dyn IteratorTrait>,
+    /// A value which has been peeked at but not yet consumed.
+    peeked: Option<Option<Value>>,
+}
+
+impl Iterator {
+    /// Construct an iterator over the given native iterator.
+    pub fn from_iter<I>(iter: I) -> Self
+    where
+        I: 'static + iter::Iterator<Item = Value> + Send + Sync,
+    {
+        Self::new(Forward { iter })
+    }
+
+    /// Construct a double-ended iterator over the given native iterator.
+    pub fn from_double_ended<I>(iter: I) -> Self
+    where
+        I: 'static + DoubleEndedIterator<Item = Value> + Send + Sync,
+    {
+        Self::new(DoubleEnded { iter })
+    }
+
+    /// Construct an iterator which calls the given function to get the next
+    /// value, until it returns `None`.
+    pub fn from_fn(f: Shared<Function>) -> Self {
+        Self::new(FromFn { f })
+    }
+
+    /// Construct an iterator over the given value.
+    ///
+    /// Collections from `std::collections` are iterated over like their
+    /// `INTO_ITER` protocol would. This errors if the value can't be iterated
+    /// over natively.
+    pub fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Vec(vec) => Self::from_values(vec.borrow_ref()?.to_vec()),
+            Value::Tuple(tuple) => Self::from_values(tuple.borrow_ref()?.to_vec()),
+            Value::Object(object) => Self::from_object(&*object.borrow_ref()?),
+            Value::StaticString(s) => Self::from_chars(&s),
+            Value::String(s) => Self::from_chars(&s.borrow_ref()?),
+            Value::Bytes(bytes) => {
+                let bytes = bytes.borrow_ref()?.to_vec();
+                Self::from_double_ended(bytes.into_iter().map(Value::Byte))
+            }
+            Value::Option(option) => Self::from_values(option.borrow_ref()?.iter().cloned()),
+            Value::Generator(generator) => Self::new(FromGenerator { generator }),
+            Value::Any(any) if any.borrow_ref()?.is::<Iterator>() => any.take_downcast()?,
+            Value::Any(any) => {
+                let iter = Self::from_collection(&*any.borrow_ref()?);
+
+                match iter {
+                    Some(iter) => iter,
+                    None => {
+                        return Err(VmError::from(VmErrorKind::UnsupportedIterator {
+                            actual: Value::Any(any).type_info()?,
+                        }));
+                    }
+                }
+            }
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedIterator {
+                    actual: actual.type_info()?,
+                }));
+            }
+        })
+    }
+
+    fn new<T>(iter: T) -> Self
+    where
+        T: 'static + IteratorTrait,
+    {
+        Self {
+            iter: Box::new(iter),
+            peeked: None,
+        }
+    }
+
+    /// Construct a double-ended iterator over a copy of the given values.
+    pub(crate) fn from_values<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Value>,
+    {
+        let values = values.into_iter().collect::<Vec<_>>();
+        Self::from_double_ended(values.into_iter())
+    }
+
+    /// Construct a double-ended iterator over a copy of the `(key, value)`
+    /// entries of the given object.
+    pub(crate) fn from_object(object: &Object) -> Self {
+        Self::from_values(
+            object
+                .iter()
+                .map(|(key, value)| Value::tuple(vec![Value::from(key.clone()), value.clone()])),
+        )
+    }
+
+    /// Construct an iterator over a copy of the values of a collection from
+    /// `std::collections`, or `None` if the value isn't one.
+    fn from_collection(any: &AnyObj) -> Option<Self> {
+        if let Some(map) = any.downcast_borrow_ref::<HashMap>() {
+            return Some(map.iter());
+        }
+
+        if let Some(set) = any.downcast_borrow_ref::<HashSet>() {
+            return Some(set.iter());
+        }
+
+        if let Some(map) = any.downcast_borrow_ref::<BTreeMap>() {
+            return Some(map.iter());
+        }
+
+        if let Some(deque) = any.downcast_borrow_ref::<VecDeque>() {
+            return Some(deque.iter());
+        }
+
+        None
+    }
+
+    fn from_chars(s: &str) -> Self {
+        let chars = s.chars().collect::<Vec<_>>();
+        Self::from_double_ended(chars.into_iter().map(Value::Char))
+    }
+
+    /// Get the next value from the iterator.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<Value>, VmError> {
+        match self.peeked.take() {
+            Some(peeked) => Ok(peeked),
+            None => self.iter.next(),
+        }
+    }
+
+    /// Get the next value from the back of the iterator.
+    ///
+    /// This errors if the iterator isn't double-ended.
+    pub fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        match self.peeked.take() {
+            Some(None) => Ok(None),
+            Some(Some(peeked)) => match self.iter.next_back()? {
+                Some(value) => {
+                    self.peeked = Some(Some(peeked));
+                    Ok(Some(value))
+                }
+                None => Ok(Some(peeked)),
+            },
+            None => self.iter.next_back(),
+        }
+    }
+
+    /// Peek at the next value of the iterator without consuming it.
+    pub fn peek(&mut self) -> Result<Option<Value>, VmError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.iter.next()?);
+        }
+
+        Ok(self.peeked.clone().flatten())
+    }
+
+    /// Turn the iterator into one which supports [peek][Iterator::peek].
+    ///
+    /// All iterators can be peeked at, so this returns the iterator as-is.
+    pub fn peekable(self) -> Self {
+        self
+    }
+
+    /// Reverse the iterator.
+    ///
+    /// Advancing the reversed iterator errors if the iterator isn't
+    /// double-ended.
+    pub fn rev(self) -> Self {
+        Self::new(Rev { iter: self })
+    }
+
+    /// Map every value of the iterator through the given function.
+    pub fn map(self, f: Shared<Function>) -> Self {
+        Self::new(Map { iter: self, f })
+    }
+
+    /// Only keep values for which the given function returns `true`.
+    pub fn filter(self, f: Shared<Function>) -> Self {
+        Self::new(Filter { iter: self, f })
+    }
+
+    /// Map every value through the given function, only keeping the ones for
+    /// which it returns `Some`.
+    pub fn filter_map(self, f: Shared<Function>) -> Self {
+        Self::new(FilterMap { iter: self, f })
+    }
+
+    /// Pair every value with its index.
+    pub fn enumerate(self) -> Self {
+        Self::new(Enumerate {
+            iter: self,
+            index: 0,
+        })
+    }
+
+    /// Pair up the values of two iterators, until either is exhausted.
+    pub fn zip(self, other: Value) -> Result<Self, VmError> {
+        Ok(Self::new(Zip {
+            a: self,
+            b: Self::from_value(other)?,
+        }))
+    }
+
+    /// Continue with the values of another iterator once this one is
+    /// exhausted.
+    pub fn chain(self, other: Value) -> Result<Self, VmError> {
+        Ok(Self::new(Chain {
+            a: Some(self),
+            b: Self::from_value(other)?,
+        }))
+    }
+
+    /// Only produce the first `n` values of the iterator.
+    pub fn take(self, n: usize) -> Self {
+        Self::new(Take { iter: self, n })
+    }
+
+    /// Skip the first `n` values of the iterator.
+    pub fn skip(self, n: usize) -> Self {
+        Self::new(Skip { iter: self, n })
+    }
+
+    /// Produce values until the given function returns `false`.
+    pub fn take_while(self, f: Shared<Function>) -> Self {
+        Self::new(TakeWhile {
+            iter: Some(self),
+            f,
+        })
+    }
+
+    /// Map every value to an iterator through the given function, and
+    /// produce the values of those iterators.
+    pub fn flat_map(self, f: Shared<Function>) -> Self {
+        Self::new(FlatMap {
+            iter: self,
+            f,
+            current: None,
+        })
+    }
+
+    /// Collect the values of the iterator into a vector.
+    pub fn collect_vec(mut self) -> Result<Vec<Value>, VmError> {
+        let mut vec = Vec::new();
+
+        while let Some(value) = self.next()? {
+            vec.push(value);
+        }
+
+        Ok(vec)
+    }
+
+    /// Collect `(key, value)` tuples produced by the iterator into an object.
+    pub fn collect_object(mut self) -> Result<Object, VmError> {
+        let mut object = Object::new();
+
+        while let Some(value) = self.next()? {
+            let (key, value) = <(String, Value)>::from_value(value)?;
+            object.insert(key, value);
+        }
+
+        Ok(object)
+    }
+
+    /// Concatenate the strings and characters produced by the iterator into a
+    /// string.
+    pub fn collect_string(mut self) -> Result<String, VmError> {
+        let mut string = String::new();
+
+        while let Some(value) = self.next()? {
+            match value {
+                Value::Char(c) => string.push(c),
+                Value::StaticString(s) => string.push_str(&s),
+                Value::String(s) => string.push_str(&s.borrow_ref()?),
+                actual => {
+                    return Err(VmError::expected::<String>(actual.type_info()?));
+                }
+            }
+        }
+
+        Ok(string)
+    }
+
+    /// Fold every value of the iterator into an accumulator using the given
+    /// function.
+    pub fn fold(mut self, mut accumulator: Value, f: Shared<Function>) -> Result<Value, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            accumulator = f.call((accumulator, value))?;
+        }
+
+        Ok(accumulator)
+    }
+
+    /// Sum up the integers or floats produced by the iterator.
+    pub fn sum(mut self) -> Result<Value, VmError> {
+        let mut sum = match self.next()? {
+            Some(value) => value,
+            None => return Ok(Value::Integer(0)),
+        };
+
+        while let Some(value) = self.next()? {
+            sum = match (sum, value) {
+                (Value::Integer(a), Value::Integer(b)) => {
+                    Value::Integer(a.checked_add(b).ok_or(VmErrorKind::Overflow)?)
+                }
+                (Value::Float(a), Value::Float(b)) => Value::Float(a + b),
+                (lhs, rhs) => {
+                    return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                        op: "+",
+                        lhs: lhs.type_info()?,
+                        rhs: rhs.type_info()?,
+                    }));
+                }
+            };
+        }
+
+        Ok(sum)
+    }
+
+    /// Count the number of values produced by the iterator.
+    pub fn count(mut self) -> Result<usize, VmError> {
+        let mut count = 0;
+
+        while self.next()?.is_some() {
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Test if the given function returns `true` for any value.
+    pub fn any(mut self, f: Shared<Function>) -> Result<bool, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if f.call::<_, bool>((value,))? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Test if the given function returns `true` for all values.
+    pub fn all(mut self, f: Shared<Function>) -> Result<bool, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if !f.call::<_, bool>((value,))? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Find the first value for which the given function returns `true`.
+    pub fn find(mut self, f: Shared<Function>) -> Result<Option<Value>, VmError> {
+        let f = f.borrow_ref()?;
+
+        while let Some(value) = self.next()? {
+            if f.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Find the smallest value produced by the iterator.
+    pub fn min(self) -> Result<Option<Value>, VmError> {
+        self.select(cmp::Ordering::Less)
+    }
+
+    /// Find the largest value produced by the iterator.
+    pub fn max(self) -> Result<Option<Value>, VmError> {
+        self.select(cmp::Ordering::Greater)
+    }
+
+    /// Select the value which compares as `ordering` against every other
+    /// value, preferring the first value for min and the last for max like
+    /// the std library.
+    fn select(mut self, ordering: cmp::Ordering) -> Result<Option<Value>, VmError> {
+        let mut selected = match self.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        while let Some(value) = self.next()? {
+            let order = Value::value_ptr_cmp(&value, &selected)?;
+
+            if order == ordering
+                || (order == cmp::Ordering::Equal && ordering == cmp::Ordering::Greater)
+            {
+                selected = value;
+            }
+        }
+
+        Ok(Some(selected))
+    }
+}
+
+impl IteratorTrait for Iterator {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        Iterator::next(self)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Iterator::next_back(self)
+    }
+}
+
+impl fmt::Debug for Iterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iterator").finish()
+    }
+}
+
+struct Forward<I> {
+    iter: I,
+}
+
+impl<I> IteratorTrait for Forward<I>
+where
+    I: iter::Iterator<Item = Value> + Send + Sync,
+{
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        Ok(self.iter.next())
+    }
+}
+
+struct DoubleEnded<I> {
+    iter: I,
+}
+
+impl<I> IteratorTrait for DoubleEnded<I>
+where
+    I: DoubleEndedIterator<Item = Value> + Send + Sync,
+{
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        Ok(self.iter.next())
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        Ok(self.iter.next_back())
+    }
+}
+
+struct FromFn {
+    f: Shared<Function>,
+}
+
+impl IteratorTrait for FromFn {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.f.borrow_ref()?.call(())
+    }
+}
+
+struct FromGenerator {
+    generator: Shared<Generator>,
+}
+
+impl IteratorTrait for FromGenerator {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.generator.borrow_mut()?.next()
+    }
+}
+
+struct Rev {
+    iter: Iterator,
+}
+
+impl IteratorTrait for Rev {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        self.iter.next_back()
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        self.iter.next()
+    }
+}
+
+struct Map {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl IteratorTrait for Map {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        match self.iter.next()? {
+            Some(value) => Ok(Some(self.f.borrow_ref()?.call((value,))?)),
+            None => Ok(None),
+        }
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        match self.iter.next_back()? {
+            Some(value) => Ok(Some(self.f.borrow_ref()?.call((value,))?)),
+            None => Ok(None),
+        }
+    }
+}
+
+struct Filter {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl IteratorTrait for Filter {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next()? {
+            if self.f.borrow_ref()?.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next_back()? {
+            if self.f.borrow_ref()?.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+struct FilterMap {
+    iter: Iterator,
+    f: Shared<Function>,
+}
+
+impl IteratorTrait for FilterMap {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next()? {
+            if let Some(value) = self.f.borrow_ref()?.call::<_, Option<Value>>((value,))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_back(&mut self) -> Result<Option<Value>, VmError> {
+        while let Some(value) = self.iter.next_back()? {
+            if let Some(value) = self.f.borrow_ref()?.call::<_, Option<Value>>((value,))? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+struct Enumerate {
+    iter: Iterator,
+    index: i64,
+}
+
+impl IteratorTrait for Enumerate {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let value = match self.iter.next()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let index = self.index;
+        self.index += 1;
+        Ok(Some(Value::tuple(vec![Value::Integer(index), value])))
+    }
+}
+
+struct Zip {
+    a: Iterator,
+    b: Iterator,
+}
+
+impl IteratorTrait for Zip {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let a = match self.a.next()? {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let b = match self.b.next()? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Value::tuple(vec![a, b])))
+    }
+}
+
+struct Chain {
+    a: Option<Iterator>,
+    b: Iterator,
+}
+
+impl IteratorTrait for Chain {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if let Some(a) = &mut self.a {
+            if let Some(value) = a.next()? {
+                return Ok(Some(value));
+            }
+
+            self.a = None;
+        }
+
+        self.b.next()
+    }
+}
+
+struct Take {
+    iter: Iterator,
+    n: usize,
+}
+
+impl IteratorTrait for Take {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        if self.n == 0 {
+            return Ok(None);
+        }
+
+        self.n -= 1;
+        self.iter.next()
+    }
+}
+
+struct Skip {
+    iter: Iterator,
+    n: usize,
+}
+
+impl IteratorTrait for Skip {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        while self.n > 0 {
+            self.n -= 1;
+
+            if self.iter.next()?.is_none() {
+                return Ok(None);
+            }
+        }
+
+        self.iter.next()
+    }
+}
+
+struct TakeWhile {
+    /// The iterator, which is cleared once the predicate fails.
+    iter: Option<Iterator>,
+    f: Shared<Function>,
+}
+
+impl IteratorTrait for TakeWhile {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        let iter = match &mut self.iter {
+            Some(iter) => iter,
+            None => return Ok(None),
+        };
+
+        if let Some(value) = iter.next()? {
+            if self.f.borrow_ref()?.call::<_, bool>((value.clone(),))? {
+                return Ok(Some(value));
+            }
+        }
+
+        self.iter = None;
+        Ok(None)
+    }
+}
+
+struct FlatMap {
+    iter: Iterator,
+    f: Shared<Function>,
+    /// The iterator currently being flattened.
+    current: Option<Iterator>,
+}
+
+impl IteratorTrait for FlatMap {
+    fn next(&mut self) -> Result<Option<Value>, VmError> {
+        loop {
+            if let Some(current) = &mut self.current {
+                if let Some(value) = current.next()? {
+                    return Ok(Some(value));
+                }
+
+                self.current = None;
+            }
+
+            let value = match self.iter.next()? {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+
+            self.current = Some(Iterator::from_value(self.f.borrow_ref()?.call((value,))?)?);
+        }
+    }
+}
+
+crate::__internal_impl_any!(Iterator);
@@ -0,0 +1,246 @@
+use crate::{
+    BigInt, Bytes, FromValue, Hash, Shared, ToValue, Tuple, TupleVariant, TypedTuple, Value,
+    VmError, VmErrorKind,
+};
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash;
+
+/// An immutable copy of a value, which can be hashed, compared for equality
+/// and ordered.
+///
+/// Keys are used by native collections like `HashMap` and `HashSet` which are
+/// keyed by values. Only values which are compared by content can be used as
+/// keys, like integers, strings, bytes and tuples of other keys. Static
+/// strings and strings are considered to be the same, and so are integers of
+/// different types with the same value, like `1u64` and `1`. Integers are
+/// stored as the narrowest type which can hold them.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Key, Value};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let a = Key::from_value(&Value::from(String::from("foo")))?;
+/// let b = Key::from_value(&Value::from(String::from("foo")))?;
+/// assert_eq!(a, b);
+///
+/// assert!(Key::from_value(&Value::Float(f64::NAN)).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Inner {
+    Unit,
+    Bool(bool),
+    Byte(u8),
+    Char(char),
+    Integer(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    BigInt(BigInt),
+    Float(Float),
+    Type(Hash),
+    String(Box<str>),
+    Bytes(Box<[u8]>),
+    Vec(Box<[Inner]>),
+    Tuple(Box<[Inner]>),
+    Option(Option<Box<Inner>>),
+    Result(Result<Box<Inner>, Box<Inner>>),
+    TypedTuple(Hash, Box<[Inner]>),
+    TupleVariant(Hash, Hash, Box<[Inner]>),
+}
+
+impl Key {
+    /// Construct a key from the given value.
+    ///
+    /// This errors if the value or anything it contains can't be used as a
+    /// key, like objects, functions or floats which are NaN.
+    pub fn from_value(value: &Value) -> Result<Self, VmError> {
+        Ok(Self {
+            inner: Inner::from_value(value)?,
+        })
+    }
+
+    /// Convert the key back into a value.
+    pub fn into_value(self) -> Value {
+        self.inner.into_value()
+    }
+}
+
+impl Inner {
+    fn from_value(value: &Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Unit => Self::Unit,
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Byte(b) => Self::Byte(*b),
+            Value::Char(c) => Self::Char(*c),
+            Value::Integer(n) => Self::Integer(*n),
+            Value::U64(n) => Self::wide_integer(BigInt::from(*n)),
+            Value::I128(n) => Self::wide_integer(BigInt::from(**n)),
+            Value::U128(n) => Self::wide_integer(BigInt::from(**n)),
+            Value::BigInt(n) => Self::wide_integer((**n).clone()),
+            Value::Float(n) => {
+                if n.is_nan() {
+                    return Err(VmError::from(VmErrorKind::NanKey));
+                }
+
+                // NB: negative and positive zero are the same key.
+                Self::Float(Float(if *n == 0.0 { 0.0 } else { *n }))
+            }
+            Value::Type(hash) => Self::Type(*hash),
+            Value::StaticString(s) => Self::String(s.as_str().into()),
+            Value::String(s) => Self::String(s.borrow_ref()?.as_str().into()),
+            Value::Bytes(b) => Self::Bytes((&**b.borrow_ref()?).into()),
+            Value::Vec(vec) => Self::Vec(Self::from_values(&vec.borrow_ref()?)?),
+            Value::Tuple(tuple) => Self::Tuple(Self::from_values(&tuple.borrow_ref()?)?),
+            Value::Option(option) => Self::Option(match &*option.borrow_ref()? {
+                Some(value) => Some(Box::new(Self::from_value(value)?)),
+                None => None,
+            }),
+            Value::Result(result) => Self::Result(match &*result.borrow_ref()? {
+                Ok(value) => Ok(Box::new(Self::from_value(value)?)),
+                Err(value) => Err(Box::new(Self::from_value(value)?)),
+            }),
+            Value::TypedTuple(tuple) => {
+                let tuple = tuple.borrow_ref()?;
+                Self::TypedTuple(tuple.hash, Self::from_values(&tuple.tuple)?)
+            }
+            Value::TupleVariant(variant) => {
+                let variant = variant.borrow_ref()?;
+
+                Self::TupleVariant(
+                    variant.enum_hash,
+                    variant.hash,
+                    Self::from_values(&variant.tuple)?,
+                )
+            }
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedKey {
+                    actual: actual.type_info()?,
+                }));
+            }
+        })
+    }
+
+    /// Normalize a wide integer into the narrowest type which can hold it, so
+    /// that integers which are equal under `==` are the same key.
+    fn wide_integer(n: BigInt) -> Self {
+        if let Ok(n) = i64::try_from(&n) {
+            return Self::Integer(n);
+        }
+
+        if let Ok(n) = u64::try_from(&n) {
+            return Self::U64(n);
+        }
+
+        if let Ok(n) = i128::try_from(&n) {
+            return Self::I128(n);
+        }
+
+        if let Ok(n) = u128::try_from(&n) {
+            return Self::U128(n);
+        }
+
+        Self::BigInt(n)
+    }
+
+    fn from_values(values: &[Value]) -> Result<Box<[Self]>, VmError> {
+        values.iter().map(Self::from_value).collect()
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            Self::Unit => Value::Unit,
+            Self::Bool(b) => Value::Bool(b),
+            Self::Byte(b) => Value::Byte(b),
+            Self::Char(c) => Value::Char(c),
+            Self::Integer(n) => Value::Integer(n),
+            Self::U64(n) => Value::U64(n),
+            Self::I128(n) => Value::from(n),
+            Self::U128(n) => Value::from(n),
+            Self::BigInt(n) => Value::from(n),
+            Self::Float(Float(n)) => Value::Float(n),
+            Self::Type(hash) => Value::Type(hash),
+            Self::String(s) => Value::String(Shared::new(s.into())),
+            Self::Bytes(b) => Value::Bytes(Shared::new(Bytes::from_vec(b.into()))),
+            Self::Vec(vec) => Value::vec(Self::into_values(vec).into()),
+            Self::Tuple(tuple) => Value::Tuple(Shared::new(Tuple::from(Self::into_values(tuple)))),
+            Self::Option(option) => Value::Option(Shared::new(option.map(|v| v.into_value()))),
+            Self::Result(result) => Value::Result(Shared::new(match result {
+                Ok(value) => Ok(value.into_value()),
+                Err(value) => Err(value.into_value()),
+            })),
+            Self::TypedTuple(hash, tuple) => Value::TypedTuple(Shared::new(TypedTuple {
+                hash,
+                tuple: Self::into_values(tuple),
+            })),
+            Self::TupleVariant(enum_hash, hash, tuple) => {
+                Value::TupleVariant(Shared::new(TupleVariant {
+                    enum_hash,
+                    hash,
+                    tuple: Self::into_values(tuple),
+                }))
+            }
+        }
+    }
+
+    fn into_values(values: Box<[Self]>) -> Box<[Value]> {
+        values
+            .into_vec()
+            .into_iter()
+            .map(Self::into_value)
+            .collect()
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl FromValue for Key {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Key::from_value(&value)
+    }
+}
+
+impl ToValue for Key {
+    fn to_value(self) -> Result<Value, VmError> {
+        Ok(self.into_value())
+    }
+}
+
+/// A float which is known to not be NaN, and can therefore be totally ordered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Float(f64);
+
+impl Eq for Float {}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        // NB: NaN is never constructed.
+        self.0.partial_cmp(&other.0).unwrap_or(cmp::Ordering::Equal)
+    }
+}
+
+impl hash::Hash for Float {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
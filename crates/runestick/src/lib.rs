@@ -56,7 +56,11 @@ mod awaited;
 mod bytes;
 mod call;
 mod compile_meta;
+mod content_hash;
+pub mod coverage;
 pub mod debug;
+pub mod debugger;
+mod fields;
 mod from_value;
 mod function;
 mod future;
@@ -64,18 +68,27 @@ mod generator;
 mod generator_state;
 mod guarded_args;
 mod hash;
+mod heap;
+mod inline_cache;
 mod inst;
 mod item;
+mod iterator;
+mod key;
 mod label;
 pub mod module;
 pub mod modules;
 mod names;
+mod numeric;
 mod object;
 mod panic;
+pub mod profiler;
 mod protocol;
+mod reload;
 mod select;
 mod serde;
+mod shape;
 mod shared;
+mod snapshot;
 mod source;
 mod span;
 mod stack;
@@ -88,11 +101,14 @@ mod type_;
 mod type_info;
 mod type_of;
 mod unit;
+mod unit_file;
 mod vec_tuple;
+mod verify;
 mod vm_call;
 mod vm_error;
 mod vm_execution;
 mod vm_halt;
+mod weak_value;
 
 /// Exported result type for convenience.
 pub type Result<T, E = anyhow::Error> = std::result::Result<T, E>;
@@ -116,9 +132,10 @@ pub use self::source::Source;
 pub use self::span::Span;
 pub use self::static_string::StaticString;
 pub use self::static_type::{
-    StaticType, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE, FUNCTION_TYPE,
-    FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, INTEGER_TYPE, OBJECT_TYPE, OPTION_TYPE,
-    RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
+    StaticType, BIG_INT_TYPE, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE,
+    FUNCTION_TYPE, FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, I128_TYPE, INTEGER_TYPE,
+    OBJECT_TYPE, OPTION_TYPE, RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, U128_TYPE,
+    U64_TYPE, UNIT_TYPE, VEC_TYPE, WEAK_TYPE,
 };
 pub use self::stream::Stream;
 pub use self::to_value::{ToValue, UnsafeToValue};
@@ -134,31 +151,45 @@ pub use crate::awaited::Awaited;
 pub use crate::bytes::Bytes;
 pub use crate::call::Call;
 pub use crate::context::{Context, ContextError};
+pub use crate::coverage::Coverage;
 pub use crate::debug::{DebugInfo, DebugInst};
+pub use crate::debugger::Debugger;
+pub use crate::fields::Fields;
 pub use crate::function::Function;
 pub use crate::future::Future;
 pub use crate::hash::{Hash, IntoHash};
+pub use crate::heap::Heap;
 pub use crate::inst::{Inst, PanicReason, TypeCheck};
 pub use crate::item::{Component, Item};
+pub use crate::iterator::Iterator;
+pub use crate::key::Key;
 pub use crate::names::Names;
 pub use crate::object::Object;
 pub use crate::panic::Panic;
+pub use crate::profiler::Profiler;
 pub use crate::protocol::{
     Protocol, ADD, ADD_ASSIGN, BIT_AND, BIT_AND_ASSIGN, BIT_OR, BIT_OR_ASSIGN, BIT_XOR,
     BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL, MUL_ASSIGN,
     NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DISPLAY, SUB, SUB_ASSIGN,
 };
-pub use crate::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard};
+pub use crate::reload::{Orphan, Reload, ReloadError};
+pub use crate::shape::Shape;
+pub use crate::shared::{Mut, RawMut, RawRef, Ref, Shared, SharedPointerGuard, Weak};
+pub use crate::snapshot::{SnapshotError, VmSnapshot};
 pub use crate::stack::{Stack, StackError};
 pub use crate::type_of::TypeOf;
 pub use crate::unit::{Unit, UnitFn, UnitTypeInfo};
+pub use crate::unit_file::{Compression, UnitFileError, UnitFileHeader};
 pub use crate::value::{TupleVariant, TypedObject, TypedTuple, Value, VariantObject};
 pub use crate::vec_tuple::VecTuple;
+pub use crate::verify::{VerifyError, VerifyErrors};
 pub use crate::vm::{CallFrame, Vm};
 pub use crate::vm_call::VmCall;
-pub use crate::vm_error::{VmError, VmErrorKind, VmIntegerRepr};
+pub use crate::vm_error::{BacktraceFrame, VmError, VmErrorKind, VmIntegerRepr};
 pub use crate::vm_execution::VmExecution;
 pub use crate::vm_halt::{VmHalt, VmHaltInfo};
+pub use crate::weak_value::WeakValue;
+pub use num_bigint::BigInt;
 pub(crate) use runestick_macros::__internal_impl_any;
 pub use runestick_macros::{Any, FromValue};
 
@@ -138,6 +138,8 @@ pub struct Module {
     pub(crate) functions: HashMap<Item, ModuleFn>,
     /// Macro handlers.
     pub(crate) macros: HashMap<Item, ModuleMacro>,
+    /// Constants.
+    pub(crate) constants: HashMap<Item, ModuleFn>,
     /// Instance functions.
     pub(crate) associated_functions: HashMap<ModuleAssocKey, ModuleAssociatedFn>,
     /// Registered types.
@@ -159,6 +161,7 @@ impl Module {
             path: Item::of(path),
             functions: Default::default(),
             macros: Default::default(),
+            constants: Default::default(),
             associated_functions: Default::default(),
             types: Default::default(),
             unit_type: None,
@@ -399,6 +402,55 @@ impl Module {
         Ok(())
     }
 
+    /// Register a constant.
+    ///
+    /// Constants are referenced by their path in scripts, like `std::math::PI`,
+    /// and produce a copy of the registered value every time they're used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["nonstd"]);
+    /// module.constant(&["ANSWER"], 42i64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn constant<N, V>(&mut self, name: N, value: V) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+        V: 'static + Clone + Send + Sync + ToValue,
+    {
+        let name = Item::of(name);
+
+        if self.constants.contains_key(&name) || self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        let handler: Arc<Handler> = Arc::new(move |stack, args| {
+            if args != 0 {
+                return Err(VmError::from(VmErrorKind::BadArgumentCount {
+                    actual: args,
+                    expected: 0,
+                }));
+            }
+
+            stack.push(value.clone().to_value()?);
+            Ok(())
+        });
+
+        self.constants.insert(
+            name,
+            ModuleFn {
+                handler,
+                args: Some(0),
+            },
+        );
+
+        Ok(())
+    }
+
     /// Register a native macro handler.
     pub fn macro_<N, M, A, B, O>(&mut self, name: N, f: M) -> Result<(), ContextError>
     where
@@ -836,7 +888,7 @@ macro_rules! impl_register {
         impl<Func, Return, $($ty,)*> AsyncFunction<($($ty,)*)> for Func
         where
             Func: 'static + Copy + Send + Sync + Fn($($ty,)*) -> Return,
-            Return: future::Future,
+            Return: 'static + future::Future + Send,
             Return::Output: ToValue,
             $($ty: 'static + UnsafeFromValue,)*
         {
@@ -860,14 +912,19 @@ macro_rules! impl_register {
 
                 // Safety: Future is owned and will only be called within the
                 // context of the virtual machine, which will provide
-                // exclusive thread-local access to itself while the future is
+                // exclusive access to itself while the future is
                 // being polled.
                 #[allow(unused_unsafe)]
                 let ret = unsafe {
                     impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
 
+                    let future = self($(<$ty>::to_arg($var.0),)*);
+                    let guards = ($($var.1,)*);
+
                     Future::new(async move {
-                        let output = self($(<$ty>::to_arg($var.0),)*).await;
+                        // NB: guards must be held until the future completes.
+                        let _guards = guards;
+                        let output = future.await;
                         let value = output.to_value()?;
                         Ok(value)
                     })
@@ -928,7 +985,7 @@ macro_rules! impl_register {
         impl<Func, Return, Instance, $($ty,)*> AsyncInstFn<(Instance, $($ty,)*)> for Func
         where
             Func: 'static + Copy + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
-            Return: future::Future,
+            Return: 'static + future::Future + Send,
             Return::Output: ToValue,
             Instance: UnsafeFromValue + TypeOf,
             $($ty: UnsafeFromValue,)*
@@ -959,14 +1016,19 @@ macro_rules! impl_register {
 
                 // Safety: Future is owned and will only be called within the
                 // context of the virtual machine, which will provide
-                // exclusive thread-local access to itself while the future is
+                // exclusive access to itself while the future is
                 // being polled.
                 #[allow(unused)]
                 let ret = unsafe {
                     impl_register!{@unsafe-inst-vars inst, $count, $($ty, $var, $num,)*}
 
+                    let future = self(Instance::to_arg(inst.0), $(<$ty>::to_arg($var.0),)*);
+                    let guards = (inst.1, $($var.1,)*);
+
                     Future::new(async move {
-                        let output = self(Instance::to_arg(inst.0), $(<$ty>::to_arg($var.0),)*).await;
+                        // NB: guards must be held until the future completes.
+                        let _guards = guards;
+                        let output = future.await;
                         let value = output.to_value()?;
                         Ok(value)
                     })
@@ -0,0 +1,47 @@
+//! The `std::char` module.
+
+use crate::modules::int::check_radix;
+use crate::{ContextError, Module, VmError};
+use std::char::ParseCharError;
+
+/// Construct the `std::char` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std"]);
+
+    module.ty(&["char"]).build::<char>()?;
+    module
+        .ty(&["char", "ParseCharError"])
+        .build::<ParseCharError>()?;
+    module.function(&["char", "from_u32"], std::char::from_u32)?;
+    module.function(&["char", "from_digit"], from_digit)?;
+
+    module.inst_fn("is_alphabetic", char::is_alphabetic)?;
+    module.inst_fn("is_alphanumeric", char::is_alphanumeric)?;
+    module.inst_fn("is_numeric", char::is_numeric)?;
+    module.inst_fn("is_whitespace", char::is_whitespace)?;
+    module.inst_fn("is_uppercase", char::is_uppercase)?;
+    module.inst_fn("is_lowercase", char::is_lowercase)?;
+    module.inst_fn("is_control", char::is_control)?;
+    module.inst_fn("to_digit", to_digit)?;
+    module.inst_fn("to_int", to_int)?;
+    Ok(module)
+}
+
+/// Convert a digit in the given radix to a character.
+fn from_digit(digit: u32, radix: u32) -> Result<Option<char>, VmError> {
+    check_radix(radix)?;
+    Ok(std::char::from_digit(digit, radix))
+}
+
+/// Convert a character to a digit in the given radix.
+fn to_digit(c: char, radix: u32) -> Result<Option<u32>, VmError> {
+    check_radix(radix)?;
+    Ok(c.to_digit(radix))
+}
+
+/// Convert a character to its unicode scalar value.
+fn to_int(c: char) -> i64 {
+    c as i64
+}
+
+crate::__internal_impl_any!(ParseCharError);
@@ -0,0 +1,288 @@
+//! The `std::collections` module.
+
+use crate::{ContextError, Iterator, Key, Module, Value};
+use std::collections::{BTreeMap as StdBTreeMap, VecDeque as StdVecDeque};
+
+/// Construct the `std::collections` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "collections"]);
+
+    module.ty(&["HashMap"]).build::<HashMap>()?;
+    module.ty(&["HashSet"]).build::<HashSet>()?;
+    module.ty(&["BTreeMap"]).build::<BTreeMap>()?;
+    module.ty(&["VecDeque"]).build::<VecDeque>()?;
+
+    module.function(&["HashMap", "new"], HashMap::new)?;
+    module.inst_fn("len", HashMap::len)?;
+    module.inst_fn("is_empty", HashMap::is_empty)?;
+    module.inst_fn("insert", HashMap::insert)?;
+    module.inst_fn("get", HashMap::get)?;
+    module.inst_fn("contains_key", HashMap::contains_key)?;
+    module.inst_fn("remove", HashMap::remove)?;
+    module.inst_fn("clear", HashMap::clear)?;
+    module.inst_fn("iter", HashMap::iter)?;
+    module.inst_fn("keys", HashMap::keys)?;
+    module.inst_fn("values", HashMap::values)?;
+    module.inst_fn(crate::INTO_ITER, HashMap::iter)?;
+
+    module.function(&["HashSet", "new"], HashSet::new)?;
+    module.inst_fn("len", HashSet::len)?;
+    module.inst_fn("is_empty", HashSet::is_empty)?;
+    module.inst_fn("insert", HashSet::insert)?;
+    module.inst_fn("contains", HashSet::contains)?;
+    module.inst_fn("remove", HashSet::remove)?;
+    module.inst_fn("clear", HashSet::clear)?;
+    module.inst_fn("iter", HashSet::iter)?;
+    module.inst_fn(crate::INTO_ITER, HashSet::iter)?;
+
+    module.function(&["BTreeMap", "new"], BTreeMap::new)?;
+    module.inst_fn("len", BTreeMap::len)?;
+    module.inst_fn("is_empty", BTreeMap::is_empty)?;
+    module.inst_fn("insert", BTreeMap::insert)?;
+    module.inst_fn("get", BTreeMap::get)?;
+    module.inst_fn("contains_key", BTreeMap::contains_key)?;
+    module.inst_fn("remove", BTreeMap::remove)?;
+    module.inst_fn("clear", BTreeMap::clear)?;
+    module.inst_fn("first", BTreeMap::first)?;
+    module.inst_fn("last", BTreeMap::last)?;
+    module.inst_fn("iter", BTreeMap::iter)?;
+    module.inst_fn("keys", BTreeMap::keys)?;
+    module.inst_fn("values", BTreeMap::values)?;
+    module.inst_fn(crate::INTO_ITER, BTreeMap::iter)?;
+
+    module.function(&["VecDeque", "new"], VecDeque::new)?;
+    module.inst_fn("len", VecDeque::len)?;
+    module.inst_fn("is_empty", VecDeque::is_empty)?;
+    module.inst_fn("push_back", VecDeque::push_back)?;
+    module.inst_fn("push_front", VecDeque::push_front)?;
+    module.inst_fn("pop_back", VecDeque::pop_back)?;
+    module.inst_fn("pop_front", VecDeque::pop_front)?;
+    module.inst_fn("front", VecDeque::front)?;
+    module.inst_fn("back", VecDeque::back)?;
+    module.inst_fn("clear", VecDeque::clear)?;
+    module.inst_fn("iter", VecDeque::iter)?;
+    module.inst_fn(crate::INTO_ITER, VecDeque::iter)?;
+    Ok(module)
+}
+
+/// A hash map keyed by values.
+#[derive(Default)]
+pub struct HashMap {
+    map: crate::collections::HashMap<Key, Value>,
+}
+
+impl HashMap {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
+        self.map.insert(key, value)
+    }
+
+    fn get(&self, key: Key) -> Option<Value> {
+        self.map.get(&key).cloned()
+    }
+
+    fn contains_key(&self, key: Key) -> bool {
+        self.map.contains_key(&key)
+    }
+
+    fn remove(&mut self, key: Key) -> Option<Value> {
+        self.map.remove(&key)
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub(crate) fn iter(&self) -> Iterator {
+        entries(self.map.iter())
+    }
+
+    fn keys(&self) -> Iterator {
+        Iterator::from_values(self.map.keys().map(|key| key.clone().into_value()))
+    }
+
+    fn values(&self) -> Iterator {
+        Iterator::from_values(self.map.values().cloned())
+    }
+}
+
+/// A hash set of values.
+#[derive(Default)]
+pub struct HashSet {
+    set: crate::collections::HashSet<Key>,
+}
+
+impl HashSet {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    fn insert(&mut self, key: Key) -> bool {
+        self.set.insert(key)
+    }
+
+    fn contains(&self, key: Key) -> bool {
+        self.set.contains(&key)
+    }
+
+    fn remove(&mut self, key: Key) -> bool {
+        self.set.remove(&key)
+    }
+
+    fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    pub(crate) fn iter(&self) -> Iterator {
+        Iterator::from_values(self.set.iter().map(|key| key.clone().into_value()))
+    }
+}
+
+/// An ordered map keyed by values.
+#[derive(Default)]
+pub struct BTreeMap {
+    map: StdBTreeMap<Key, Value>,
+}
+
+impl BTreeMap {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn insert(&mut self, key: Key, value: Value) -> Option<Value> {
+        self.map.insert(key, value)
+    }
+
+    fn get(&self, key: Key) -> Option<Value> {
+        self.map.get(&key).cloned()
+    }
+
+    fn contains_key(&self, key: Key) -> bool {
+        self.map.contains_key(&key)
+    }
+
+    fn remove(&mut self, key: Key) -> Option<Value> {
+        self.map.remove(&key)
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn first(&self) -> Option<Value> {
+        self.map.iter().next().map(entry)
+    }
+
+    fn last(&self) -> Option<Value> {
+        self.map.iter().next_back().map(entry)
+    }
+
+    pub(crate) fn iter(&self) -> Iterator {
+        entries(self.map.iter())
+    }
+
+    fn keys(&self) -> Iterator {
+        Iterator::from_values(self.map.keys().map(|key| key.clone().into_value()))
+    }
+
+    fn values(&self) -> Iterator {
+        Iterator::from_values(self.map.values().cloned())
+    }
+}
+
+/// A double-ended queue of values.
+#[derive(Default)]
+pub struct VecDeque {
+    deque: StdVecDeque<Value>,
+}
+
+impl VecDeque {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    fn push_back(&mut self, value: Value) {
+        self.deque.push_back(value);
+    }
+
+    fn push_front(&mut self, value: Value) {
+        self.deque.push_front(value);
+    }
+
+    fn pop_back(&mut self) -> Option<Value> {
+        self.deque.pop_back()
+    }
+
+    fn pop_front(&mut self) -> Option<Value> {
+        self.deque.pop_front()
+    }
+
+    fn front(&self) -> Option<Value> {
+        self.deque.front().cloned()
+    }
+
+    fn back(&self) -> Option<Value> {
+        self.deque.back().cloned()
+    }
+
+    fn clear(&mut self) {
+        self.deque.clear();
+    }
+
+    pub(crate) fn iter(&self) -> Iterator {
+        Iterator::from_values(self.deque.iter().cloned())
+    }
+}
+
+/// Iterate over the entries of a map as `(key, value)` tuples.
+fn entries<'a, I>(iter: I) -> Iterator
+where
+    I: IntoIterator<Item = (&'a Key, &'a Value)>,
+{
+    Iterator::from_values(iter.into_iter().map(entry))
+}
+
+/// Convert a map entry into a `(key, value)` tuple.
+fn entry((key, value): (&Key, &Value)) -> Value {
+    Value::tuple(vec![key.clone().into_value(), value.clone()])
+}
+
+crate::__internal_impl_any!(HashMap);
+crate::__internal_impl_any!(HashSet);
+crate::__internal_impl_any!(BTreeMap);
+crate::__internal_impl_any!(VecDeque);
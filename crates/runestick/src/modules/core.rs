@@ -10,7 +10,6 @@ pub fn module() -> Result<Module, ContextError> {
 
     module.unit(&["unit"])?;
     module.ty(&["bool"]).build::<bool>()?;
-    module.ty(&["char"]).build::<char>()?;
     module.ty(&["byte"]).build::<u8>()?;
 
     module.function(&["print"], print_impl)?;
@@ -1,6 +1,6 @@
 //! The `std::float` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, Module, VmError};
 use std::num::ParseFloatError;
 
 /// Parse an integer.
@@ -13,6 +13,28 @@ fn to_integer(value: f64) -> i64 {
     value as i64
 }
 
+/// Restrict a value to the given interval, which must be non-empty.
+fn clamp(value: f64, min: f64, max: f64) -> Result<f64, VmError> {
+    if min.is_nan() || max.is_nan() || min > max {
+        return Err(VmError::panic(format!(
+            "invalid clamp interval `{}..={}`",
+            min, max
+        )));
+    }
+
+    Ok(value.clamp(min, max))
+}
+
+/// The raw bits of the float.
+fn to_bits(value: f64) -> i64 {
+    value.to_bits() as i64
+}
+
+/// Construct a float from its raw bits.
+fn from_bits(bits: i64) -> f64 {
+    f64::from_bits(bits as u64)
+}
+
 crate::__internal_impl_any!(ParseFloatError);
 
 /// Install the core package into the given functions namespace.
@@ -24,7 +46,47 @@ pub fn module() -> Result<Module, ContextError> {
         .ty(&["float", "ParseFloatError"])
         .build::<ParseFloatError>()?;
     module.function(&["float", "parse"], parse)?;
+    module.function(&["float", "from_bits"], from_bits)?;
     module.inst_fn("to_integer", to_integer)?;
 
+    module.inst_fn("sqrt", f64::sqrt)?;
+    module.inst_fn("cbrt", f64::cbrt)?;
+    module.inst_fn("powf", f64::powf)?;
+    module.inst_fn("powi", f64::powi)?;
+    module.inst_fn("exp", f64::exp)?;
+    module.inst_fn("ln", f64::ln)?;
+    module.inst_fn("log", f64::log)?;
+    module.inst_fn("log2", f64::log2)?;
+    module.inst_fn("log10", f64::log10)?;
+    module.inst_fn("hypot", f64::hypot)?;
+
+    module.inst_fn("sin", f64::sin)?;
+    module.inst_fn("cos", f64::cos)?;
+    module.inst_fn("tan", f64::tan)?;
+    module.inst_fn("asin", f64::asin)?;
+    module.inst_fn("acos", f64::acos)?;
+    module.inst_fn("atan", f64::atan)?;
+    module.inst_fn("atan2", f64::atan2)?;
+    module.inst_fn("sinh", f64::sinh)?;
+    module.inst_fn("cosh", f64::cosh)?;
+    module.inst_fn("tanh", f64::tanh)?;
+    module.inst_fn("to_degrees", f64::to_degrees)?;
+    module.inst_fn("to_radians", f64::to_radians)?;
+
+    module.inst_fn("floor", f64::floor)?;
+    module.inst_fn("ceil", f64::ceil)?;
+    module.inst_fn("round", f64::round)?;
+    module.inst_fn("trunc", f64::trunc)?;
+    module.inst_fn("fract", f64::fract)?;
+    module.inst_fn("abs", f64::abs)?;
+    module.inst_fn("signum", f64::signum)?;
+    module.inst_fn("min", f64::min)?;
+    module.inst_fn("max", f64::max)?;
+    module.inst_fn("clamp", clamp)?;
+
+    module.inst_fn("is_nan", f64::is_nan)?;
+    module.inst_fn("is_infinite", f64::is_infinite)?;
+    module.inst_fn("is_finite", f64::is_finite)?;
+    module.inst_fn("to_bits", to_bits)?;
     Ok(module)
 }
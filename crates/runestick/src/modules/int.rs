@@ -1,6 +1,6 @@
 //! The `std::int` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, Module, VmError, VmErrorKind};
 use std::num::ParseIntError;
 
 /// Construct the `std::int` module.
@@ -12,8 +12,10 @@ pub fn module() -> Result<Module, ContextError> {
         .ty(&["int", "ParseIntError"])
         .build::<ParseIntError>()?;
     module.function(&["int", "parse"], parse)?;
+    module.function(&["int", "parse_radix"], parse_radix)?;
 
     module.inst_fn("to_float", to_float)?;
+    module.inst_fn("to_string_radix", to_string_radix)?;
 
     module.inst_fn("checked_add", i64::checked_add)?;
     module.inst_fn("checked_sub", i64::checked_sub)?;
@@ -34,6 +36,19 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("saturating_pow", i64::saturating_pow)?;
 
     module.inst_fn("pow", i64::pow)?;
+    module.inst_fn("abs", abs)?;
+    module.inst_fn("signum", i64::signum)?;
+    module.inst_fn("min", min)?;
+    module.inst_fn("max", max)?;
+    module.inst_fn("clamp", clamp)?;
+    module.inst_fn("rem_euclid", rem_euclid)?;
+    module.inst_fn("is_positive", i64::is_positive)?;
+    module.inst_fn("is_negative", i64::is_negative)?;
+
+    module.inst_fn("count_ones", i64::count_ones)?;
+    module.inst_fn("count_zeros", i64::count_zeros)?;
+    module.inst_fn("leading_zeros", i64::leading_zeros)?;
+    module.inst_fn("trailing_zeros", i64::trailing_zeros)?;
     Ok(module)
 }
 
@@ -42,9 +57,85 @@ fn parse(s: &str) -> Result<i64, ParseIntError> {
     Ok(str::parse::<i64>(s)?)
 }
 
+/// Parse an integer in the given radix.
+fn parse_radix(s: &str, radix: u32) -> Result<Result<i64, ParseIntError>, VmError> {
+    check_radix(radix)?;
+    Ok(i64::from_str_radix(s, radix))
+}
+
 /// Convert a whole number to float.
 fn to_float(value: i64) -> f64 {
     value as f64
 }
 
+/// Format an integer in the given radix, using lowercase letters for digits
+/// above 9.
+fn to_string_radix(value: i64, radix: u32) -> Result<String, VmError> {
+    check_radix(radix)?;
+
+    let mut n = value.unsigned_abs();
+    let mut digits = Vec::new();
+
+    loop {
+        let digit = (n % u64::from(radix)) as u32;
+        // NB: digit is always less than the radix.
+        digits.push(std::char::from_digit(digit, radix).expect("digit in radix"));
+        n /= u64::from(radix);
+
+        if n == 0 {
+            break;
+        }
+    }
+
+    if value < 0 {
+        digits.push('-');
+    }
+
+    Ok(digits.into_iter().rev().collect())
+}
+
+/// Check that the radix is in the supported `2..=36` range.
+pub(crate) fn check_radix(radix: u32) -> Result<(), VmError> {
+    if !(2..=36).contains(&radix) {
+        return Err(VmError::panic(format!(
+            "radix must be in `2..=36`, but got `{}`",
+            radix
+        )));
+    }
+
+    Ok(())
+}
+
+fn abs(value: i64) -> Result<i64, VmError> {
+    Ok(value.checked_abs().ok_or(VmErrorKind::Overflow)?)
+}
+
+fn min(value: i64, other: i64) -> i64 {
+    value.min(other)
+}
+
+fn max(value: i64, other: i64) -> i64 {
+    value.max(other)
+}
+
+/// Restrict a value to the given interval, which must be non-empty.
+fn clamp(value: i64, min: i64, max: i64) -> Result<i64, VmError> {
+    if min > max {
+        return Err(VmError::panic(format!(
+            "invalid clamp interval `{}..={}`",
+            min, max
+        )));
+    }
+
+    Ok(value.clamp(min, max))
+}
+
+fn rem_euclid(value: i64, rhs: i64) -> Result<i64, VmError> {
+    if rhs == 0 {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    Ok(value.checked_rem_euclid(rhs).ok_or(VmErrorKind::Overflow)?)
+}
+
 crate::__internal_impl_any!(ParseIntError);
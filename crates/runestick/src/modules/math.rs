@@ -0,0 +1,21 @@
+//! The `std::math` module.
+
+use crate::{ContextError, Module};
+use std::f64::consts;
+
+/// Construct the `std::math` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "math"]);
+
+    module.constant(&["PI"], consts::PI)?;
+    module.constant(&["TAU"], consts::TAU)?;
+    module.constant(&["E"], consts::E)?;
+    module.constant(&["SQRT_2"], consts::SQRT_2)?;
+    module.constant(&["LN_2"], consts::LN_2)?;
+    module.constant(&["LN_10"], consts::LN_10)?;
+    module.constant(&["EPSILON"], f64::EPSILON)?;
+    module.constant(&["INFINITY"], f64::INFINITY)?;
+    module.constant(&["NEG_INFINITY"], f64::NEG_INFINITY)?;
+    module.constant(&["NAN"], f64::NAN)?;
+    Ok(module)
+}
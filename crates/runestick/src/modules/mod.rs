@@ -2,6 +2,8 @@
 //! machines.
 
 pub mod bytes;
+pub mod char;
+pub mod collections;
 pub mod core;
 pub mod float;
 pub mod fmt;
@@ -10,6 +12,7 @@ pub mod generator;
 pub mod int;
 pub mod io;
 pub mod iter;
+pub mod math;
 pub mod object;
 pub mod option;
 pub mod result;
@@ -17,3 +20,5 @@ pub mod stream;
 pub mod string;
 pub mod test;
 pub mod vec;
+pub mod weak;
+pub mod wide_int;
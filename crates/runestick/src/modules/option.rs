@@ -9,17 +9,28 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("is_some", Option::<Value>::is_some)?;
     module.inst_fn("unwrap_or_else", unwrap_or_else_impl)?;
     module.inst_fn("transpose", transpose_impl)?;
+    module.inst_fn("unwrap", unwrap_impl)?;
+    module.inst_fn("expect", expect_impl)?;
+    module.inst_fn("unwrap_or_default", unwrap_or_default_impl)?;
+    module.inst_fn("map", map_impl)?;
+    module.inst_fn("and_then", and_then_impl)?;
+    module.inst_fn("or_else", or_else_impl)?;
+    module.inst_fn("filter", filter_impl)?;
+    module.inst_fn("ok_or", ok_or_impl)?;
+    module.inst_fn("take", Option::<Value>::take)?;
+    module.inst_fn("iter", iter_impl)?;
+    module.inst_fn(crate::INTO_ITER, iter_impl)?;
     Ok(module)
 }
 
-use crate::{ContextError, Function, Module, Shared, Value, VmError};
+use crate::{ContextError, Function, Iterator, Module, Shared, Value, VmError};
 
-fn unwrap_or_else_impl(this: &Option<Value>, default: Function) -> Result<Value, VmError> {
+fn unwrap_or_else_impl(this: &Option<Value>, default: Shared<Function>) -> Result<Value, VmError> {
     if let Some(this) = this {
         return Ok(this.clone());
     }
 
-    Ok(default.call(())?)
+    Ok(default.borrow_ref()?.call(())?)
 }
 
 /// Transpose functions, translates an Option<Result<T, E>> into a `Result<Option<T>, E>`.
@@ -32,3 +43,67 @@ fn transpose_impl(this: &Option<Value>) -> Result<Value, VmError> {
         None => Ok(Value::from(Shared::new(None::<Value>))),
     })))
 }
+
+fn unwrap_impl(this: &Option<Value>) -> Result<Value, VmError> {
+    expect_impl(this, "called `Option::unwrap()` on a `None` value")
+}
+
+/// Unwrap the option, panicking with the given message if it's `None`.
+fn expect_impl(this: &Option<Value>, message: &str) -> Result<Value, VmError> {
+    match this {
+        Some(value) => Ok(value.clone()),
+        None => Err(VmError::panic(message.to_owned())),
+    }
+}
+
+/// Unwrap the option or return the unit value.
+///
+/// Values don't carry a static type, so the unit value is the only sensible
+/// default.
+fn unwrap_or_default_impl(this: &Option<Value>) -> Value {
+    this.clone().unwrap_or(Value::Unit)
+}
+
+fn map_impl(this: &Option<Value>, then: Shared<Function>) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) => Ok(Some(then.borrow_ref()?.call::<_, Value>((value.clone(),))?)),
+        None => Ok(None),
+    }
+}
+
+fn and_then_impl(this: &Option<Value>, then: Shared<Function>) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) => {
+            let then = then.borrow_ref()?;
+            Ok(then.call::<_, Option<Value>>((value.clone(),))?)
+        }
+        None => Ok(None),
+    }
+}
+
+fn or_else_impl(this: &Option<Value>, default: Shared<Function>) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) => Ok(Some(value.clone())),
+        None => Ok(default.borrow_ref()?.call::<_, Option<Value>>(())?),
+    }
+}
+
+fn filter_impl(
+    this: &Option<Value>,
+    predicate: Shared<Function>,
+) -> Result<Option<Value>, VmError> {
+    match this {
+        Some(value) if predicate.borrow_ref()?.call::<_, bool>((value.clone(),))? => {
+            Ok(Some(value.clone()))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn ok_or_impl(this: &Option<Value>, err: Value) -> Result<Value, Value> {
+    this.clone().ok_or(err)
+}
+
+fn iter_impl(this: &Option<Value>) -> Iterator {
+    Iterator::from_values(this.clone())
+}
@@ -1,6 +1,6 @@
 //! The `std::result` module.
 
-use crate::{ContextError, Module, Value};
+use crate::{ContextError, Function, Iterator, Module, Shared, Value, VmError};
 
 /// Construct the `std::result` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -8,6 +8,19 @@ pub fn module() -> Result<Module, ContextError> {
     module.result(&["Result"])?;
     module.inst_fn("is_ok", is_ok)?;
     module.inst_fn("is_err", is_err)?;
+    module.inst_fn("ok", ok)?;
+    module.inst_fn("err", err)?;
+    module.inst_fn("unwrap", unwrap)?;
+    module.inst_fn("expect", expect)?;
+    module.inst_fn("unwrap_or", unwrap_or)?;
+    module.inst_fn("unwrap_or_else", unwrap_or_else)?;
+    module.inst_fn("unwrap_or_default", unwrap_or_default)?;
+    module.inst_fn("map", map)?;
+    module.inst_fn("map_err", map_err)?;
+    module.inst_fn("and_then", and_then)?;
+    module.inst_fn("or_else", or_else)?;
+    module.inst_fn("iter", iter)?;
+    module.inst_fn(crate::INTO_ITER, iter)?;
     Ok(module)
 }
 
@@ -18,3 +31,103 @@ fn is_ok(result: &Result<Value, Value>) -> bool {
 fn is_err(result: &Result<Value, Value>) -> bool {
     result.is_err()
 }
+
+fn ok(result: &Result<Value, Value>) -> Option<Value> {
+    result.clone().ok()
+}
+
+fn err(result: &Result<Value, Value>) -> Option<Value> {
+    result.clone().err()
+}
+
+fn unwrap(result: &Result<Value, Value>) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(error) => Err(VmError::panic(format!(
+            "called `Result::unwrap()` on an `Err` value: {:?}",
+            error
+        ))),
+    }
+}
+
+/// Unwrap the result, panicking with the given message and the error if it's
+/// `Err`.
+fn expect(result: &Result<Value, Value>, message: &str) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(error) => Err(VmError::panic(format!("{}: {:?}", message, error))),
+    }
+}
+
+fn unwrap_or(result: &Result<Value, Value>, default: Value) -> Value {
+    result.clone().unwrap_or(default)
+}
+
+fn unwrap_or_else(
+    result: &Result<Value, Value>,
+    default: Shared<Function>,
+) -> Result<Value, VmError> {
+    match result {
+        Ok(value) => Ok(value.clone()),
+        Err(error) => Ok(default.borrow_ref()?.call::<_, Value>((error.clone(),))?),
+    }
+}
+
+/// Unwrap the result or return the unit value.
+///
+/// Values don't carry a static type, so the unit value is the only sensible
+/// default.
+fn unwrap_or_default(result: &Result<Value, Value>) -> Value {
+    result.clone().unwrap_or(Value::Unit)
+}
+
+fn map(
+    result: &Result<Value, Value>,
+    then: Shared<Function>,
+) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(Ok(then.borrow_ref()?.call::<_, Value>((value.clone(),))?)),
+        Err(error) => Ok(Err(error.clone())),
+    }
+}
+
+fn map_err(
+    result: &Result<Value, Value>,
+    then: Shared<Function>,
+) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(Ok(value.clone())),
+        Err(error) => {
+            let then = then.borrow_ref()?;
+            Ok(Err(then.call::<_, Value>((error.clone(),))?))
+        }
+    }
+}
+
+fn and_then(
+    result: &Result<Value, Value>,
+    then: Shared<Function>,
+) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(then
+            .borrow_ref()?
+            .call::<_, Result<Value, Value>>((value.clone(),))?),
+        Err(error) => Ok(Err(error.clone())),
+    }
+}
+
+fn or_else(
+    result: &Result<Value, Value>,
+    then: Shared<Function>,
+) -> Result<Result<Value, Value>, VmError> {
+    match result {
+        Ok(value) => Ok(Ok(value.clone())),
+        Err(error) => Ok(then
+            .borrow_ref()?
+            .call::<_, Result<Value, Value>>((error.clone(),))?),
+    }
+}
+
+fn iter(result: &Result<Value, Value>) -> Iterator {
+    Iterator::from_values(result.clone().ok())
+}
@@ -1,6 +1,10 @@
 //! The `std::string` module.
 
-use crate::{Bytes, ContextError, Module};
+use crate::{
+    Bytes, ContextError, FromValue, Iterator, Module, Shared, Value, VmError, VmErrorKind,
+};
+use std::char::ParseCharError;
+use std::num::{ParseFloatError, ParseIntError};
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
@@ -23,6 +27,24 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("clone", String::clone)?;
     module.inst_fn("shrink_to_fit", String::shrink_to_fit)?;
     module.inst_fn("char_at", char_at)?;
+    module.inst_fn("split", split)?;
+    module.inst_fn("splitn", splitn)?;
+    module.inst_fn("lines", lines)?;
+    module.inst_fn("trim", trim)?;
+    module.inst_fn("trim_start", trim_start)?;
+    module.inst_fn("trim_end", trim_end)?;
+    module.inst_fn("starts_with", starts_with)?;
+    module.inst_fn("ends_with", ends_with)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("find", find)?;
+    module.inst_fn("replace", replace)?;
+    module.inst_fn("to_uppercase", str::to_uppercase)?;
+    module.inst_fn("to_lowercase", str::to_lowercase)?;
+    module.inst_fn("chars", chars)?;
+    module.inst_fn("repeat", repeat)?;
+    module.inst_fn("parse_int", parse_int)?;
+    module.inst_fn("parse_float", parse_float)?;
+    module.inst_fn("parse_char", parse_char)?;
     module.inst_fn(crate::ADD, add)?;
     module.inst_fn(crate::ADD_ASSIGN, String::push_str)?;
     Ok(module)
@@ -44,6 +66,135 @@ fn char_at(s: &str, index: usize) -> Result<Option<char>, NotCharBoundary> {
     Ok(s[index..].chars().next())
 }
 
+/// A pattern to search for in a string, which is either a string or a
+/// character.
+enum Pattern {
+    String(String),
+    Char(char),
+}
+
+impl FromValue for Pattern {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(match value {
+            Value::Char(c) => Self::Char(c),
+            Value::StaticString(s) => Self::String(s.as_str().to_owned()),
+            Value::String(s) => Self::String(s.borrow_ref()?.clone()),
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        })
+    }
+}
+
+/// Apply a std pattern-based function with the given pattern.
+macro_rules! with_pattern {
+    ($pattern:expr, |$p:ident| $body:expr) => {
+        match &$pattern {
+            Pattern::String(string) => {
+                let $p = string.as_str();
+                $body
+            }
+            Pattern::Char(c) => {
+                let $p = *c;
+                $body
+            }
+        }
+    };
+}
+
+/// Construct an iterator over copies of the given substrings.
+fn substrings<'a, I>(iter: I) -> Iterator
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    Iterator::from_values(
+        iter.into_iter()
+            .map(|s| Value::String(Shared::new(s.to_owned()))),
+    )
+}
+
+fn split(s: &str, pattern: Pattern) -> Iterator {
+    with_pattern!(pattern, |p| substrings(s.split(p)))
+}
+
+fn splitn(s: &str, n: usize, pattern: Pattern) -> Iterator {
+    with_pattern!(pattern, |p| substrings(s.splitn(n, p)))
+}
+
+fn lines(s: &str) -> Iterator {
+    substrings(s.lines())
+}
+
+fn trim(s: &str) -> String {
+    s.trim().to_owned()
+}
+
+fn trim_start(s: &str) -> String {
+    s.trim_start().to_owned()
+}
+
+fn trim_end(s: &str) -> String {
+    s.trim_end().to_owned()
+}
+
+fn starts_with(s: &str, pattern: Pattern) -> bool {
+    with_pattern!(pattern, |p| s.starts_with(p))
+}
+
+fn ends_with(s: &str, pattern: Pattern) -> bool {
+    with_pattern!(pattern, |p| s.ends_with(p))
+}
+
+fn contains(s: &str, pattern: Pattern) -> bool {
+    with_pattern!(pattern, |p| s.contains(p))
+}
+
+/// Find the byte index of the first match of the pattern.
+fn find(s: &str, pattern: Pattern) -> Option<usize> {
+    with_pattern!(pattern, |p| s.find(p))
+}
+
+fn replace(s: &str, from: Pattern, to: &str) -> String {
+    with_pattern!(from, |p| s.replace(p, to))
+}
+
+/// Repeat the string, raising an error if the result would be too large.
+fn repeat(s: &str, count: usize) -> Result<String, VmError> {
+    if s.is_empty() {
+        return Ok(String::new());
+    }
+
+    let len = s.len().checked_mul(count).ok_or(VmErrorKind::Overflow)?;
+    let mut out = String::new();
+
+    if out.try_reserve_exact(len).is_err() {
+        return Err(VmError::from(VmErrorKind::Overflow));
+    }
+
+    for _ in 0..count {
+        out.push_str(s);
+    }
+
+    Ok(out)
+}
+
+fn chars(s: &str) -> Iterator {
+    Iterator::from_values(s.chars().map(Value::Char))
+}
+
+/// Parse the string as an integer.
+fn parse_int(s: &str) -> Result<i64, ParseIntError> {
+    str::parse::<i64>(s)
+}
+
+/// Parse the string as a float.
+fn parse_float(s: &str) -> Result<f64, ParseFloatError> {
+    str::parse::<f64>(s)
+}
+
+/// Parse the string as a single character.
+fn parse_char(s: &str) -> Result<char, ParseCharError> {
+    str::parse::<char>(s)
+}
+
 /// The add operation for strings.
 fn add(a: &str, b: &str) -> String {
     let mut string = String::with_capacity(a.len() + b.len());
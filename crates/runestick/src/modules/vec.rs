@@ -1,60 +1,241 @@
 //! The `std::vec` module.
 
-use crate::{ContextError, Module, Value};
-use std::iter::Rev;
+use crate::{ContextError, Function, Iterator, Module, Shared, Value, VmError, VmErrorKind};
+use std::cmp;
 
 /// Construct the `std::vec` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "vec"]);
 
     module.ty(&["Vec"]).build::<Vec<Value>>()?;
-    module.ty(&["Iter"]).build::<Iter>()?;
-    module.ty(&["Rev"]).build::<Rev<Iter>>()?;
 
     module.function(&["Vec", "new"], Vec::<Value>::new)?;
     module.inst_fn("iter", vec_iter)?;
     module.inst_fn("len", Vec::<Value>::len)?;
+    module.inst_fn("is_empty", Vec::<Value>::is_empty)?;
     module.inst_fn("push", Vec::<Value>::push)?;
     module.inst_fn("clear", Vec::<Value>::clear)?;
     module.inst_fn("pop", Vec::<Value>::pop)?;
+    module.inst_fn("insert", insert)?;
+    module.inst_fn("remove", remove)?;
+    module.inst_fn("swap", swap)?;
+    module.inst_fn("extend", extend)?;
+    module.inst_fn("truncate", Vec::<Value>::truncate)?;
+    module.inst_fn("reverse", reverse)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("index_of", index_of)?;
+    module.inst_fn("sort", sort)?;
+    module.inst_fn("sort_by", sort_by)?;
+    module.inst_fn("dedup", dedup)?;
+    module.inst_fn("retain", retain)?;
+    module.inst_fn("join", join)?;
+    module.inst_fn("first", first)?;
+    module.inst_fn("last", last)?;
+    module.inst_fn("get", get)?;
+    module.inst_fn("split_off", split_off)?;
+    module.inst_fn("windows", windows)?;
+    module.inst_fn("chunks", chunks)?;
 
     module.inst_fn(crate::INTO_ITER, vec_iter)?;
-    module.inst_fn("next", Iter::next)?;
-    module.inst_fn(crate::NEXT, Iter::next)?;
-    module.inst_fn(crate::INTO_ITER, Iter::into_iter)?;
-
-    module.inst_fn("rev", Iter::rev)?;
-    module.inst_fn("next", Rev::<Iter>::next)?;
-    module.inst_fn("next_back", Rev::<Iter>::next_back)?;
-    module.inst_fn(crate::NEXT, Rev::<Iter>::next)?;
-    module.inst_fn(crate::INTO_ITER, Rev::<Iter>::into_iter)?;
     Ok(module)
 }
 
-/// An iterator over a vector.
-pub struct Iter {
-    iter: std::vec::IntoIter<Value>,
+fn vec_iter(vec: &[Value]) -> Iterator {
+    Iterator::from_values(vec.iter().cloned())
 }
 
-impl Iterator for Iter {
-    type Item = Value;
+/// Check that `index` is in bounds for a collection of length `len`.
+fn check_index(index: usize, len: usize) -> Result<(), VmError> {
+    if index >= len {
+        return Err(VmError::from(VmErrorKind::IndexOutOfBounds { index, len }));
+    }
+
+    Ok(())
+}
+
+fn insert(vec: &mut Vec<Value>, index: usize, value: Value) -> Result<(), VmError> {
+    // NB: inserting at the end is permitted.
+    check_index(index, vec.len() + 1)?;
+    vec.insert(index, value);
+    Ok(())
+}
+
+fn remove(vec: &mut Vec<Value>, index: usize) -> Result<Value, VmError> {
+    check_index(index, vec.len())?;
+    Ok(vec.remove(index))
+}
+
+// NB: mutable slices can't be used as instance receivers.
+#[allow(clippy::ptr_arg)]
+fn swap(vec: &mut Vec<Value>, a: usize, b: usize) -> Result<(), VmError> {
+    check_index(a, vec.len())?;
+    check_index(b, vec.len())?;
+    vec.swap(a, b);
+    Ok(())
+}
+
+fn extend(vec: &mut Vec<Value>, value: Value) -> Result<(), VmError> {
+    let mut iter = Iterator::from_value(value)?;
+
+    while let Some(value) = iter.next()? {
+        vec.push(value);
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::ptr_arg)]
+fn reverse(vec: &mut Vec<Value>) {
+    vec.reverse();
+}
+
+fn contains(vec: &[Value], value: Value) -> Result<bool, VmError> {
+    Ok(index_of(vec, value)?.is_some())
+}
+
+fn index_of(vec: &[Value], value: Value) -> Result<Option<usize>, VmError> {
+    for (index, candidate) in vec.iter().enumerate() {
+        if Value::value_ptr_eq(candidate, &value)? {
+            return Ok(Some(index));
+        }
+    }
+
+    Ok(None)
+}
+
+fn sort(vec: &mut Vec<Value>) -> Result<(), VmError> {
+    merge_sort(vec, Value::value_ptr_cmp)
+}
+
+/// Sort using a comparator function, which returns a negative integer if the
+/// first argument is less than the second, zero if they're equal and a
+/// positive integer otherwise.
+fn sort_by(vec: &mut Vec<Value>, comparator: Shared<Function>) -> Result<(), VmError> {
+    let comparator = comparator.borrow_ref()?;
+
+    merge_sort(vec, |a, b| {
+        let ordering = comparator.call::<_, i64>((a.clone(), b.clone()))?;
+        Ok(ordering.cmp(&0))
+    })
+}
+
+/// A stable merge sort with a fallible comparator.
+///
+/// The sort in the standard library can't be used, since it can't stop
+/// sorting when the comparator errors and might panic if a comparator
+/// provided by a script isn't a total order.
+fn merge_sort<C>(vec: &mut Vec<Value>, mut compare: C) -> Result<(), VmError>
+where
+    C: FnMut(&Value, &Value) -> Result<cmp::Ordering, VmError>,
+{
+    let len = vec.len();
+    let mut width = 1;
+
+    while width < len {
+        let mut sorted = Vec::with_capacity(len);
 
-    fn next(&mut self) -> Option<Value> {
-        self.iter.next()
+        for start in (0..len).step_by(width * 2) {
+            let mid = usize::min(start + width, len);
+            let end = usize::min(start + width * 2, len);
+            let (mut a, mut b) = (start, mid);
+
+            while a < mid && b < end {
+                if compare(&vec[b], &vec[a])? == cmp::Ordering::Less {
+                    sorted.push(vec[b].clone());
+                    b += 1;
+                } else {
+                    sorted.push(vec[a].clone());
+                    a += 1;
+                }
+            }
+
+            sorted.extend(vec[a..mid].iter().cloned());
+            sorted.extend(vec[b..end].iter().cloned());
+        }
+
+        *vec = sorted;
+        width *= 2;
     }
+
+    Ok(())
 }
 
-impl DoubleEndedIterator for Iter {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.iter.next_back()
+fn dedup(vec: &mut Vec<Value>) -> Result<(), VmError> {
+    let mut keep = Vec::with_capacity(vec.len());
+
+    for (index, value) in vec.iter().enumerate() {
+        keep.push(index == 0 || !Value::value_ptr_eq(&vec[index - 1], value)?);
     }
+
+    let mut keep = keep.into_iter();
+    vec.retain(|_| keep.next().unwrap_or_default());
+    Ok(())
 }
 
-fn vec_iter(vec: &[Value]) -> Iter {
-    Iter {
-        iter: vec.to_vec().into_iter(),
+fn retain(vec: &mut Vec<Value>, predicate: Shared<Function>) -> Result<(), VmError> {
+    let predicate = predicate.borrow_ref()?;
+    let mut keep = Vec::with_capacity(vec.len());
+
+    for value in vec.iter() {
+        keep.push(predicate.call::<_, bool>((value.clone(),))?);
     }
+
+    let mut keep = keep.into_iter();
+    vec.retain(|_| keep.next().unwrap_or_default());
+    Ok(())
 }
 
-crate::__internal_impl_any!(Iter);
-crate::__internal_impl_any!(Rev<Iter>);
+fn join(vec: &[Value], separator: &str) -> Result<String, VmError> {
+    let mut string = String::new();
+
+    for (index, value) in vec.iter().enumerate() {
+        if index > 0 {
+            string.push_str(separator);
+        }
+
+        match value {
+            Value::StaticString(s) => string.push_str(s.as_str()),
+            Value::String(s) => string.push_str(&s.borrow_ref()?),
+            actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+        }
+    }
+
+    Ok(string)
+}
+
+fn first(vec: &[Value]) -> Option<Value> {
+    vec.first().cloned()
+}
+
+fn last(vec: &[Value]) -> Option<Value> {
+    vec.last().cloned()
+}
+
+fn get(vec: &[Value], index: usize) -> Option<Value> {
+    vec.get(index).cloned()
+}
+
+fn split_off(vec: &mut Vec<Value>, at: usize) -> Result<Vec<Value>, VmError> {
+    check_index(at, vec.len() + 1)?;
+    Ok(vec.split_off(at))
+}
+
+fn windows(vec: &[Value], size: usize) -> Result<Iterator, VmError> {
+    if size == 0 {
+        return Err(VmError::panic("window size must be non-zero"));
+    }
+
+    Ok(Iterator::from_values(
+        vec.windows(size).map(|window| Value::vec(window.to_vec())),
+    ))
+}
+
+fn chunks(vec: &[Value], size: usize) -> Result<Iterator, VmError> {
+    if size == 0 {
+        return Err(VmError::panic("chunk size must be non-zero"));
+    }
+
+    Ok(Iterator::from_values(
+        vec.chunks(size).map(|chunk| Value::vec(chunk.to_vec())),
+    ))
+}
@@ -0,0 +1,18 @@
+//! The `std::weak` module.
+
+use crate::{ContextError, Module, Value, VmError, WeakValue};
+
+/// Construct the `std::weak` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "weak"]);
+    module.ty(&["Weak"]).build::<WeakValue>()?;
+
+    module.function(&["Weak", "new"], new)?;
+    module.inst_fn("upgrade", WeakValue::upgrade)?;
+    module.inst_fn("is_alive", WeakValue::is_alive)?;
+    Ok(module)
+}
+
+fn new(value: Value) -> Result<WeakValue, VmError> {
+    WeakValue::new(&value)
+}
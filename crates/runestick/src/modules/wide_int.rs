@@ -0,0 +1,116 @@
+//! The `std::u64`, `std::i128`, `std::u128` and `std::bigint` modules.
+
+use crate::{BigInt, ContextError, FromValue, Module, ToValue, VmError, VmErrorKind};
+use num_bigint::ParseBigIntError;
+use std::num::ParseIntError;
+
+/// Construct the wide integer modules.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std"]);
+
+    module.ty(&["u64"]).build::<u64>()?;
+    module.ty(&["i128"]).build::<i128>()?;
+    module.ty(&["u128"]).build::<u128>()?;
+    module.ty(&["bigint"]).build::<BigInt>()?;
+    module
+        .ty(&["bigint", "ParseBigIntError"])
+        .build::<ParseBigIntError>()?;
+
+    module.function(&["u64", "parse"], parse::<u64>)?;
+    module.function(&["i128", "parse"], parse::<i128>)?;
+    module.function(&["u128", "parse"], parse::<u128>)?;
+    module.function(&["bigint", "parse"], parse_bigint)?;
+
+    module.constant(&["u64", "MIN"], u64::MIN)?;
+    module.constant(&["u64", "MAX"], u64::MAX)?;
+    module.constant(&["i128", "MIN"], i128::MIN)?;
+    module.constant(&["i128", "MAX"], i128::MAX)?;
+    module.constant(&["u128", "MIN"], u128::MIN)?;
+    module.constant(&["u128", "MAX"], u128::MAX)?;
+
+    module.inst_fn("to_u64", convert::<i64, u64>)?;
+    module.inst_fn("to_i128", convert::<i64, i128>)?;
+    module.inst_fn("to_u128", convert::<i64, u128>)?;
+    module.inst_fn("to_bigint", convert::<i64, BigInt>)?;
+
+    module.inst_fn("to_int", convert::<u64, i64>)?;
+    module.inst_fn("to_i128", convert::<u64, i128>)?;
+    module.inst_fn("to_u128", convert::<u64, u128>)?;
+    module.inst_fn("to_bigint", convert::<u64, BigInt>)?;
+    module.inst_fn("to_float", to_float::<u64>)?;
+    module.inst_fn("pow", pow::<u64>)?;
+    module.inst_fn("wrapping_add", u64::wrapping_add)?;
+    module.inst_fn("wrapping_sub", u64::wrapping_sub)?;
+    module.inst_fn("wrapping_mul", u64::wrapping_mul)?;
+
+    module.inst_fn("to_int", convert::<i128, i64>)?;
+    module.inst_fn("to_u64", convert::<i128, u64>)?;
+    module.inst_fn("to_u128", convert::<i128, u128>)?;
+    module.inst_fn("to_bigint", convert::<i128, BigInt>)?;
+    module.inst_fn("to_float", to_float::<i128>)?;
+    module.inst_fn("pow", pow::<i128>)?;
+    module.inst_fn("wrapping_add", i128::wrapping_add)?;
+    module.inst_fn("wrapping_sub", i128::wrapping_sub)?;
+    module.inst_fn("wrapping_mul", i128::wrapping_mul)?;
+
+    module.inst_fn("to_int", convert::<u128, i64>)?;
+    module.inst_fn("to_u64", convert::<u128, u64>)?;
+    module.inst_fn("to_i128", convert::<u128, i128>)?;
+    module.inst_fn("to_bigint", convert::<u128, BigInt>)?;
+    module.inst_fn("to_float", to_float::<u128>)?;
+    module.inst_fn("pow", pow::<u128>)?;
+    module.inst_fn("wrapping_add", u128::wrapping_add)?;
+    module.inst_fn("wrapping_sub", u128::wrapping_sub)?;
+    module.inst_fn("wrapping_mul", u128::wrapping_mul)?;
+
+    module.inst_fn("to_int", convert::<BigInt, i64>)?;
+    module.inst_fn("to_u64", convert::<BigInt, u64>)?;
+    module.inst_fn("to_i128", convert::<BigInt, i128>)?;
+    module.inst_fn("to_u128", convert::<BigInt, u128>)?;
+    module.inst_fn("to_float", to_float::<BigInt>)?;
+    module.inst_fn("pow", pow::<BigInt>)?;
+    Ok(module)
+}
+
+/// Parse an integer.
+fn parse<T>(s: &str) -> Result<T, ParseIntError>
+where
+    T: std::str::FromStr<Err = ParseIntError>,
+{
+    str::parse::<T>(s)
+}
+
+/// Parse an arbitrary precision integer.
+fn parse_bigint(s: &str) -> Result<BigInt, ParseBigIntError> {
+    str::parse::<BigInt>(s)
+}
+
+/// Convert between integer types, raising an error if the value is out of
+/// range for the type converted to.
+fn convert<S, T>(value: S) -> Result<T, VmError>
+where
+    S: ToValue,
+    T: FromValue,
+{
+    T::from_value(value.to_value()?)
+}
+
+/// Raise an integer to a power, raising an error on overflow.
+fn pow<T>(value: T, exp: u32) -> Result<T, VmError>
+where
+    T: Clone + num_traits::One + num_traits::CheckedMul,
+{
+    Ok(num_traits::checked_pow(value, exp as usize).ok_or(VmErrorKind::Overflow)?)
+}
+
+/// Convert an integer to the nearest float, which is infinite if it's out of
+/// range.
+fn to_float<T>(value: T) -> f64
+where
+    T: num_traits::ToPrimitive,
+{
+    // NB: conversions of integers saturate to infinity, so they never fail.
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+crate::__internal_impl_any!(ParseBigIntError);
@@ -0,0 +1,199 @@
+//! Support for integers which are wider than the `i64` stored in
+//! [Value::Integer].
+//!
+//! Arithmetic between wide integers follows these promotion rules:
+//! * Integers of the same type are combined with checked arithmetic, so
+//!   overflows are reported as errors just like for `int`.
+//! * An `int` combined with a `u64`, `i128` or `u128` is converted into the
+//!   wider type, which fails if it's out of range for it (like a negative
+//!   `int` combined with a `u64`).
+//! * Any integer combined with a `bigint` is promoted to a `bigint`.
+//! * Any other combination, like a `u64` combined with an `i128`, requires an
+//!   explicit conversion.
+//!
+//! Comparisons are exact between all integer types. Equality follows the
+//! same rule everywhere, so an integer is equal to any other integer with the
+//! same value regardless of their types: `1u64 == 1` holds, the pattern `1`
+//! matches `1u64`, and `1u64` and `1` are the same key in a `HashMap`.
+
+use crate::{BigInt, Value, VmError, VmErrorKind, VmIntegerRepr};
+use num_traits::Zero as _;
+use std::cmp;
+use std::convert::TryFrom;
+
+/// A checked arithmetic operation.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum NumericOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// Operands promoted to a common integer type.
+enum Promoted {
+    U64(u64, u64),
+    I128(i128, i128),
+    U128(u128, u128),
+    BigInt(BigInt, BigInt),
+}
+
+/// Apply a checked arithmetic operation to two integers where at least one of
+/// them is wider than `int`.
+///
+/// Returns `None` if the operands can't be promoted to a common type. The
+/// `error` is raised if the operation over- or underflows, or divides by zero.
+pub(crate) fn checked_op<E>(
+    op: NumericOp,
+    lhs: &Value,
+    rhs: &Value,
+    error: E,
+) -> Result<Option<Value>, VmError>
+where
+    E: FnOnce() -> VmError,
+{
+    macro_rules! checked {
+        ($a:expr, $b:expr) => {{
+            let (a, b) = ($a, $b);
+
+            let out = match op {
+                NumericOp::Add => a.checked_add(b),
+                NumericOp::Sub => a.checked_sub(b),
+                NumericOp::Mul => a.checked_mul(b),
+                NumericOp::Div => a.checked_div(b),
+                NumericOp::Rem => a.checked_rem(b),
+            };
+
+            Value::from(out.ok_or_else(error)?)
+        }};
+    }
+
+    Ok(Some(match promote(lhs, rhs)? {
+        Some(Promoted::U64(a, b)) => checked!(a, b),
+        Some(Promoted::I128(a, b)) => checked!(a, b),
+        Some(Promoted::U128(a, b)) => checked!(a, b),
+        Some(Promoted::BigInt(a, b)) => {
+            let out = match op {
+                NumericOp::Add => a + b,
+                NumericOp::Sub => a - b,
+                NumericOp::Mul => a * b,
+                NumericOp::Div | NumericOp::Rem if b.is_zero() => return Err(error()),
+                NumericOp::Div => a / b,
+                NumericOp::Rem => a % b,
+            };
+
+            Value::from(out)
+        }
+        None => return Ok(None),
+    }))
+}
+
+/// Compare two integers where at least one of them is wider than `int`.
+///
+/// Returns `None` if either value isn't an integer, or if neither value is a
+/// wide integer.
+pub(crate) fn cmp(lhs: &Value, rhs: &Value) -> Option<cmp::Ordering> {
+    match (lhs, rhs) {
+        (Value::U64(a), Value::U64(b)) => Some(a.cmp(b)),
+        (Value::I128(a), Value::I128(b)) => Some(a.cmp(b)),
+        (Value::U128(a), Value::U128(b)) => Some(a.cmp(b)),
+        (Value::BigInt(a), Value::BigInt(b)) => Some(a.cmp(b)),
+        _ if is_wide(lhs) || is_wide(rhs) => Some(to_bigint(lhs)?.cmp(&to_bigint(rhs)?)),
+        _ => None,
+    }
+}
+
+/// Test if the value is an integer of any width which is equal to the given
+/// `int`.
+pub(crate) fn eq_integer(value: &Value, integer: i64) -> bool {
+    match value {
+        Value::Integer(n) => *n == integer,
+        Value::U64(n) => u64::try_from(integer).ok() == Some(*n),
+        Value::I128(n) => **n == i128::from(integer),
+        Value::U128(n) => u128::try_from(integer).ok() == Some(**n),
+        Value::BigInt(n) => **n == BigInt::from(integer),
+        _ => false,
+    }
+}
+
+/// Convert an integer value of any width into a primitive integer, raising a
+/// coercion error if it's out of range.
+pub(crate) fn from_value<T>(value: Value) -> Result<T, VmError>
+where
+    T: TryFrom<i64> + TryFrom<u64> + TryFrom<i128> + TryFrom<u128>,
+    T: for<'a> TryFrom<&'a BigInt>,
+{
+    match value {
+        Value::Integer(n) => coerce(T::try_from(n).ok(), || VmIntegerRepr::I64(n)),
+        Value::U64(n) => coerce(T::try_from(n).ok(), || VmIntegerRepr::U64(n)),
+        Value::I128(n) => coerce(T::try_from(*n).ok(), || VmIntegerRepr::I128(*n)),
+        Value::U128(n) => coerce(T::try_from(*n).ok(), || VmIntegerRepr::U128(*n)),
+        Value::BigInt(n) => coerce(T::try_from(&*n).ok(), || {
+            VmIntegerRepr::BigInt((*n).clone())
+        }),
+        actual => Err(VmError::expected::<i64>(actual.type_info()?)),
+    }
+}
+
+/// Convert an integer value of any width into a `bigint`.
+pub(crate) fn to_bigint(value: &Value) -> Option<BigInt> {
+    Some(match value {
+        Value::Integer(n) => BigInt::from(*n),
+        Value::U64(n) => BigInt::from(*n),
+        Value::I128(n) => BigInt::from(**n),
+        Value::U128(n) => BigInt::from(**n),
+        Value::BigInt(n) => (**n).clone(),
+        _ => return None,
+    })
+}
+
+/// Test if the value is an integer which is wider than `int`.
+fn is_wide(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::U64(..) | Value::I128(..) | Value::U128(..) | Value::BigInt(..)
+    )
+}
+
+/// Promote two operands to a common integer type.
+fn promote(lhs: &Value, rhs: &Value) -> Result<Option<Promoted>, VmError> {
+    Ok(Some(match (lhs, rhs) {
+        (Value::U64(a), Value::U64(b)) => Promoted::U64(*a, *b),
+        (Value::U64(a), Value::Integer(b)) => Promoted::U64(*a, widen(*b)?),
+        (Value::Integer(a), Value::U64(b)) => Promoted::U64(widen(*a)?, *b),
+        (Value::I128(a), Value::I128(b)) => Promoted::I128(**a, **b),
+        (Value::I128(a), Value::Integer(b)) => Promoted::I128(**a, i128::from(*b)),
+        (Value::Integer(a), Value::I128(b)) => Promoted::I128(i128::from(*a), **b),
+        (Value::U128(a), Value::U128(b)) => Promoted::U128(**a, **b),
+        (Value::U128(a), Value::Integer(b)) => Promoted::U128(**a, widen(*b)?),
+        (Value::Integer(a), Value::U128(b)) => Promoted::U128(widen(*a)?, **b),
+        (Value::BigInt(..), _) | (_, Value::BigInt(..)) => match (to_bigint(lhs), to_bigint(rhs)) {
+            (Some(a), Some(b)) => Promoted::BigInt(a, b),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    }))
+}
+
+/// Convert an `int` into an unsigned wide integer.
+fn widen<T>(n: i64) -> Result<T, VmError>
+where
+    T: TryFrom<i64>,
+{
+    coerce(T::try_from(n).ok(), || VmIntegerRepr::I64(n))
+}
+
+/// Raise a coercion error if an integer was out of range for its target type.
+fn coerce<T, F>(number: Option<T>, from: F) -> Result<T, VmError>
+where
+    F: FnOnce() -> VmIntegerRepr,
+{
+    match number {
+        Some(number) => Ok(number),
+        None => Err(VmError::from(VmErrorKind::ValueToIntegerCoercionError {
+            from: from(),
+            to: std::any::type_name::<T>(),
+        })),
+    }
+}
@@ -0,0 +1,368 @@
+//! A profiler recording where time is spent while running a virtual machine.
+
+use crate::collections::HashMap;
+use crate::context::ContextSignature;
+use crate::{Hash, Inst, Value, Vm, VmError, VmExecution};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// The name used for frames which can't be resolved to a function.
+const UNKNOWN: &str = "<unknown>";
+
+/// A profile of a single function, as recorded by a [Profiler].
+#[derive(Debug, Clone)]
+pub struct FunctionProfile {
+    /// The name of the function.
+    pub name: String,
+    /// If the function is a native function.
+    pub native: bool,
+    /// The number of times the function was called.
+    pub calls: usize,
+    /// The number of instructions executed in the function itself.
+    pub instructions: usize,
+    /// The time spent in the function itself.
+    pub self_time: Duration,
+    /// The time spent in the function, including everything it called.
+    pub total_time: Duration,
+}
+
+/// Samples recorded for a single call stack.
+#[derive(Debug, Default, Clone, Copy)]
+struct Sample {
+    instructions: usize,
+    time: Duration,
+}
+
+/// A frame in the call stack being profiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Frame {
+    /// Index of the name of the frame.
+    name: usize,
+    /// If the frame is a native function.
+    native: bool,
+}
+
+/// A profiler for an execution.
+///
+/// The profiler steps through the execution one instruction at a time,
+/// measuring the time spent on each instruction and attributing it to the
+/// function and the call stack it was executed in. Functions are resolved
+/// through the debug info of the unit being executed, so the unit should be
+/// compiled with debug info.
+///
+/// Time spent in native functions which are called from scripts is attributed
+/// to the native function, separately from the script function calling it.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    /// Interned frame names.
+    names: Vec<String>,
+    /// Reverse lookup of interned frame names.
+    names_rev: HashMap<String, usize>,
+    /// The current call stack.
+    stack: Vec<Frame>,
+    /// Call counts by frame.
+    calls: HashMap<Frame, usize>,
+    /// Samples by call stack.
+    samples: HashMap<Vec<Frame>, Sample>,
+}
+
+impl Profiler {
+    /// Construct a new profiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the given execution to completion while profiling it.
+    ///
+    /// If any async instructions are encountered, this will error.
+    pub fn run(&mut self, execution: &mut VmExecution) -> Result<Value, VmError> {
+        self.enter(execution);
+
+        loop {
+            let depth = execution.call_depth();
+            let native = self.native_call(execution.vm()?);
+
+            let start = Instant::now();
+            let result = execution.step()?;
+            let time = start.elapsed();
+
+            self.record(native, time);
+
+            if let Some(value) = result {
+                self.stack.clear();
+                return Ok(value);
+            }
+
+            self.adjust(execution, depth);
+        }
+    }
+
+    /// Run the given execution to completion with support for async
+    /// instructions while profiling it.
+    pub async fn async_run(&mut self, execution: &mut VmExecution) -> Result<Value, VmError> {
+        self.enter(execution);
+
+        loop {
+            let depth = execution.call_depth();
+            let native = self.native_call(execution.vm()?);
+
+            let start = Instant::now();
+            let result = execution.async_step().await?;
+            let time = start.elapsed();
+
+            self.record(native, time);
+
+            if let Some(value) = result {
+                self.stack.clear();
+                return Ok(value);
+            }
+
+            self.adjust(execution, depth);
+        }
+    }
+
+    /// Get the profiles of all functions which have been recorded, ordered by
+    /// the time spent in the functions themselves.
+    pub fn functions(&self) -> Vec<FunctionProfile> {
+        let mut functions = HashMap::<Frame, FunctionProfile>::new();
+
+        for (stack, sample) in &self.samples {
+            for (n, frame) in stack.iter().enumerate() {
+                // NB: recursive functions only count once per stack.
+                if stack[..n].contains(frame) {
+                    continue;
+                }
+
+                let profile = functions.entry(*frame).or_insert_with(|| FunctionProfile {
+                    name: self.names[frame.name].clone(),
+                    native: frame.native,
+                    calls: self.calls.get(frame).copied().unwrap_or_default(),
+                    instructions: 0,
+                    self_time: Duration::default(),
+                    total_time: Duration::default(),
+                });
+
+                profile.total_time += sample.time;
+            }
+
+            if let Some(frame) = stack.last() {
+                if let Some(profile) = functions.get_mut(frame) {
+                    profile.instructions += sample.instructions;
+                    profile.self_time += sample.time;
+                }
+            }
+        }
+
+        let mut functions = functions.into_iter().map(|(_, f)| f).collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.self_time.cmp(&a.self_time).then(a.name.cmp(&b.name)));
+        functions
+    }
+
+    /// Write all recorded call stacks in the folded stack format used by
+    /// flamegraph tools, where each line is a semicolon-separated call stack
+    /// followed by the number of nanoseconds spent in it.
+    pub fn write_folded<O>(&self, mut out: O) -> io::Result<()>
+    where
+        O: io::Write,
+    {
+        let mut lines = Vec::new();
+
+        for (stack, sample) in &self.samples {
+            let stack = stack
+                .iter()
+                .map(|frame| self.names[frame.name].as_str())
+                .collect::<Vec<_>>()
+                .join(";");
+
+            lines.push((stack, sample.time.as_nanos()));
+        }
+
+        lines.sort();
+
+        for (stack, nanos) in lines {
+            writeln!(out, "{} {}", stack, nanos)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a table of the `limit` functions which the most time was spent
+    /// in.
+    pub fn write_summary<O>(&self, mut out: O, limit: usize) -> io::Result<()>
+    where
+        O: io::Write,
+    {
+        let functions = self.functions();
+        let total = functions
+            .iter()
+            .map(|f| f.self_time)
+            .sum::<Duration>()
+            .as_nanos()
+            .max(1);
+
+        writeln!(
+            out,
+            "{:>7} {:>12} {:>12} {:>10} {:>12}  function",
+            "self %", "self", "total", "calls", "instructions"
+        )?;
+
+        for f in functions.iter().take(limit) {
+            let name = if f.native {
+                format!("{} (native)", f.name)
+            } else {
+                f.name.clone()
+            };
+
+            writeln!(
+                out,
+                "{:>6.2}% {:>12} {:>12} {:>10} {:>12}  {}",
+                f.self_time.as_nanos() as f64 * 100.0 / total as f64,
+                format!("{:?}", f.self_time),
+                format!("{:?}", f.total_time),
+                f.calls,
+                f.instructions,
+                name
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Set up the call stack for the given execution.
+    fn enter(&mut self, execution: &VmExecution) {
+        self.stack.clear();
+
+        for frame in self.call_stack(execution) {
+            self.push(frame);
+        }
+    }
+
+    /// Record the time spent on a single instruction.
+    fn record(&mut self, native: Option<Frame>, time: Duration) {
+        if let Some(frame) = native {
+            *self.calls.entry(frame).or_default() += 1;
+
+            self.stack.push(frame);
+            self.sample().time += time;
+            self.stack.pop();
+            self.sample().instructions += 1;
+            return;
+        }
+
+        let sample = self.sample();
+        sample.instructions += 1;
+        sample.time += time;
+    }
+
+    /// Access the sample for the current call stack.
+    fn sample(&mut self) -> &mut Sample {
+        if !self.samples.contains_key(&self.stack[..]) {
+            self.samples.insert(self.stack.clone(), Sample::default());
+        }
+
+        self.samples
+            .get_mut(&self.stack[..])
+            .expect("sample exists")
+    }
+
+    /// Adjust the call stack after an instruction was executed at the given
+    /// call depth.
+    fn adjust(&mut self, execution: &VmExecution, depth: usize) {
+        let new_depth = execution.call_depth();
+
+        for _ in new_depth..depth {
+            self.stack.pop();
+        }
+
+        if new_depth > depth {
+            let frames = self.call_stack(execution);
+
+            for frame in frames.into_iter().skip(depth) {
+                self.push(frame);
+            }
+        }
+    }
+
+    /// Push the given frame as a new call.
+    fn push(&mut self, frame: Frame) {
+        *self.calls.entry(frame).or_default() += 1;
+        self.stack.push(frame);
+    }
+
+    /// Resolve the frames of all functions being called in the given
+    /// execution, starting with the outermost one.
+    fn call_stack(&mut self, execution: &VmExecution) -> Vec<Frame> {
+        let mut frames = Vec::with_capacity(execution.call_depth());
+
+        for vm in execution.vms() {
+            for frame in vm.call_frames() {
+                frames.push(self.function_frame(vm, frame.ip()));
+            }
+
+            frames.push(self.function_frame(vm, vm.ip()));
+        }
+
+        frames
+    }
+
+    /// Get the frame for the function which contains the given instruction in
+    /// the unit of the given virtual machine.
+    fn function_frame(&mut self, vm: &Vm, ip: usize) -> Frame {
+        let name = vm
+            .unit()
+            .debug_info()
+            .and_then(|debug| debug.function_containing(ip))
+            .map(|(_, signature)| signature.path.to_string());
+
+        let name = self.intern(name.as_deref().unwrap_or(UNKNOWN));
+        Frame {
+            name,
+            native: false,
+        }
+    }
+
+    /// Test if the current instruction of the given virtual machine is a call
+    /// to a native function, and if so get the frame for it.
+    fn native_call(&mut self, vm: &Vm) -> Option<Frame> {
+        let hash = match vm.unit().instruction_at(vm.ip())? {
+            Inst::Call { hash, .. } => *hash,
+            Inst::CallInstance { hash, args } => {
+                // NB: +1 to include the instance itself.
+                let instance = vm.stack().at_offset_from_top(*args + 1).ok()?;
+                let type_of = instance.type_of().ok()?;
+                Hash::instance_function(type_of, *hash)
+            }
+            _ => return None,
+        };
+
+        if vm.unit().lookup(hash).is_some() {
+            return None;
+        }
+
+        let context = vm.context();
+        context.lookup(hash)?;
+
+        let name = match context.lookup_signature(hash) {
+            Some(ContextSignature::Function { path, .. }) => path.to_string(),
+            Some(ContextSignature::Instance { path, name, .. }) => format!("{}::{}", path, name),
+            None => String::from(UNKNOWN),
+        };
+
+        Some(Frame {
+            name: self.intern(&name),
+            native: true,
+        })
+    }
+
+    /// Intern the given name.
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(index) = self.names_rev.get(name) {
+            return *index;
+        }
+
+        let index = self.names.len();
+        self.names.push(name.to_owned());
+        self.names_rev.insert(name.to_owned(), index);
+        index
+    }
+}
@@ -0,0 +1,310 @@
+//! Hot reloading of units.
+
+use crate::collections::{HashMap, HashSet};
+use crate::unit::UnitFn;
+use crate::{
+    AccessError, Call, Function, GeneratorState, Hash, Item, Object, Shared, Unit, Value, Vm,
+};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Error raised when reloading.
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    /// Tried to reload a virtual machine which is in the middle of a call.
+    #[error("cannot reload virtual machine which is in the middle of a call")]
+    Running,
+    /// Tried to reload a virtual machine which isn't at the start of a
+    /// function.
+    #[error(
+        "cannot reload virtual machine at instruction {ip}, which isn't the start of a function"
+    )]
+    NotAtFunction {
+        /// The instruction pointer of the virtual machine.
+        ip: usize,
+    },
+    /// The function a virtual machine is about to call is missing from the new
+    /// unit, or its number of arguments changed.
+    #[error("function {hash} is missing from the new unit or has changed its arguments")]
+    MissingFunction {
+        /// The hash of the function.
+        hash: Hash,
+    },
+    /// A value couldn't be accessed.
+    #[error("{error}")]
+    AccessError {
+        /// Source error.
+        #[from]
+        error: AccessError,
+    },
+}
+
+/// A function which couldn't be moved over to the new unit, either because it
+/// no longer exists or because its signature changed.
+///
+/// Function references to it keep using the old unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    /// The hash of the function.
+    pub hash: Hash,
+    /// The path of the function, if debug information is available.
+    pub item: Option<Item>,
+}
+
+/// Replaces an old unit with a recompiled one in virtual machines and values.
+///
+/// Function pointers and closures are matched up with functions in the new
+/// unit by their hash. Functions which are missing from the new unit, or which
+/// have changed their number of arguments, are reported as
+/// [orphaned][Reload::orphans] and keep referencing the old unit.
+///
+/// Note that the environment of closures is kept as-is, so changing what a
+/// closure captures between reloads will not work as expected.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Reload, Unit, Value};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let old = Arc::new(Unit::default());
+/// let new = Arc::new(Unit::default());
+///
+/// let value = Value::vec(vec![Value::Integer(42)]);
+///
+/// let mut reload = Reload::new(old, new);
+/// reload.value(&value)?;
+///
+/// assert_eq!(reload.remapped(), 0);
+/// assert!(reload.orphans().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Reload {
+    /// The unit being replaced.
+    old: Arc<Unit>,
+    /// The unit replacing it.
+    new: Arc<Unit>,
+    /// Functions in the old unit, by offset.
+    functions: HashMap<usize, Hash>,
+    /// Addresses of shared values which have already been visited.
+    visited: HashSet<usize>,
+    /// The number of function references which have been moved.
+    remapped: usize,
+    /// Hashes of orphaned functions which have already been reported.
+    orphaned: HashSet<Hash>,
+    /// Orphaned functions.
+    orphans: Vec<Orphan>,
+}
+
+impl Reload {
+    /// Construct a reload replacing the `old` unit with the `new` one.
+    pub fn new(old: Arc<Unit>, new: Arc<Unit>) -> Self {
+        let functions = old
+            .iter_functions()
+            .filter_map(|(hash, f)| match f {
+                UnitFn::Offset { offset, .. } => Some((*offset, hash)),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            old,
+            new,
+            functions,
+            visited: HashSet::new(),
+            remapped: 0,
+            orphaned: HashSet::new(),
+            orphans: Vec::new(),
+        }
+    }
+
+    /// Move a virtual machine over to the new unit, together with the values
+    /// on its stack.
+    ///
+    /// The virtual machine can't be in the middle of a call, and its
+    /// instruction pointer must be at the start of a function which also
+    /// exists in the new unit with the same number of arguments. It's then
+    /// moved to the start of that function in the new unit.
+    ///
+    /// Virtual machines which use another unit are left as-is.
+    pub fn vm(&mut self, vm: &mut Vm) -> Result<(), ReloadError> {
+        if !Arc::ptr_eq(vm.unit(), &self.old) {
+            return Ok(());
+        }
+
+        if !vm.call_frames().is_empty() {
+            return Err(ReloadError::Running);
+        }
+
+        let hash = match self.functions.get(&vm.ip()).copied() {
+            Some(hash) => hash,
+            None => return Err(ReloadError::NotAtFunction { ip: vm.ip() }),
+        };
+
+        let args = match self.old.lookup(hash) {
+            Some(UnitFn::Offset { args, .. }) => args,
+            _ => return Err(ReloadError::NotAtFunction { ip: vm.ip() }),
+        };
+
+        let ip = match self.lookup(hash, Some(args)) {
+            Some((offset, _)) => offset,
+            None => return Err(ReloadError::MissingFunction { hash }),
+        };
+
+        for value in vm.stack().iter() {
+            self.value(value)?;
+        }
+
+        vm.set_unit(self.new.clone(), ip);
+        Ok(())
+    }
+
+    /// Move all functions referenced by the given value over to the new unit.
+    ///
+    /// Generators and streams are left as-is, since they are in the middle of
+    /// executing code in the old unit.
+    pub fn value(&mut self, value: &Value) -> Result<(), ReloadError> {
+        match value {
+            Value::Vec(vec) if self.visit(vec) => {
+                for value in vec.borrow_ref()?.iter() {
+                    self.value(value)?;
+                }
+            }
+            Value::Tuple(tuple) if self.visit(tuple) => {
+                for value in tuple.borrow_ref()?.iter() {
+                    self.value(value)?;
+                }
+            }
+            Value::Object(object) if self.visit(object) => {
+                self.object(&*object.borrow_ref()?)?;
+            }
+            Value::GeneratorState(state) if self.visit(state) => match &*state.borrow_ref()? {
+                GeneratorState::Yielded(value) | GeneratorState::Complete(value) => {
+                    self.value(value)?;
+                }
+            },
+            Value::Option(option) if self.visit(option) => {
+                if let Some(value) = &*option.borrow_ref()? {
+                    self.value(value)?;
+                }
+            }
+            Value::Result(result) if self.visit(result) => match &*result.borrow_ref()? {
+                Ok(value) | Err(value) => self.value(value)?,
+            },
+            Value::TypedTuple(tuple) if self.visit(tuple) => {
+                for value in tuple.borrow_ref()?.tuple.iter() {
+                    self.value(value)?;
+                }
+            }
+            Value::TupleVariant(tuple) if self.visit(tuple) => {
+                for value in tuple.borrow_ref()?.tuple.iter() {
+                    self.value(value)?;
+                }
+            }
+            Value::TypedObject(object) if self.visit(object) => {
+                for value in object.borrow_ref()?.fields.values() {
+                    self.value(value)?;
+                }
+            }
+            Value::VariantObject(object) if self.visit(object) => {
+                for value in object.borrow_ref()?.fields.values() {
+                    self.value(value)?;
+                }
+            }
+            Value::Function(function) if self.visit(function) => {
+                self.function(&mut *function.borrow_mut()?)?;
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Move the given function over to the new unit, if it belongs to the old
+    /// one.
+    pub fn function(&mut self, function: &mut Function) -> Result<(), ReloadError> {
+        let (unit, offset) = match function.unit_offset() {
+            Some(parts) => parts,
+            None => return Ok(()),
+        };
+
+        if !Arc::ptr_eq(unit, &self.old) {
+            return Ok(());
+        }
+
+        let hash = match self.functions.get(&offset).copied() {
+            Some(hash) => hash,
+            None => return Ok(()),
+        };
+
+        let args = match self.old.lookup(hash) {
+            Some(UnitFn::Offset { args, .. }) => args,
+            _ => return Ok(()),
+        };
+
+        if let Some((offset, call)) = self.lookup(hash, Some(args)) {
+            function.relocate(self.new.clone(), offset, call);
+            self.remapped += 1;
+        }
+
+        if let Some(environment) = function.environment() {
+            if self.visit(environment) {
+                for value in environment.borrow_ref()?.iter() {
+                    self.value(value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The number of function references which have been moved over to the
+    /// new unit.
+    pub fn remapped(&self) -> usize {
+        self.remapped
+    }
+
+    /// Functions which were referenced, but couldn't be moved over to the new
+    /// unit.
+    pub fn orphans(&self) -> &[Orphan] {
+        &self.orphans
+    }
+
+    /// Look up the function with the given hash in the new unit, reporting it
+    /// as orphaned if it's missing or its number of arguments differ.
+    fn lookup(&mut self, hash: Hash, expected: Option<usize>) -> Option<(usize, Call)> {
+        if let Some(UnitFn::Offset { offset, call, args }) = self.new.lookup(hash) {
+            if expected.unwrap_or(args) == args {
+                return Some((offset, call));
+            }
+        }
+
+        if self.orphaned.insert(hash) {
+            let item = self
+                .old
+                .debug_info()
+                .and_then(|debug| debug.functions.get(&hash))
+                .map(|signature| signature.path.clone());
+
+            self.orphans.push(Orphan { hash, item });
+        }
+
+        None
+    }
+
+    fn object(&mut self, object: &Object) -> Result<(), ReloadError> {
+        for (_, value) in object.iter() {
+            self.value(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Mark the given shared value as visited, returning `true` if it hadn't
+    /// been visited before.
+    fn visit<T>(&mut self, shared: &Shared<T>) -> bool {
+        self.visited.insert(shared.addr())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{Bytes, Object, Shared, Value};
 use serde::{de, ser};
+use std::convert::TryFrom as _;
 use std::fmt;
 
 /// Deserialize implementation for value pointers.
@@ -18,6 +19,7 @@ impl ser::Serialize for Value {
     where
         S: ser::Serializer,
     {
+        use num_traits::ToPrimitive as _;
         use serde::ser::SerializeMap as _;
         use serde::ser::SerializeSeq as _;
 
@@ -27,6 +29,20 @@ impl ser::Serialize for Value {
             Value::Char(c) => serializer.serialize_char(*c),
             Value::Byte(c) => serializer.serialize_u8(*c),
             Value::Integer(integer) => serializer.serialize_i64(*integer),
+            Value::U64(integer) => serializer.serialize_u64(*integer),
+            Value::I128(integer) => serializer.serialize_i128(**integer),
+            Value::U128(integer) => serializer.serialize_u128(**integer),
+            Value::BigInt(integer) => {
+                if let Some(integer) = integer.to_i128() {
+                    serializer.serialize_i128(integer)
+                } else if let Some(integer) = integer.to_u128() {
+                    serializer.serialize_u128(integer)
+                } else {
+                    Err(ser::Error::custom(
+                        "cannot serialize out of range big integers",
+                    ))
+                }
+            }
             Value::Float(float) => serializer.serialize_f64(*float),
             Value::StaticString(string) => serializer.serialize_str(string.as_ref()),
             Value::String(string) => {
@@ -84,6 +100,7 @@ impl ser::Serialize for Value {
                 Err(ser::Error::custom("cannot serialize generator states"))
             }
             Value::Function(..) => Err(ser::Error::custom("cannot serialize function pointers")),
+            Value::Weak(..) => Err(ser::Error::custom("cannot serialize weak references")),
             Value::Any(..) => Err(ser::Error::custom("cannot serialize external objects")),
         }
     }
@@ -167,7 +184,10 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Ok(Value::from(v)),
+        }
     }
 
     #[inline]
@@ -199,7 +219,10 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Ok(Value::U64(v)),
+        }
     }
 
     #[inline]
@@ -207,7 +230,10 @@ impl<'de> de::Visitor<'de> for VmVisitor {
     where
         E: de::Error,
     {
-        Ok(Value::Integer(v as i64))
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(..) => Ok(Value::from(v)),
+        }
     }
 
     #[inline]
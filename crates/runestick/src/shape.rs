@@ -0,0 +1,96 @@
+use crate::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow;
+use std::fmt;
+use std::hash;
+
+/// The layout of a struct, which is the names of its fields in the order of
+/// the slots they're stored in.
+///
+/// Shapes are shared between all instances constructed from the same static
+/// object keys in a unit, so that fields can be accessed by slot index instead
+/// of by name.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Shape;
+///
+/// let shape = Shape::new(vec![String::from("b"), String::from("a")]);
+/// assert_eq!(shape.keys(), &[String::from("b"), String::from("a")]);
+/// assert_eq!(shape.index("a"), Some(1));
+/// assert_eq!(shape.index("c"), None);
+/// ```
+pub struct Shape {
+    /// The names of the fields, in slot order.
+    keys: Box<[String]>,
+    /// The slot of each field, by name.
+    index: HashMap<String, usize>,
+}
+
+impl Shape {
+    /// Construct a new shape out of the given field names.
+    pub fn new<I>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let keys = keys.into_iter().collect::<Box<[String]>>();
+
+        let index = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (key.clone(), index))
+            .collect();
+
+        Self { keys, index }
+    }
+
+    /// Get the names of the fields, in slot order.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Get the number of fields in the shape.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Test if the shape has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Get the slot index of the given field.
+    pub fn index<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + hash::Hash + Eq,
+        String: borrow::Borrow<Q>,
+    {
+        self.index.get(key).copied()
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.keys.iter()).finish()
+    }
+}
+
+impl Serialize for Shape {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.keys.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Shape {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let keys = Box::<[String]>::deserialize(deserializer)?;
+        Ok(Self::new(keys.into_vec()))
+    }
+}
@@ -3,7 +3,7 @@ use crate::access::{
 };
 use crate::{Any, AnyObj, Hash};
 use std::any;
-use std::cell::{Cell, UnsafeCell};
+use std::cell::UnsafeCell;
 use std::fmt;
 use std::future::Future;
 use std::marker;
@@ -12,6 +12,7 @@ use std::ops;
 use std::pin::Pin;
 use std::process;
 use std::ptr;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
 use std::task::{Context, Poll};
 
 /// A shared value.
@@ -24,7 +25,8 @@ impl<T> Shared<T> {
     pub fn new(data: T) -> Self {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(false),
-            count: Cell::new(1),
+            count: AtomicUsize::new(1),
+            weak: AtomicUsize::new(1),
             data: data.into(),
         }));
 
@@ -33,6 +35,12 @@ impl<T> Shared<T> {
         }
     }
 
+    /// Get the address of the shared data, which can be used to tell if two
+    /// shared values refer to the same data.
+    pub(crate) fn addr(&self) -> usize {
+        self.inner.as_ptr() as *const () as usize
+    }
+
     /// Return a debug formatter, that when printed will display detailed
     /// diagnostics of this shared type.
     pub fn debug(&self) -> SharedDebug<'_, T> {
@@ -272,6 +280,38 @@ impl<T> Shared<T> {
 }
 
 impl<T: ?Sized> Shared<T> {
+    /// Construct a weak reference to the shared value, which doesn't keep the
+    /// value alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Shared;
+    ///
+    /// let a = Shared::new(1u32);
+    /// let weak = a.downgrade();
+    /// assert_eq!(*weak.upgrade().unwrap().borrow_ref().unwrap(), 1u32);
+    ///
+    /// drop(a);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(&self) -> Weak<T> {
+        // Safety: by virtue of holding onto a shared we can safely access
+        // `inner` because it must outlive any `Shared` instances.
+        unsafe {
+            SharedBox::inc_weak(self.inner.as_ptr());
+        }
+
+        Weak { inner: self.inner }
+    }
+
+    /// Get the number of strong references to the shared value.
+    pub(crate) fn strong_count(&self) -> usize {
+        // Safety: by virtue of holding onto a shared we can safely access
+        // `inner` because it must outlive any `Shared` instances.
+        unsafe { self.inner.as_ref().count.load(Ordering::Acquire) }
+    }
+
     /// Get a reference to the interior value while checking for shared access.
     ///
     /// This prevents other exclusive accesses from being performed while the
@@ -436,7 +476,8 @@ impl Shared<AnyObj> {
     unsafe fn unsafe_from_any_pointer(any: AnyObj) -> (Self, SharedPointerGuard) {
         let inner = ptr::NonNull::from(Box::leak(Box::new(SharedBox {
             access: Access::new(true),
-            count: Cell::new(2),
+            count: AtomicUsize::new(2),
+            weak: AtomicUsize::new(1),
             data: any.into(),
         })));
 
@@ -647,6 +688,12 @@ impl Shared<AnyObj> {
     }
 }
 
+// Safety: the reference count and access to the shared data are both
+// atomically checked, so a shared value can be used from multiple threads as
+// long as the data it guards can be.
+unsafe impl<T: ?Sized + Send + Sync> Send for Shared<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Shared<T> {}
+
 impl<T: ?Sized> Clone for Shared<T> {
     fn clone(&self) -> Self {
         unsafe {
@@ -684,6 +731,62 @@ where
     }
 }
 
+/// A weak reference to a shared value, which doesn't keep the value alive.
+///
+/// Constructed using [downgrade][Shared::downgrade].
+pub struct Weak<T: ?Sized> {
+    inner: ptr::NonNull<SharedBox<T>>,
+}
+
+impl<T: ?Sized> Weak<T> {
+    /// Try to get a strong reference to the shared value, returning `None` if
+    /// it has already been dropped.
+    pub fn upgrade(&self) -> Option<Shared<T>> {
+        // Safety: the box is kept alive by the weak reference.
+        unsafe {
+            if !SharedBox::upgrade(self.inner.as_ptr()) {
+                return None;
+            }
+        }
+
+        Some(Shared { inner: self.inner })
+    }
+
+    /// Test if the shared value is still alive.
+    pub fn is_alive(&self) -> bool {
+        // Safety: the box is kept alive by the weak reference.
+        unsafe { self.inner.as_ref().count.load(Ordering::Acquire) != 0 }
+    }
+}
+
+// Safety: see the implementations for `Shared`.
+unsafe impl<T: ?Sized + Send + Sync> Send for Weak<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Weak<T> {}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        unsafe {
+            SharedBox::inc_weak(self.inner.as_ptr());
+        }
+
+        Self { inner: self.inner }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        unsafe {
+            SharedBox::dec_weak(self.inner.as_ptr());
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "(Weak)")
+    }
+}
+
 /// A debug helper that prints detailed diagnostics on the type being debugged.
 ///
 /// Constructed using [debug][Shared::debug].
@@ -703,7 +806,7 @@ where
             let mut debug = fmt.debug_struct("Shared");
 
             debug.field("access", &inner.access);
-            debug.field("count", &inner.count.get());
+            debug.field("count", &inner.count.load(Ordering::Relaxed));
 
             if !inner.access.is_shared() {
                 debug.field("data", &any::type_name::<T>());
@@ -722,7 +825,10 @@ struct SharedBox<T: ?Sized> {
     /// The access of the shared data.
     access: Access,
     /// The number of strong references to the shared data.
-    count: Cell<usize>,
+    count: AtomicUsize,
+    /// The number of weak references to the shared data, plus one which is
+    /// collectively held by all strong references.
+    weak: AtomicUsize,
     /// The value being held. Guarded by the `access` field to determine if it
     /// can be access shared or exclusively.
     data: UnsafeCell<T>,
@@ -731,14 +837,11 @@ struct SharedBox<T: ?Sized> {
 impl<T: ?Sized> SharedBox<T> {
     /// Increment the reference count of the inner value.
     unsafe fn inc(this: *const Self) {
-        let count = (*this).count.get();
+        let count = (*this).count.fetch_add(1, Ordering::Relaxed);
 
-        if count == 0 || count == usize::max_value() {
+        if count == 0 || count >= isize::max_value() as usize {
             process::abort();
         }
-
-        let count = count + 1;
-        (*this).count.set(count);
     }
 
     /// Decrement the reference count in inner, and free the underlying data if
@@ -748,34 +851,93 @@ impl<T: ?Sized> SharedBox<T> {
     ///
     /// Caller needs to ensure that `this` is a valid pointer.
     unsafe fn dec(this: *mut Self) -> bool {
-        let count = (*this).count.get();
+        let count = (*this).count.fetch_sub(1, Ordering::Release);
 
         if count == 0 {
             process::abort();
         }
 
-        let count = count - 1;
-        (*this).count.set(count);
-
-        if count != 0 {
+        if count != 1 {
             return false;
         }
 
-        if (*this).access.is_taken() {
-            // NB: This prevents the inner `T` from being dropped in case it
-            // has already been taken (as indicated by `is_taken`).
-            //
-            // If it has been taken, the shared box contains invalid memory.
-            let _ = std::mem::transmute::<_, Box<SharedBox<ManuallyDrop<T>>>>(Box::from_raw(this));
-        } else {
+        // NB: synchronize with the releases of all other references before
+        // freeing the data, in the same way as `Arc` does.
+        atomic::fence(Ordering::Acquire);
+
+        // NB: If the value has been taken (as indicated by `is_taken`), the
+        // shared box contains invalid memory which must not be dropped.
+        if !(*this).access.is_taken() {
             // NB: At the point of the final drop, no on else should be using
             // this.
             debug_assert!((*this).access.is_exclusive());
-            let _ = Box::from_raw(this);
+            ptr::drop_in_place((*this).data.get());
         }
 
+        // NB: release the weak reference held by all strong references.
+        Self::dec_weak(this);
         true
     }
+
+    /// Increment the weak reference count of the inner value.
+    unsafe fn inc_weak(this: *const Self) {
+        let count = (*this).weak.fetch_add(1, Ordering::Relaxed);
+
+        if count == 0 || count >= isize::max_value() as usize {
+            process::abort();
+        }
+    }
+
+    /// Decrement the weak reference count in inner, and free the box if it has
+    /// reached zero.
+    ///
+    /// # Safety
+    ///
+    /// Caller needs to ensure that `this` is a valid pointer, and that the
+    /// data has already been dropped or taken if this is the last reference.
+    unsafe fn dec_weak(this: *mut Self) {
+        let count = (*this).weak.fetch_sub(1, Ordering::Release);
+
+        if count == 0 {
+            process::abort();
+        }
+
+        if count != 1 {
+            return;
+        }
+
+        atomic::fence(Ordering::Acquire);
+
+        // NB: the data is no longer valid at this point, so only the box
+        // itself is freed.
+        let _ = std::mem::transmute::<_, Box<SharedBox<ManuallyDrop<T>>>>(Box::from_raw(this));
+    }
+
+    /// Try to increment the reference count of the inner value, failing if the
+    /// value has already been dropped.
+    unsafe fn upgrade(this: *const Self) -> bool {
+        let mut count = (*this).count.load(Ordering::Relaxed);
+
+        loop {
+            if count == 0 {
+                return false;
+            }
+
+            if count >= isize::max_value() as usize {
+                process::abort();
+            }
+
+            match (*this).count.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(..) => return true,
+                Err(actual) => count = actual,
+            }
+        }
+    }
 }
 
 type DropFn = unsafe fn(*const ());
@@ -856,6 +1018,10 @@ pub struct Ref<T: ?Sized> {
     _marker: marker::PhantomData<T>,
 }
 
+// Safety: a strong reference behaves like a [Shared] which is being accessed.
+unsafe impl<T: ?Sized + Send + Sync> Send for Ref<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Ref<T> {}
+
 impl<T: ?Sized> Ref<T> {
     /// Convert into a raw pointer and associated raw access guard.
     ///
@@ -864,7 +1030,10 @@ impl<T: ?Sized> Ref<T> {
     /// The returned pointer must not outlive the associated guard, since this
     /// prevents other uses of the underlying data which is incompatible with
     /// the current.
-    pub fn into_raw(this: Self) -> (*const T, RawRef) {
+    pub fn into_raw(this: Self) -> (*const T, RawRef)
+    where
+        T: Send + Sync,
+    {
         let guard = RawRef {
             _guard: this.guard,
             _inner: this.inner,
@@ -899,6 +1068,11 @@ pub struct RawRef {
     _inner: RawDrop,
 }
 
+// Safety: raw guards can only be constructed through [Ref::into_raw], which
+// requires the guarded data to be thread safe.
+unsafe impl Send for RawRef {}
+unsafe impl Sync for RawRef {}
+
 /// A strong mutable reference to the given type.
 pub struct Mut<T: ?Sized> {
     data: *mut T,
@@ -910,6 +1084,10 @@ pub struct Mut<T: ?Sized> {
     _marker: marker::PhantomData<T>,
 }
 
+// Safety: a strong reference behaves like a [Shared] which is being accessed.
+unsafe impl<T: ?Sized + Send + Sync> Send for Mut<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for Mut<T> {}
+
 impl<T: ?Sized> Mut<T> {
     /// Convert into a raw pointer and associated raw access guard.
     ///
@@ -918,7 +1096,10 @@ impl<T: ?Sized> Mut<T> {
     /// The returned pointer must not outlive the associated guard, since this
     /// prevents other uses of the underlying data which is incompatible with
     /// the current.
-    pub fn into_raw(this: Self) -> (*mut T, RawMut) {
+    pub fn into_raw(this: Self) -> (*mut T, RawMut)
+    where
+        T: Send + Sync,
+    {
         let guard = RawMut {
             _guard: this.guard,
             _inner: this.inner,
@@ -974,6 +1155,11 @@ pub struct RawMut {
     _inner: RawDrop,
 }
 
+// Safety: raw guards can only be constructed through [Mut::into_raw], which
+// requires the guarded data to be thread safe.
+unsafe impl Send for RawMut {}
+unsafe impl Sync for RawMut {}
+
 /// A guard for an `Any` containing a pointer.
 ///
 /// Constructing using [Shared::from_ref] or [Shared::from_mut].
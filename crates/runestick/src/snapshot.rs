@@ -0,0 +1,745 @@
+//! Serializable snapshots of virtual machines.
+
+use crate::collections::HashMap;
+use crate::function::FunctionKind;
+use crate::{
+    AccessError, BigInt, Bytes, Call, CallFrame, Context, Fields, Function, Generator,
+    GeneratorState, Hash, Object, Shape, Shared, Stack, StaticString, Stream, Tuple, TypeInfo,
+    TypedObject, Unit, Value, VariantObject, Vm, VmError, VmExecution,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Error raised when taking or restoring a snapshot of a virtual machine.
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    /// A value which can't be snapshotted was encountered.
+    #[error("value of type `{type_info}` cannot be snapshotted")]
+    UnsupportedValue {
+        /// The type of the value.
+        type_info: TypeInfo,
+    },
+    /// A function or virtual machine belonging to another unit or context was
+    /// encountered while taking a snapshot.
+    #[error("cannot snapshot function or virtual machine from another unit")]
+    ForeignUnit,
+    /// Tried to restore a snapshot against a unit it wasn't taken from.
+    #[error(
+        "snapshot was taken from unit `{expected}`, but tried to restore it against `{actual}`"
+    )]
+    UnitMismatch {
+        /// The content hash of the unit the snapshot was taken from.
+        expected: Hash,
+        /// The content hash of the unit the snapshot was restored against.
+        actual: Hash,
+    },
+    /// A native function referenced by the snapshot is missing from the
+    /// context it's being restored in.
+    #[error("missing native function with hash `{hash}`")]
+    MissingFunction {
+        /// The hash of the missing function.
+        hash: Hash,
+    },
+    /// The snapshot refers to a value which doesn't exist in it.
+    #[error("snapshot is corrupt, missing value `{index}`")]
+    MissingValue {
+        /// The index of the missing value.
+        index: usize,
+    },
+    /// A value couldn't be accessed while taking or restoring a snapshot.
+    #[error("{error}")]
+    AccessError {
+        /// Source error.
+        #[from]
+        error: AccessError,
+    },
+    /// A virtual machine error was raised.
+    #[error("{error}")]
+    VmError {
+        /// Source error.
+        #[from]
+        error: VmError,
+    },
+}
+
+/// A serializable snapshot of a virtual machine, which can be restored with
+/// [Vm::restore].
+///
+/// The snapshot contains the stack and call frames of the virtual machine,
+/// and every value reachable from them. Values which are referenced from
+/// multiple places are only stored once, so that they are still shared after
+/// the snapshot has been restored.
+///
+/// The instructions of the virtual machine are not part of the snapshot, it
+/// instead records the [content hash][Unit::content_hash] of the unit it was
+/// taken from, which must be provided when restoring it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmSnapshot {
+    /// The content hash of the unit the snapshot was taken from.
+    unit: Hash,
+    /// The state of the virtual machine.
+    vm: VmState,
+    /// All shared values reachable from the virtual machine.
+    values: Vec<SharedState>,
+}
+
+impl VmSnapshot {
+    /// Get the content hash of the unit the snapshot was taken from.
+    pub fn unit_hash(&self) -> Hash {
+        self.unit
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VmState {
+    ip: usize,
+    stack: Vec<ValueState>,
+    stack_bottom: usize,
+    call_frames: Vec<CallFrame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum ValueState {
+    Unit,
+    Bool(bool),
+    Byte(u8),
+    Char(char),
+    Integer(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    BigInt(BigInt),
+    Float(f64),
+    Type(Hash),
+    StaticString(String),
+    /// A reference to a shared value, by index.
+    Shared(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SharedState {
+    String(String),
+    Bytes(Vec<u8>),
+    Vec(Vec<ValueState>),
+    Tuple(Vec<ValueState>),
+    Object(Vec<(String, ValueState)>),
+    Stream(ExecutionState),
+    Generator(ExecutionState),
+    GeneratorState(GeneratorStateState),
+    Option(Option<ValueState>),
+    Result(Result<ValueState, ValueState>),
+    TypedTuple {
+        hash: Hash,
+        tuple: Vec<ValueState>,
+    },
+    TupleVariant {
+        enum_hash: Hash,
+        hash: Hash,
+        tuple: Vec<ValueState>,
+    },
+    TypedObject {
+        hash: Hash,
+        object: Vec<(String, ValueState)>,
+    },
+    VariantObject {
+        enum_hash: Hash,
+        hash: Hash,
+        object: Vec<(String, ValueState)>,
+    },
+    Function(FunctionState),
+}
+
+/// The state of a generator or a stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ExecutionState {
+    /// The virtual machines of the execution, unless it has completed.
+    vms: Option<Vec<VmState>>,
+    /// If the execution hasn't been resumed yet.
+    first: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum GeneratorStateState {
+    Yielded(ValueState),
+    Complete(ValueState),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum FunctionState {
+    Handler {
+        hash: Hash,
+    },
+    Offset {
+        offset: usize,
+        call: Call,
+        args: usize,
+        environment: Option<usize>,
+    },
+    Tuple {
+        hash: Hash,
+        args: usize,
+    },
+    VariantTuple {
+        enum_hash: Hash,
+        hash: Hash,
+        args: usize,
+    },
+}
+
+/// Take a snapshot of the given virtual machine.
+pub(crate) fn snapshot(vm: &Vm) -> Result<VmSnapshot, SnapshotError> {
+    let mut snapshotter = Snapshotter {
+        context: vm.context(),
+        unit: vm.unit(),
+        seen: HashMap::new(),
+        values: Vec::new(),
+    };
+
+    let state = snapshotter.vm(vm)?;
+
+    let values = snapshotter
+        .values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| value.ok_or(SnapshotError::MissingValue { index }))
+        .collect::<Result<_, _>>()?;
+
+    Ok(VmSnapshot {
+        unit: vm.unit().content_hash(),
+        vm: state,
+        values,
+    })
+}
+
+/// Restore a virtual machine from the given snapshot.
+pub(crate) fn restore(
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+    snapshot: &VmSnapshot,
+) -> Result<Vm, SnapshotError> {
+    let actual = unit.content_hash();
+
+    if snapshot.unit != actual {
+        return Err(SnapshotError::UnitMismatch {
+            expected: snapshot.unit,
+            actual,
+        });
+    }
+
+    let shapes = unit
+        .iter_static_object_keys()
+        .enumerate()
+        .map(|(slot, (hash, _))| (hash, slot))
+        .collect();
+
+    let mut restorer = Restorer {
+        context,
+        unit,
+        shapes,
+        values: Vec::with_capacity(snapshot.values.len()),
+    };
+
+    // NB: shared values are constructed empty first and populated afterwards,
+    // since they might refer to each other.
+    for state in &snapshot.values {
+        let value = restorer.empty(state)?;
+        restorer.values.push(value);
+    }
+
+    for (state, value) in snapshot.values.iter().zip(restorer.values.clone()) {
+        restorer.populate(state, value)?;
+    }
+
+    restorer.vm(&snapshot.vm)
+}
+
+struct Snapshotter<'a> {
+    context: &'a Arc<Context>,
+    unit: &'a Arc<Unit>,
+    /// Shared values which have been seen, by address.
+    seen: HashMap<usize, usize>,
+    /// Shared values, which are `None` while they're being snapshotted.
+    values: Vec<Option<SharedState>>,
+}
+
+impl Snapshotter<'_> {
+    fn vm(&mut self, vm: &Vm) -> Result<VmState, SnapshotError> {
+        if !vm.is_same(self.context, self.unit) {
+            return Err(SnapshotError::ForeignUnit);
+        }
+
+        Ok(VmState {
+            ip: vm.ip(),
+            stack: self.values(vm.stack().iter())?,
+            stack_bottom: vm.stack().stack_bottom(),
+            call_frames: vm.call_frames().to_vec(),
+        })
+    }
+
+    fn execution(
+        &mut self,
+        (execution, first): (Option<&VmExecution>, bool),
+    ) -> Result<ExecutionState, SnapshotError> {
+        let vms = match execution {
+            Some(execution) => Some(
+                execution
+                    .vms()
+                    .iter()
+                    .map(|vm| self.vm(vm))
+                    .collect::<Result<_, _>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(ExecutionState { vms, first })
+    }
+
+    fn values<'v, I>(&mut self, values: I) -> Result<Vec<ValueState>, SnapshotError>
+    where
+        I: IntoIterator<Item = &'v Value>,
+    {
+        values.into_iter().map(|value| self.value(value)).collect()
+    }
+
+    fn object(&mut self, object: &Object) -> Result<Vec<(String, ValueState)>, SnapshotError> {
+        let mut entries = Vec::with_capacity(object.len());
+
+        for (key, value) in object.iter() {
+            entries.push((key.clone(), self.value(value)?));
+        }
+
+        // NB: sorted so that snapshots are deterministic.
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    fn fields(&mut self, fields: &Fields) -> Result<Vec<(String, ValueState)>, SnapshotError> {
+        let mut entries = Vec::with_capacity(fields.len());
+
+        // NB: fields are kept in slot order, so that they can be restored
+        // with the same shape.
+        for (key, value) in fields.iter() {
+            entries.push((key.clone(), self.value(value)?));
+        }
+
+        Ok(entries)
+    }
+
+    fn value(&mut self, value: &Value) -> Result<ValueState, SnapshotError> {
+        Ok(match value {
+            Value::Unit => ValueState::Unit,
+            Value::Bool(b) => ValueState::Bool(*b),
+            Value::Byte(b) => ValueState::Byte(*b),
+            Value::Char(c) => ValueState::Char(*c),
+            Value::Integer(n) => ValueState::Integer(*n),
+            Value::U64(n) => ValueState::U64(*n),
+            Value::I128(n) => ValueState::I128(**n),
+            Value::U128(n) => ValueState::U128(**n),
+            Value::BigInt(n) => ValueState::BigInt((**n).clone()),
+            Value::Float(n) => ValueState::Float(*n),
+            Value::Type(hash) => ValueState::Type(*hash),
+            Value::StaticString(string) => ValueState::StaticString((**string).as_ref().clone()),
+            Value::String(string) => {
+                self.shared(string, |_, string| Ok(SharedState::String(string.clone())))?
+            }
+            Value::Bytes(bytes) => {
+                self.shared(bytes, |_, bytes| Ok(SharedState::Bytes(bytes.to_vec())))?
+            }
+            Value::Vec(vec) => {
+                self.shared(vec, |this, vec| Ok(SharedState::Vec(this.values(vec)?)))?
+            }
+            Value::Tuple(tuple) => self.shared(tuple, |this, tuple| {
+                Ok(SharedState::Tuple(this.values(tuple.iter())?))
+            })?,
+            Value::Object(object) => self.shared(object, |this, object| {
+                Ok(SharedState::Object(this.object(object)?))
+            })?,
+            Value::Stream(stream) => self.shared(stream, |this, stream| {
+                Ok(SharedState::Stream(this.execution(stream.parts())?))
+            })?,
+            Value::Generator(generator) => self.shared(generator, |this, generator| {
+                Ok(SharedState::Generator(this.execution(generator.parts())?))
+            })?,
+            Value::GeneratorState(state) => self.shared(state, |this, state| {
+                Ok(SharedState::GeneratorState(match state {
+                    GeneratorState::Yielded(value) => {
+                        GeneratorStateState::Yielded(this.value(value)?)
+                    }
+                    GeneratorState::Complete(value) => {
+                        GeneratorStateState::Complete(this.value(value)?)
+                    }
+                }))
+            })?,
+            Value::Option(option) => self.shared(option, |this, option| {
+                Ok(SharedState::Option(match option {
+                    Some(value) => Some(this.value(value)?),
+                    None => None,
+                }))
+            })?,
+            Value::Result(result) => self.shared(result, |this, result| {
+                Ok(SharedState::Result(match result {
+                    Ok(value) => Ok(this.value(value)?),
+                    Err(value) => Err(this.value(value)?),
+                }))
+            })?,
+            Value::TypedTuple(tuple) => self.shared(tuple, |this, tuple| {
+                Ok(SharedState::TypedTuple {
+                    hash: tuple.hash,
+                    tuple: this.values(tuple.tuple.iter())?,
+                })
+            })?,
+            Value::TupleVariant(tuple) => self.shared(tuple, |this, tuple| {
+                Ok(SharedState::TupleVariant {
+                    enum_hash: tuple.enum_hash,
+                    hash: tuple.hash,
+                    tuple: this.values(tuple.tuple.iter())?,
+                })
+            })?,
+            Value::TypedObject(object) => self.shared(object, |this, object| {
+                Ok(SharedState::TypedObject {
+                    hash: object.type_hash(),
+                    object: this.fields(&object.fields)?,
+                })
+            })?,
+            Value::VariantObject(object) => self.shared(object, |this, object| {
+                Ok(SharedState::VariantObject {
+                    enum_hash: object.enum_hash,
+                    hash: object.hash,
+                    object: this.fields(&object.fields)?,
+                })
+            })?,
+            Value::Function(function) => self.shared(function, |this, function| {
+                Ok(SharedState::Function(this.function(function)?))
+            })?,
+            Value::Future(..) | Value::Weak(..) | Value::Any(..) => {
+                return Err(SnapshotError::UnsupportedValue {
+                    type_info: value.type_info()?,
+                });
+            }
+        })
+    }
+
+    fn function(&mut self, function: &Function) -> Result<FunctionState, SnapshotError> {
+        Ok(match function.kind() {
+            FunctionKind::Handler { hash } => FunctionState::Handler { hash },
+            FunctionKind::Offset {
+                context,
+                unit,
+                offset,
+                call,
+                args,
+                environment,
+            } => {
+                if !Arc::ptr_eq(context, self.context) || !Arc::ptr_eq(unit, self.unit) {
+                    return Err(SnapshotError::ForeignUnit);
+                }
+
+                let environment = match environment {
+                    Some(environment) => Some(self.shared_index(environment, |this, tuple| {
+                        Ok(SharedState::Tuple(this.values(tuple.iter())?))
+                    })?),
+                    None => None,
+                };
+
+                FunctionState::Offset {
+                    offset,
+                    call,
+                    args,
+                    environment,
+                }
+            }
+            FunctionKind::Tuple { hash, args } => FunctionState::Tuple { hash, args },
+            FunctionKind::VariantTuple {
+                enum_hash,
+                hash,
+                args,
+            } => FunctionState::VariantTuple {
+                enum_hash,
+                hash,
+                args,
+            },
+        })
+    }
+
+    /// Snapshot a shared value using the given function, unless it has
+    /// already been seen.
+    fn shared<T, F>(&mut self, shared: &Shared<T>, f: F) -> Result<ValueState, SnapshotError>
+    where
+        F: FnOnce(&mut Self, &T) -> Result<SharedState, SnapshotError>,
+    {
+        Ok(ValueState::Shared(self.shared_index(shared, f)?))
+    }
+
+    fn shared_index<T, F>(&mut self, shared: &Shared<T>, f: F) -> Result<usize, SnapshotError>
+    where
+        F: FnOnce(&mut Self, &T) -> Result<SharedState, SnapshotError>,
+    {
+        if let Some(index) = self.seen.get(&shared.addr()) {
+            return Ok(*index);
+        }
+
+        let index = self.values.len();
+        self.values.push(None);
+        self.seen.insert(shared.addr(), index);
+
+        let data = shared.borrow_ref()?;
+        let state = f(self, &*data)?;
+        self.values[index] = Some(state);
+        Ok(index)
+    }
+}
+
+struct Restorer {
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+    /// Slots of the shapes in the unit, by the hash of their keys.
+    shapes: HashMap<Hash, usize>,
+    /// Restored shared values, by index.
+    values: Vec<Value>,
+}
+
+impl Restorer {
+    fn vm(&self, state: &VmState) -> Result<Vm, SnapshotError> {
+        let mut stack = Stack::from(self.values(&state.stack)?);
+        stack.set_stack_bottom(state.stack_bottom);
+
+        Ok(Vm::from_parts(
+            self.context.clone(),
+            self.unit.clone(),
+            state.ip,
+            stack,
+            state.call_frames.clone(),
+        ))
+    }
+
+    fn execution(&self, state: &ExecutionState) -> Result<Option<VmExecution>, SnapshotError> {
+        let vms = match &state.vms {
+            Some(vms) => vms,
+            None => return Ok(None),
+        };
+
+        let vms = vms.iter().map(|vm| self.vm(vm)).collect::<Result<_, _>>()?;
+
+        Ok(Some(VmExecution::from_vms(vms)))
+    }
+
+    fn value(&self, state: &ValueState) -> Result<Value, SnapshotError> {
+        Ok(match state {
+            ValueState::Unit => Value::Unit,
+            ValueState::Bool(b) => Value::Bool(*b),
+            ValueState::Byte(b) => Value::Byte(*b),
+            ValueState::Char(c) => Value::Char(*c),
+            ValueState::Integer(n) => Value::Integer(*n),
+            ValueState::U64(n) => Value::U64(*n),
+            ValueState::I128(n) => Value::from(*n),
+            ValueState::U128(n) => Value::from(*n),
+            ValueState::BigInt(n) => Value::from(n.clone()),
+            ValueState::Float(n) => Value::Float(*n),
+            ValueState::Type(hash) => Value::Type(*hash),
+            ValueState::StaticString(string) => {
+                Value::StaticString(Arc::new(StaticString::new(string)))
+            }
+            ValueState::Shared(index) => self.shared(*index)?,
+        })
+    }
+
+    fn values(&self, states: &[ValueState]) -> Result<Vec<Value>, SnapshotError> {
+        states.iter().map(|state| self.value(state)).collect()
+    }
+
+    fn object(&self, entries: &[(String, ValueState)]) -> Result<Object, SnapshotError> {
+        let mut object = Object::with_capacity(entries.len());
+
+        for (key, value) in entries {
+            object.insert(key.clone(), self.value(value)?);
+        }
+
+        Ok(object)
+    }
+
+    /// Restore the fields of a struct, sharing the shape of the unit if it
+    /// has one with the same keys.
+    fn fields(&self, entries: &[(String, ValueState)]) -> Result<Fields, SnapshotError> {
+        let hash = Hash::object_keys(entries.iter().map(|(key, _)| key));
+
+        let shape = self
+            .shapes
+            .get(&hash)
+            .and_then(|slot| self.unit.lookup_shape(*slot))
+            .filter(|shape| shape.keys().iter().eq(entries.iter().map(|(key, _)| key)));
+
+        let shape = match shape {
+            Some(shape) => shape.clone(),
+            None => Arc::new(Shape::new(entries.iter().map(|(key, _)| key.clone()))),
+        };
+
+        let mut values = Vec::with_capacity(entries.len());
+
+        for (_, value) in entries {
+            values.push(self.value(value)?);
+        }
+
+        Ok(Fields::new(shape, values))
+    }
+
+    fn shared(&self, index: usize) -> Result<Value, SnapshotError> {
+        match self.values.get(index) {
+            Some(value) => Ok(value.clone()),
+            None => Err(SnapshotError::MissingValue { index }),
+        }
+    }
+
+    /// Construct an empty shared value corresponding to the given state.
+    fn empty(&self, state: &SharedState) -> Result<Value, SnapshotError> {
+        Ok(match state {
+            SharedState::String(..) => Value::String(Shared::new(String::new())),
+            SharedState::Bytes(..) => Value::Bytes(Shared::new(Bytes::from_vec(Vec::new()))),
+            SharedState::Vec(..) => Value::vec(Vec::new()),
+            SharedState::Tuple(..) => Value::tuple(Vec::new()),
+            SharedState::Object(..) => Value::Object(Shared::new(Object::new())),
+            SharedState::Stream(..) => Value::from(Stream::from_parts(None, false)),
+            SharedState::Generator(..) => Value::from(Generator::from_parts(None, false)),
+            SharedState::GeneratorState(..) => {
+                Value::GeneratorState(Shared::new(GeneratorState::Complete(Value::Unit)))
+            }
+            SharedState::Option(..) => Value::Option(Shared::new(None)),
+            SharedState::Result(..) => Value::Result(Shared::new(Ok(Value::Unit))),
+            SharedState::TypedTuple { hash, .. } => Value::typed_tuple(*hash, Vec::new()),
+            SharedState::TupleVariant {
+                enum_hash, hash, ..
+            } => Value::variant_tuple(*enum_hash, *hash, Vec::new()),
+            SharedState::TypedObject { hash, .. } => {
+                Value::TypedObject(Shared::new(TypedObject::new(*hash, Object::new())))
+            }
+            SharedState::VariantObject {
+                enum_hash, hash, ..
+            } => Value::VariantObject(Shared::new(VariantObject {
+                enum_hash: *enum_hash,
+                hash: *hash,
+                fields: Fields::from(Object::new()),
+            })),
+            SharedState::Function(..) => {
+                Value::Function(Shared::new(Function::from_tuple(Hash::new(0), 0)))
+            }
+        })
+    }
+
+    /// Populate an empty shared value from the given state.
+    fn populate(&self, state: &SharedState, value: Value) -> Result<(), SnapshotError> {
+        match (state, value) {
+            (SharedState::String(string), Value::String(shared)) => {
+                *shared.borrow_mut()? = string.clone();
+            }
+            (SharedState::Bytes(bytes), Value::Bytes(shared)) => {
+                *shared.borrow_mut()? = Bytes::from_vec(bytes.clone());
+            }
+            (SharedState::Vec(vec), Value::Vec(shared)) => {
+                *shared.borrow_mut()? = self.values(vec)?;
+            }
+            (SharedState::Tuple(tuple), Value::Tuple(shared)) => {
+                *shared.borrow_mut()? = Tuple::from(self.values(tuple)?);
+            }
+            (SharedState::Object(object), Value::Object(shared)) => {
+                *shared.borrow_mut()? = self.object(object)?;
+            }
+            (SharedState::Stream(state), Value::Stream(shared)) => {
+                *shared.borrow_mut()? = Stream::from_parts(self.execution(state)?, state.first);
+            }
+            (SharedState::Generator(state), Value::Generator(shared)) => {
+                *shared.borrow_mut()? = Generator::from_parts(self.execution(state)?, state.first);
+            }
+            (SharedState::GeneratorState(state), Value::GeneratorState(shared)) => {
+                *shared.borrow_mut()? = match state {
+                    GeneratorStateState::Yielded(value) => {
+                        GeneratorState::Yielded(self.value(value)?)
+                    }
+                    GeneratorStateState::Complete(value) => {
+                        GeneratorState::Complete(self.value(value)?)
+                    }
+                };
+            }
+            (SharedState::Option(option), Value::Option(shared)) => {
+                *shared.borrow_mut()? = match option {
+                    Some(value) => Some(self.value(value)?),
+                    None => None,
+                };
+            }
+            (SharedState::Result(result), Value::Result(shared)) => {
+                *shared.borrow_mut()? = match result {
+                    Ok(value) => Ok(self.value(value)?),
+                    Err(value) => Err(self.value(value)?),
+                };
+            }
+            (SharedState::TypedTuple { tuple, .. }, Value::TypedTuple(shared)) => {
+                let values = self.values(tuple)?;
+                shared.borrow_mut()?.tuple = values.into_boxed_slice();
+            }
+            (SharedState::TupleVariant { tuple, .. }, Value::TupleVariant(shared)) => {
+                let values = self.values(tuple)?;
+                shared.borrow_mut()?.tuple = values.into_boxed_slice();
+            }
+            (SharedState::TypedObject { object, .. }, Value::TypedObject(shared)) => {
+                shared.borrow_mut()?.fields = self.fields(object)?;
+            }
+            (SharedState::VariantObject { object, .. }, Value::VariantObject(shared)) => {
+                shared.borrow_mut()?.fields = self.fields(object)?;
+            }
+            (SharedState::Function(function), Value::Function(shared)) => {
+                *shared.borrow_mut()? = self.function(function)?;
+            }
+            // NB: values are constructed by `empty` to match their state.
+            _ => unreachable!("shared value doesn't match its state"),
+        }
+
+        Ok(())
+    }
+
+    fn function(&self, state: &FunctionState) -> Result<Function, SnapshotError> {
+        Ok(match state {
+            FunctionState::Handler { hash } => {
+                let handler = self
+                    .context
+                    .lookup(*hash)
+                    .ok_or_else(|| SnapshotError::MissingFunction { hash: *hash })?;
+
+                Function::from_handler(*hash, handler.clone())
+            }
+            FunctionState::Offset {
+                offset,
+                call,
+                args,
+                environment,
+            } => match environment {
+                Some(index) => {
+                    let environment = match self.shared(*index)? {
+                        Value::Tuple(tuple) => tuple,
+                        _ => return Err(SnapshotError::MissingValue { index: *index }),
+                    };
+
+                    Function::from_closure(
+                        self.context.clone(),
+                        self.unit.clone(),
+                        *offset,
+                        *call,
+                        *args,
+                        environment,
+                    )
+                }
+                None => Function::from_offset(
+                    self.context.clone(),
+                    self.unit.clone(),
+                    *offset,
+                    *call,
+                    *args,
+                ),
+            },
+            FunctionState::Tuple { hash, args } => Function::from_tuple(*hash, *args),
+            FunctionState::VariantTuple {
+                enum_hash,
+                hash,
+                args,
+            } => Function::from_variant_tuple(*enum_hash, *hash, *args),
+        })
+    }
+}
@@ -63,4 +63,20 @@ impl Source {
     pub fn path(&self) -> Option<&Path> {
         self.path.as_deref()
     }
+
+    /// Get the byte offset at which each line of the source starts.
+    pub(crate) fn line_starts(&self) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(self.source.match_indices('\n').map(|(n, _)| n + 1))
+            .collect()
+    }
+}
+
+/// Get the line, where the first line is `0`, that the given byte offset
+/// belongs to using line starts from [Source::line_starts].
+pub(crate) fn line_of(line_starts: &[usize], offset: usize) -> usize {
+    match line_starts.binary_search(&offset) {
+        Ok(line) => line,
+        Err(line) => line.saturating_sub(1),
+    }
 }
@@ -42,6 +42,11 @@ impl Stack {
         self.stack_bottom
     }
 
+    /// Set the bottom of the current stack frame.
+    pub(crate) fn set_stack_bottom(&mut self, stack_bottom: usize) {
+        self.stack_bottom = stack_bottom;
+    }
+
     /// Construct a new stack with the given capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
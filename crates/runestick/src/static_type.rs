@@ -68,10 +68,39 @@ impl_static_type!(u16 => crate::INTEGER_TYPE);
 impl_static_type!(i16 => crate::INTEGER_TYPE);
 impl_static_type!(u32 => crate::INTEGER_TYPE);
 impl_static_type!(i32 => crate::INTEGER_TYPE);
-impl_static_type!(u64 => crate::INTEGER_TYPE);
 impl_static_type!(i64 => crate::INTEGER_TYPE);
-impl_static_type!(u128 => crate::INTEGER_TYPE);
-impl_static_type!(i128 => crate::INTEGER_TYPE);
+
+/// The specialized type information for an unsigned 64-bit integer type.
+pub static U64_TYPE: &StaticType = &StaticType {
+    name: "u64",
+    hash: Hash::new(0x1001a6625a1298a1),
+};
+
+impl_static_type!(u64 => crate::U64_TYPE);
+
+/// The specialized type information for a signed 128-bit integer type.
+pub static I128_TYPE: &StaticType = &StaticType {
+    name: "i128",
+    hash: Hash::new(0x8dbb5b2a6e20af8e),
+};
+
+impl_static_type!(i128 => crate::I128_TYPE);
+
+/// The specialized type information for an unsigned 128-bit integer type.
+pub static U128_TYPE: &StaticType = &StaticType {
+    name: "u128",
+    hash: Hash::new(0x921f54d17423c60d),
+};
+
+impl_static_type!(u128 => crate::U128_TYPE);
+
+/// The specialized type information for an arbitrary precision integer type.
+pub static BIG_INT_TYPE: &StaticType = &StaticType {
+    name: "bigint",
+    hash: Hash::new(0x41e6cb0657eda214),
+};
+
+impl_static_type!(crate::BigInt => crate::BIG_INT_TYPE);
 
 /// The specialized type information for a float type.
 pub static FLOAT_TYPE: &StaticType = &StaticType {
@@ -183,4 +212,14 @@ pub static FUNCTION_TYPE: &StaticType = &StaticType {
 impl_static_type!(crate::Function => FUNCTION_TYPE);
 impl_static_type!(crate::Shared<crate::Function> => FUNCTION_TYPE);
 impl_static_type!(crate::Ref<crate::Function> => FUNCTION_TYPE);
+
+/// The specialized type information for a weak reference type.
+pub static WEAK_TYPE: &StaticType = &StaticType {
+    name: "Weak",
+    hash: Hash::new(0x2a5c6b3f8e9d1074),
+};
+
+impl_static_type!(crate::WeakValue => WEAK_TYPE);
+impl_static_type!(crate::Shared<crate::WeakValue> => WEAK_TYPE);
+impl_static_type!(crate::Ref<crate::WeakValue> => WEAK_TYPE);
 impl_static_type!(impl<T> std::collections::HashMap<String, T> => crate::OBJECT_TYPE);
@@ -20,6 +20,17 @@ impl Stream {
         }
     }
 
+    /// Construct a stream from its raw parts.
+    pub(crate) fn from_parts(execution: Option<VmExecution>, first: bool) -> Self {
+        Self { execution, first }
+    }
+
+    /// Get the execution of the stream, unless it has completed, and if it
+    /// has been resumed yet.
+    pub(crate) fn parts(&self) -> (Option<&VmExecution>, bool) {
+        (self.execution.as_ref(), self.first)
+    }
+
     /// Get the next value produced by this stream.
     pub async fn next(&mut self) -> Result<Option<Value>, VmError> {
         Ok(match self.resume(Value::Unit).await? {
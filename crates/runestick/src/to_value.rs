@@ -151,12 +151,9 @@ macro_rules! number_value_trait {
 }
 
 number_value_trait!(u32, U32);
-number_value_trait!(u64, U64);
-number_value_trait!(u128, U128);
 number_value_trait!(usize, Usize);
 number_value_trait!(i8, I8);
 number_value_trait!(i32, I32);
-number_value_trait!(i128, I128);
 number_value_trait!(isize, Isize);
 
 impl ToValue for f32 {
@@ -4,9 +4,16 @@
 //! metadata like function locations.
 
 use crate::collections::HashMap;
-use crate::{Call, DebugInfo, Hash, Inst, StaticString, Type, VmError, VmErrorKind};
+use crate::content_hash;
+use crate::unit_file;
+use crate::verify;
+use crate::{
+    Call, Compression, Context, DebugInfo, Hash, Inst, Shape, StaticString, Type, UnitFileError,
+    VerifyErrors, VmError, VmErrorKind,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::io;
 use std::sync::Arc;
 
 /// Instructions from a single source file.
@@ -27,8 +34,9 @@ pub struct Unit {
     /// This is used when an object is used in a pattern match, to avoid having
     /// to send the collection of keys to the virtual machine.
     ///
-    /// All keys are sorted with the default string sort.
-    static_object_keys: Vec<Box<[String]>>,
+    /// Each collection of keys is stored as a shape, which is shared by all
+    /// structs constructed with them.
+    static_object_keys: Vec<Arc<Shape>>,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
 }
@@ -50,7 +58,10 @@ impl Unit {
             types,
             static_strings,
             static_bytes,
-            static_object_keys,
+            static_object_keys: static_object_keys
+                .into_iter()
+                .map(|keys| Arc::new(Shape::new(keys.into_vec())))
+                .collect(),
             debug,
         }
     }
@@ -66,11 +77,61 @@ impl Unit {
         Some(&**debug)
     }
 
+    /// Calculate a hash of the content of the unit, which is stable across
+    /// processes and can be used to tell if two units are the same.
+    pub fn content_hash(&self) -> Hash {
+        // NB: nothing in a unit fails to serialize.
+        content_hash::content_hash(self).expect("unit content can always be hashed")
+    }
+
     /// Get the instruction at the given instruction pointer.
     pub fn instruction_at(&self, ip: usize) -> Option<&Inst> {
         self.instructions.get(ip)
     }
 
+    /// Access all instructions.
+    pub(crate) fn instructions(&self) -> &[Inst] {
+        &self.instructions
+    }
+
+    /// Verify that the unit can be safely executed with the given context.
+    ///
+    /// This checks that every jump stays inside of its function, that every
+    /// static slot and called function exists, and that the stack is used
+    /// consistently by every function. Units which haven't been produced by
+    /// the compiler, like ones loaded from a bytecode cache, should be
+    /// verified before they are executed.
+    pub fn verify(&self, context: &Context) -> Result<(), VerifyErrors> {
+        verify::verify(self, context)
+    }
+
+    /// Write the unit to the given writer using the versioned unit file
+    /// format, recording the [fingerprint][Context::fingerprint] of the
+    /// context it was compiled against.
+    pub fn write_file<W>(
+        &self,
+        writer: W,
+        context: &Context,
+        compression: Compression,
+    ) -> Result<(), UnitFileError>
+    where
+        W: io::Write,
+    {
+        unit_file::write(writer, self, context, compression)
+    }
+
+    /// Read a unit written with [write_file][Unit::write_file].
+    ///
+    /// This fails if the file was written by a different version of
+    /// runestick, or if the unit was compiled against a context which doesn't
+    /// match the given one.
+    pub fn read_file<R>(reader: R, context: &Context) -> Result<Self, UnitFileError>
+    where
+        R: io::Read,
+    {
+        unit_file::read(reader, context)
+    }
+
     /// Iterate over all static strings in the unit.
     pub fn iter_static_strings(&self) -> impl Iterator<Item = &Arc<StaticString>> + '_ {
         self.static_strings.iter()
@@ -81,8 +142,8 @@ impl Unit {
         let mut it = self.static_object_keys.iter();
 
         std::iter::from_fn(move || {
-            let s = it.next()?;
-            Some((Hash::object_keys(&s[..]), &s[..]))
+            let s = it.next()?.keys();
+            Some((Hash::object_keys(s), s))
         })
     }
 
@@ -120,7 +181,12 @@ impl Unit {
 
     /// Lookup the static object keys by slot, if it exists.
     pub fn lookup_object_keys(&self, slot: usize) -> Option<&[String]> {
-        self.static_object_keys.get(slot).map(|keys| &keys[..])
+        self.static_object_keys.get(slot).map(|shape| shape.keys())
+    }
+
+    /// Lookup the shape of the static object keys by slot, if it exists.
+    pub fn lookup_shape(&self, slot: usize) -> Option<&Arc<Shape>> {
+        self.static_object_keys.get(slot)
     }
 
     /// Lookup information of a function.
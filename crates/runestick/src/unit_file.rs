@@ -0,0 +1,233 @@
+//! A versioned container format for storing compiled units.
+//!
+//! A unit file starts with a fixed magic number and format version, followed
+//! by a [UnitFileHeader] and the serialized unit itself, which can optionally
+//! be compressed. The header records which version of runestick produced the
+//! unit and a [fingerprint][Context::fingerprint] of the context it was
+//! compiled against, so that stale files can be detected before the unit is
+//! loaded.
+
+use crate::{Context, Hash, Unit};
+use serde::{Deserialize, Serialize};
+use std::io;
+use thiserror::Error;
+
+/// The magic number every unit file starts with.
+const MAGIC: [u8; 8] = *b"RUNEUNIT";
+
+/// The current version of the unit file format.
+///
+/// This must be bumped whenever the layout of the header changes.
+const FORMAT_VERSION: u32 = 1;
+
+/// The version of runestick used to write unit files.
+const RUNESTICK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Error raised when reading or writing a unit file.
+#[derive(Debug, Error)]
+pub enum UnitFileError {
+    /// The file doesn't start with the expected magic number.
+    #[error("not a unit file")]
+    NotAUnitFile,
+    /// The file was written with an unsupported version of the format.
+    #[error("unsupported unit file format version `{version}`")]
+    UnsupportedFormatVersion {
+        /// The format version of the file.
+        version: u32,
+    },
+    /// The file was written by a different version of runestick.
+    #[error("unit file was written by runestick `{actual}`, but expected `{expected}`")]
+    VersionMismatch {
+        /// The version of runestick in use.
+        expected: String,
+        /// The version of runestick which wrote the file.
+        actual: String,
+    },
+    /// The unit was compiled against a different context.
+    #[error("unit file was compiled against context `{actual}`, but expected `{expected}`")]
+    ContextMismatch {
+        /// The fingerprint of the context in use.
+        expected: Hash,
+        /// The fingerprint of the context the unit was compiled against.
+        actual: Hash,
+    },
+    /// The content of the unit doesn't match the hash stored in the header.
+    #[error("unit file is corrupt, expected content `{expected}` but got `{actual}`")]
+    Corrupt {
+        /// The content hash stored in the header.
+        expected: Hash,
+        /// The content hash of the unit that was read.
+        actual: Hash,
+    },
+    /// An I/O error was raised.
+    #[error("{error}")]
+    Io {
+        /// Source error.
+        #[from]
+        error: io::Error,
+    },
+    /// The header or unit couldn't be encoded or decoded.
+    #[error("{error}")]
+    Encoding {
+        /// Source error.
+        #[from]
+        error: bincode::Error,
+    },
+}
+
+/// The compression used for the unit stored in a unit file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compression {
+    /// The unit is stored as-is.
+    None,
+    /// The unit is compressed using deflate.
+    Deflate,
+}
+
+/// The header of a unit file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitFileHeader {
+    /// The version of runestick which wrote the file.
+    version: String,
+    /// The fingerprint of the context the unit was compiled against.
+    context: Hash,
+    /// The content hash of the stored unit.
+    content: Hash,
+    /// The compression used for the stored unit.
+    compression: Compression,
+}
+
+impl UnitFileHeader {
+    /// Read the header from the start of a unit file.
+    ///
+    /// This only checks that the file has the expected format version, and
+    /// leaves the reader positioned at the start of the stored unit.
+    pub fn read<R>(mut reader: R) -> Result<Self, UnitFileError>
+    where
+        R: io::Read,
+    {
+        let mut magic = [0u8; MAGIC.len()];
+
+        match reader.read_exact(&mut magic) {
+            Ok(()) => (),
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(UnitFileError::NotAUnitFile);
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        if magic != MAGIC {
+            return Err(UnitFileError::NotAUnitFile);
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+
+        if version != FORMAT_VERSION {
+            return Err(UnitFileError::UnsupportedFormatVersion { version });
+        }
+
+        Ok(bincode::deserialize_from(reader)?)
+    }
+
+    /// The version of runestick which wrote the file.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// The fingerprint of the context the unit was compiled against.
+    pub fn context(&self) -> Hash {
+        self.context
+    }
+
+    /// The content hash of the stored unit.
+    pub fn content(&self) -> Hash {
+        self.content
+    }
+
+    /// The compression used for the stored unit.
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+}
+
+/// Write the given unit as a unit file.
+pub(crate) fn write<W>(
+    mut writer: W,
+    unit: &Unit,
+    context: &Context,
+    compression: Compression,
+) -> Result<(), UnitFileError>
+where
+    W: io::Write,
+{
+    let header = UnitFileHeader {
+        version: RUNESTICK_VERSION.to_owned(),
+        context: context.fingerprint(),
+        content: unit.content_hash(),
+        compression,
+    };
+
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    bincode::serialize_into(&mut writer, &header)?;
+
+    match compression {
+        Compression::None => {
+            bincode::serialize_into(&mut writer, unit)?;
+        }
+        Compression::Deflate => {
+            let mut encoder =
+                flate2::write::DeflateEncoder::new(&mut writer, flate2::Compression::default());
+            bincode::serialize_into(&mut encoder, unit)?;
+            encoder.finish()?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Read a unit from a unit file, checking that it was written by the same
+/// version of runestick and compiled against the given context.
+pub(crate) fn read<R>(mut reader: R, context: &Context) -> Result<Unit, UnitFileError>
+where
+    R: io::Read,
+{
+    let header = UnitFileHeader::read(&mut reader)?;
+
+    if header.version != RUNESTICK_VERSION {
+        return Err(UnitFileError::VersionMismatch {
+            expected: RUNESTICK_VERSION.to_owned(),
+            actual: header.version,
+        });
+    }
+
+    let fingerprint = context.fingerprint();
+
+    if header.context != fingerprint {
+        return Err(UnitFileError::ContextMismatch {
+            expected: fingerprint,
+            actual: header.context,
+        });
+    }
+
+    let unit: Unit = match header.compression {
+        Compression::None => bincode::deserialize_from(reader)?,
+        Compression::Deflate => {
+            bincode::deserialize_from(flate2::read::DeflateDecoder::new(reader))?
+        }
+    };
+
+    let content = unit.content_hash();
+
+    if header.content != content {
+        return Err(UnitFileError::Corrupt {
+            expected: header.content,
+            actual: content,
+        });
+    }
+
+    Ok(unit)
+}
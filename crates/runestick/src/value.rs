@@ -1,8 +1,10 @@
 use crate::access::AccessKind;
 use crate::{
-    Any, AnyObj, Bytes, Function, Future, Generator, GeneratorState, Hash, Mut, Object, RawMut,
-    RawRef, Ref, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    Any, AnyObj, BigInt, Bytes, Fields, Function, Future, Generator, GeneratorState, Hash, Mut,
+    Object, RawMut, RawRef, Ref, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    VmErrorKind, WeakValue,
 };
+use std::cmp;
 use std::fmt;
 use std::sync::Arc;
 
@@ -50,14 +52,19 @@ impl TupleVariant {
 pub struct TypedObject {
     /// The type hash of the object.
     hash: Hash,
-    /// Content of the object.
-    pub(crate) object: Object,
+    /// Fields of the object.
+    pub(crate) fields: Fields,
 }
 
 impl TypedObject {
     /// Construct a new typed object with the given type hash.
     pub fn new(hash: Hash, object: Object) -> Self {
-        Self { hash, object }
+        Self::with_fields(hash, Fields::from(object))
+    }
+
+    /// Construct a new typed object with the given type hash and fields.
+    pub fn with_fields(hash: Hash, fields: Fields) -> Self {
+        Self { hash, fields }
     }
 
     /// Get type info for the typed object.
@@ -77,7 +84,17 @@ impl TypedObject {
         String: std::borrow::Borrow<Q>,
         Q: std::hash::Hash + std::cmp::Eq,
     {
-        self.object.get(k)
+        self.fields.get(k)
+    }
+
+    /// Get the fields of the object.
+    pub fn fields(&self) -> &Fields {
+        &self.fields
+    }
+
+    /// Get the fields of the object mutably.
+    pub fn fields_mut(&mut self) -> &mut Fields {
+        &mut self.fields
     }
 }
 
@@ -88,8 +105,8 @@ pub struct VariantObject {
     pub enum_hash: Hash,
     /// The type variant hash.
     pub hash: Hash,
-    /// Content of the object.
-    pub object: Object,
+    /// Fields of the object.
+    pub fields: Fields,
 }
 
 impl VariantObject {
@@ -112,6 +129,17 @@ pub enum Value {
     Char(char),
     /// A number.
     Integer(i64),
+    /// An unsigned 64-bit integer.
+    U64(u64),
+    /// A signed 128-bit integer.
+    ///
+    /// Wide integers are stored behind a pointer to keep a stack value at 16
+    /// bytes in size.
+    I128(Arc<i128>),
+    /// An unsigned 128-bit integer.
+    U128(Arc<u128>),
+    /// An arbitrary precision integer.
+    BigInt(Arc<BigInt>),
     /// A float.
     Float(f64),
     /// A type hash. Describes a type in the virtual machine.
@@ -157,6 +185,8 @@ pub enum Value {
     VariantObject(Shared<VariantObject>),
     /// A stored function pointer.
     Function(Shared<Function>),
+    /// A weak reference to a value.
+    Weak(Shared<WeakValue>),
     /// An opaque value that can be downcasted.
     Any(Shared<AnyObj>),
 }
@@ -353,6 +383,15 @@ impl Value {
         }
     }
 
+    /// Try to coerce value into a weak reference.
+    #[inline]
+    pub fn into_weak(self) -> Result<Shared<WeakValue>, VmError> {
+        match self {
+            Self::Weak(weak) => Ok(weak),
+            actual => Err(VmError::expected::<WeakValue>(actual.type_info()?)),
+        }
+    }
+
     /// Try to coerce value into an opaque value.
     #[inline]
     pub fn into_any(self) -> Result<Shared<AnyObj>, VmError> {
@@ -418,6 +457,10 @@ impl Value {
             Self::Byte(..) => Type::from(crate::BYTE_TYPE),
             Self::Char(..) => Type::from(crate::CHAR_TYPE),
             Self::Integer(..) => Type::from(crate::INTEGER_TYPE),
+            Self::U64(..) => Type::from(crate::U64_TYPE),
+            Self::I128(..) => Type::from(crate::I128_TYPE),
+            Self::U128(..) => Type::from(crate::U128_TYPE),
+            Self::BigInt(..) => Type::from(crate::BIG_INT_TYPE),
             Self::Float(..) => Type::from(crate::FLOAT_TYPE),
             Self::StaticString(..) => Type::from(crate::STRING_TYPE),
             Self::String(..) => Type::from(crate::STRING_TYPE),
@@ -432,6 +475,7 @@ impl Value {
             Self::Result(..) => Type::from(crate::RESULT_TYPE),
             Self::Option(..) => Type::from(crate::OPTION_TYPE),
             Self::Function(..) => Type::from(crate::FUNCTION_TYPE),
+            Self::Weak(..) => Type::from(crate::WEAK_TYPE),
             Self::Type(hash) => Type::from(*hash),
             Self::TypedObject(object) => Type::from(object.borrow_ref()?.hash),
             Self::VariantObject(object) => {
@@ -455,6 +499,10 @@ impl Value {
             Self::Byte(..) => TypeInfo::StaticType(crate::BYTE_TYPE),
             Self::Char(..) => TypeInfo::StaticType(crate::CHAR_TYPE),
             Self::Integer(..) => TypeInfo::StaticType(crate::INTEGER_TYPE),
+            Self::U64(..) => TypeInfo::StaticType(crate::U64_TYPE),
+            Self::I128(..) => TypeInfo::StaticType(crate::I128_TYPE),
+            Self::U128(..) => TypeInfo::StaticType(crate::U128_TYPE),
+            Self::BigInt(..) => TypeInfo::StaticType(crate::BIG_INT_TYPE),
             Self::Float(..) => TypeInfo::StaticType(crate::FLOAT_TYPE),
             Self::StaticString(..) => TypeInfo::StaticType(crate::STRING_TYPE),
             Self::String(..) => TypeInfo::StaticType(crate::STRING_TYPE),
@@ -469,6 +517,7 @@ impl Value {
             Self::Option(..) => TypeInfo::StaticType(crate::OPTION_TYPE),
             Self::Result(..) => TypeInfo::StaticType(crate::RESULT_TYPE),
             Self::Function(..) => TypeInfo::StaticType(crate::FUNCTION_TYPE),
+            Self::Weak(..) => TypeInfo::StaticType(crate::WEAK_TYPE),
             Self::Type(hash) => TypeInfo::Hash(*hash),
             Self::TypedObject(object) => object.borrow_ref()?.type_info(),
             Self::VariantObject(object) => object.borrow_ref()?.type_info(),
@@ -544,9 +593,69 @@ impl Value {
             // fast external comparison by slot.
             // TODO: implement ptr equals.
             // (Self::Any(a), Self::Any(b)) => a == b,
-            _ => false,
+            (a, b) => crate::numeric::cmp(a, b) == Some(cmp::Ordering::Equal),
         })
     }
+
+    /// Compare two values according to their natural ordering.
+    ///
+    /// Values of the same primitive type are ordered as they are in Rust,
+    /// static strings and strings compare by content, and vectors, tuples and
+    /// options are ordered lexicographically by their elements. Any other
+    /// combination of values, like floats which are NaN, results in an
+    /// [UnsupportedOrdering][VmErrorKind::UnsupportedOrdering] error.
+    pub(crate) fn value_ptr_cmp(a: &Value, b: &Value) -> Result<cmp::Ordering, VmError> {
+        let ordering = match (a, b) {
+            (Self::Unit, Self::Unit) => Some(cmp::Ordering::Equal),
+            (Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+            (Self::Byte(a), Self::Byte(b)) => Some(a.cmp(b)),
+            (Self::Char(a), Self::Char(b)) => Some(a.cmp(b)),
+            (Self::Integer(a), Self::Integer(b)) => Some(a.cmp(b)),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::StaticString(a), Self::StaticString(b)) => Some(a.as_str().cmp(b.as_str())),
+            (Self::String(a), Self::String(b)) => Some(a.borrow_ref()?.cmp(&*b.borrow_ref()?)),
+            (Self::StaticString(a), Self::String(b)) => {
+                Some(a.as_str().cmp(b.borrow_ref()?.as_str()))
+            }
+            (Self::String(a), Self::StaticString(b)) => {
+                Some(a.borrow_ref()?.as_str().cmp(b.as_str()))
+            }
+            (Self::Bytes(a), Self::Bytes(b)) => Some(a.borrow_ref()?.cmp(&*b.borrow_ref()?)),
+            (Self::Vec(a), Self::Vec(b)) => {
+                return Self::values_cmp(&a.borrow_ref()?, &b.borrow_ref()?);
+            }
+            (Self::Tuple(a), Self::Tuple(b)) => {
+                return Self::values_cmp(&a.borrow_ref()?, &b.borrow_ref()?);
+            }
+            (Self::Option(a), Self::Option(b)) => {
+                return match (&*a.borrow_ref()?, &*b.borrow_ref()?) {
+                    (Some(a), Some(b)) => Self::value_ptr_cmp(a, b),
+                    (a, b) => Ok(a.is_some().cmp(&b.is_some())),
+                };
+            }
+            (a, b) => crate::numeric::cmp(a, b),
+        };
+
+        match ordering {
+            Some(ordering) => Ok(ordering),
+            None => Err(VmError::from(VmErrorKind::UnsupportedOrdering {
+                lhs: a.type_info()?,
+                rhs: b.type_info()?,
+            })),
+        }
+    }
+
+    /// Compare two slices of values lexicographically.
+    fn values_cmp(a: &[Value], b: &[Value]) -> Result<cmp::Ordering, VmError> {
+        for (a, b) in a.iter().zip(b.iter()) {
+            match Self::value_ptr_cmp(a, b)? {
+                cmp::Ordering::Equal => (),
+                ordering => return Ok(ordering),
+            }
+        }
+
+        Ok(a.len().cmp(&b.len()))
+    }
 }
 
 impl fmt::Debug for Value {
@@ -567,6 +676,18 @@ impl fmt::Debug for Value {
             Value::Integer(value) => {
                 write!(f, "{:?}", value)?;
             }
+            Value::U64(value) => {
+                write!(f, "{:?}", value)?;
+            }
+            Value::I128(value) => {
+                write!(f, "{:?}", value)?;
+            }
+            Value::U128(value) => {
+                write!(f, "{:?}", value)?;
+            }
+            Value::BigInt(value) => {
+                write!(f, "{}", value)?;
+            }
             Value::Float(value) => {
                 write!(f, "{:?}", value)?;
             }
@@ -624,6 +745,9 @@ impl fmt::Debug for Value {
             Value::Function(value) => {
                 write!(f, "{:?}", value)?;
             }
+            Value::Weak(value) => {
+                write!(f, "{:?}", value)?;
+            }
             Value::Any(value) => {
                 write!(f, "{:?}", value)?;
             }
@@ -680,8 +804,32 @@ impl_from!(u8, Byte);
 impl_from!(bool, Bool);
 impl_from!(char, Char);
 impl_from!(i64, Integer);
+impl_from!(u64, U64);
 impl_from!(f64, Float);
 impl_from!(Arc<StaticString>, StaticString);
+impl_from!(Arc<i128>, I128);
+impl_from!(Arc<u128>, U128);
+impl_from!(Arc<BigInt>, BigInt);
+
+macro_rules! impl_from_arc {
+    ($ty:ty, $variant:ident) => {
+        impl From<$ty> for Value {
+            fn from(value: $ty) -> Self {
+                Self::$variant(Arc::new(value))
+            }
+        }
+
+        impl $crate::ToValue for $ty {
+            fn to_value(self) -> Result<Value, VmError> {
+                Ok(Value::from(self))
+            }
+        }
+    };
+}
+
+impl_from_arc!(i128, I128);
+impl_from_arc!(u128, U128);
+impl_from_arc!(BigInt, BigInt);
 
 macro_rules! impl_from_shared {
     (Shared<$ty:ty>, $variant:ident) => {
@@ -717,6 +865,7 @@ impl_from_shared!(Shared<TupleVariant>, TupleVariant);
 impl_from_shared!(Shared<TypedObject>, TypedObject);
 impl_from_shared!(Shared<VariantObject>, VariantObject);
 impl_from_shared!(Shared<Function>, Function);
+impl_from_shared!(Shared<WeakValue>, Weak);
 impl_from_shared!(Shared<AnyObj>, Any);
 
 #[cfg(test)]
@@ -0,0 +1,577 @@
+//! Verification of units before they are executed.
+//!
+//! Units which are deserialized, like from a bytecode cache, haven't been
+//! produced by a compiler we trust. Verification makes sure that they can't
+//! make the virtual machine jump or reach outside of the unit.
+
+use crate::{Context, Hash, Inst, Unit, UnitFn};
+use std::fmt;
+use std::ops::Range;
+use thiserror::Error;
+
+/// An error raised when verifying a unit.
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    /// A function starts outside of the instructions of the unit.
+    #[error("function `{hash}` at offset `{offset}` is out of bounds")]
+    FunctionOutOfBounds {
+        /// The hash of the function.
+        hash: Hash,
+        /// The offset of the function.
+        offset: usize,
+    },
+    /// A jump leads outside of the function it's part of.
+    #[error("{ip}: jump with offset `{offset}` leads outside of the function")]
+    JumpOutOfBounds {
+        /// The instruction pointer of the jump.
+        ip: usize,
+        /// The offset of the jump.
+        offset: isize,
+    },
+    /// Execution can run past the end of a function.
+    #[error("{ip}: execution runs past the end of the function")]
+    FallthroughEnd {
+        /// The instruction pointer of the last instruction in the function.
+        ip: usize,
+    },
+    /// An instruction references a static string which doesn't exist.
+    #[error("{ip}: missing static string in slot `{slot}`")]
+    MissingStaticString {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// An instruction references a static byte string which doesn't exist.
+    #[error("{ip}: missing static byte string in slot `{slot}`")]
+    MissingStaticBytes {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// An instruction references static object keys which don't exist.
+    #[error("{ip}: missing static object keys in slot `{slot}`")]
+    MissingStaticObjectKeys {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The missing slot.
+        slot: usize,
+    },
+    /// An instruction references a field outside of a shape.
+    #[error("{ip}: field `{index}` is out of bounds for the shape in slot `{slot}`")]
+    ShapeIndexOutOfBounds {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The slot of the shape.
+        slot: usize,
+        /// The index of the field.
+        index: usize,
+    },
+    /// An instruction references a function which exists neither in the unit
+    /// nor in the context.
+    #[error("{ip}: missing function with hash `{hash}`")]
+    MissingFunction {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The hash of the missing function.
+        hash: Hash,
+    },
+    /// An instruction pops more values than are available in its frame.
+    #[error("{ip}: instruction needs `{required}` values, but only `{depth}` are on the stack")]
+    StackUnderflow {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The number of values required.
+        required: usize,
+        /// The number of values on the stack.
+        depth: usize,
+    },
+    /// An instruction accesses a frame offset which is out of bounds.
+    #[error("{ip}: frame offset `{offset}` is out of bounds for a stack of `{depth}` values")]
+    FrameOffsetOutOfBounds {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The frame offset accessed.
+        offset: usize,
+        /// The number of values on the stack.
+        depth: usize,
+    },
+    /// An instruction is reached with different stack depths.
+    #[error("{ip}: reached with `{actual}` values on the stack, but expected `{expected}`")]
+    InconsistentStack {
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The stack depth the instruction was first reached with.
+        expected: usize,
+        /// The conflicting stack depth.
+        actual: usize,
+    },
+}
+
+/// Errors raised when verifying a unit.
+#[derive(Debug, Default)]
+pub struct VerifyErrors {
+    errors: Vec<VerifyError>,
+}
+
+impl VerifyErrors {
+    /// Test if error collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Get the number of errors.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Return an iterator over all verification errors.
+    pub fn errors(self) -> impl Iterator<Item = VerifyError> {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a VerifyErrors {
+    type IntoIter = std::slice::Iter<'a, VerifyError>;
+    type Item = <Self::IntoIter as Iterator>::Item;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl fmt::Display for VerifyErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unit failed verification")?;
+
+        for error in &self.errors {
+            write!(f, "\n{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for VerifyErrors {}
+
+/// The depth of the stack at an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Depth {
+    /// The depth is known.
+    Known(usize),
+    /// The depth can't be determined statically, like after a `Select`.
+    Unknown,
+}
+
+/// How control continues after an instruction.
+enum Flow {
+    /// Continue with the next instruction.
+    Next,
+    /// Jump unconditionally.
+    Jump(isize),
+    /// Either continue with the next instruction, or jump after popping an
+    /// additional number of values.
+    Branch(isize, usize),
+    /// Leave the function.
+    Exit,
+}
+
+/// Verify the given unit against a context.
+pub(crate) fn verify(unit: &Unit, context: &Context) -> Result<(), VerifyErrors> {
+    let instructions = unit.instructions();
+    let mut errors = Vec::new();
+    let mut functions = Vec::new();
+
+    for (hash, f) in unit.iter_functions() {
+        if let UnitFn::Offset { offset, args, .. } = *f {
+            if offset >= instructions.len() {
+                errors.push(VerifyError::FunctionOutOfBounds { hash, offset });
+                continue;
+            }
+
+            functions.push((offset, args));
+        }
+    }
+
+    // NB: the same function can be registered under multiple hashes.
+    functions.sort();
+    functions.dedup_by_key(|(offset, _)| *offset);
+
+    for (n, (offset, args)) in functions.iter().copied().enumerate() {
+        let end = match functions.get(n + 1) {
+            Some((next, _)) => *next,
+            None => instructions.len(),
+        };
+
+        let range = offset..end;
+        verify_operands(unit, context, range.clone(), &mut errors);
+        verify_stack(unit, range, args, &mut errors);
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    errors.sort_by_key(error_ip);
+    Err(VerifyErrors { errors })
+}
+
+/// Verify that the operands of every instruction in the given range refers to
+/// things which exist.
+fn verify_operands(
+    unit: &Unit,
+    context: &Context,
+    range: Range<usize>,
+    errors: &mut Vec<VerifyError>,
+) {
+    for ip in range.clone() {
+        let inst = unit.instructions()[ip];
+
+        if let Some(offset) = jump_offset(&inst) {
+            if jump_target(ip, offset, &range).is_none() {
+                errors.push(VerifyError::JumpOutOfBounds { ip, offset });
+            }
+        }
+
+        match inst {
+            Inst::Call { hash, .. } | Inst::Fn { hash } | Inst::Closure { hash, .. }
+                if unit.lookup(hash).is_none() && context.lookup(hash).is_none() =>
+            {
+                errors.push(VerifyError::MissingFunction { ip, hash });
+            }
+            Inst::String { slot }
+            | Inst::EqStaticString { slot }
+            | Inst::ObjectSlotIndexGet { slot }
+            | Inst::ObjectSlotIndexGetAt { slot, .. }
+            | Inst::ObjectSlotIndexSet { slot }
+                if unit.lookup_string(slot).is_err() =>
+            {
+                errors.push(VerifyError::MissingStaticString { ip, slot });
+            }
+            Inst::Bytes { slot } | Inst::BigInt { slot } if unit.lookup_bytes(slot).is_err() => {
+                errors.push(VerifyError::MissingStaticBytes { ip, slot });
+            }
+            Inst::Object { slot }
+            | Inst::TypedObject { slot, .. }
+            | Inst::VariantObject { slot, .. }
+            | Inst::MatchObject { slot, .. }
+                if unit.lookup_shape(slot).is_none() =>
+            {
+                errors.push(VerifyError::MissingStaticObjectKeys { ip, slot });
+            }
+            Inst::ShapeIndexGetAt { slot, index, .. } => match unit.lookup_shape(slot) {
+                Some(shape) if index < shape.len() => (),
+                Some(..) => {
+                    errors.push(VerifyError::ShapeIndexOutOfBounds { ip, slot, index });
+                }
+                None => {
+                    errors.push(VerifyError::MissingStaticObjectKeys { ip, slot });
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+/// Verify that the stack is used consistently in the function occupying the
+/// given range, which is called with `args` values on the stack.
+///
+/// This follows every path through the function, making sure that no
+/// instruction pops more values than are available, that frame offsets are in
+/// bounds, and that every instruction is reached with the same stack depth.
+fn verify_stack(unit: &Unit, range: Range<usize>, args: usize, errors: &mut Vec<VerifyError>) {
+    let mut depths = vec![None; range.len()];
+    let mut queue = vec![(range.start, Depth::Known(args))];
+
+    while let Some((ip, depth)) = queue.pop() {
+        let seen = &mut depths[ip - range.start];
+
+        match (*seen, depth) {
+            (None, _) | (Some(Depth::Known(..)), Depth::Unknown) => {
+                *seen = Some(depth);
+            }
+            (Some(Depth::Known(expected)), Depth::Known(actual)) if expected != actual => {
+                errors.push(VerifyError::InconsistentStack {
+                    ip,
+                    expected,
+                    actual,
+                });
+                continue;
+            }
+            _ => continue,
+        }
+
+        let inst = unit.instructions()[ip];
+        let (pops, pushes) = stack_effect(unit, &inst);
+        let flow = flow(&inst);
+
+        let (next, jump) = match depth {
+            Depth::Known(depth) => {
+                let extra = match flow {
+                    Flow::Branch(_, extra) => extra,
+                    _ => 0,
+                };
+
+                let required = pops + extra;
+
+                if depth < required {
+                    errors.push(VerifyError::StackUnderflow {
+                        ip,
+                        required,
+                        depth,
+                    });
+                    continue;
+                }
+
+                let popped = depth - pops;
+
+                for (offset, after_pop) in frame_offsets(&inst) {
+                    let available = if after_pop { popped } else { depth };
+
+                    if offset >= available {
+                        errors.push(VerifyError::FrameOffsetOutOfBounds {
+                            ip,
+                            offset,
+                            depth: available,
+                        });
+                    }
+                }
+
+                let next = match pushes {
+                    Some(pushes) => Depth::Known(popped + pushes),
+                    None => Depth::Unknown,
+                };
+
+                (next, Depth::Known(popped - extra))
+            }
+            Depth::Unknown => (Depth::Unknown, Depth::Unknown),
+        };
+
+        match flow {
+            Flow::Next => {
+                if ip + 1 < range.end {
+                    queue.push((ip + 1, next));
+                } else {
+                    errors.push(VerifyError::FallthroughEnd { ip });
+                }
+            }
+            Flow::Jump(offset) => {
+                if let Some(target) = jump_target(ip, offset, &range) {
+                    queue.push((target, next));
+                }
+            }
+            Flow::Branch(offset, _) => {
+                if ip + 1 < range.end {
+                    queue.push((ip + 1, next));
+                } else {
+                    errors.push(VerifyError::FallthroughEnd { ip });
+                }
+
+                if let Some(target) = jump_target(ip, offset, &range) {
+                    queue.push((target, jump));
+                }
+            }
+            Flow::Exit => (),
+        }
+    }
+}
+
+/// The instruction pointer an error refers to.
+fn error_ip(error: &VerifyError) -> usize {
+    match *error {
+        VerifyError::FunctionOutOfBounds { offset, .. } => offset,
+        VerifyError::JumpOutOfBounds { ip, .. }
+        | VerifyError::FallthroughEnd { ip }
+        | VerifyError::MissingStaticString { ip, .. }
+        | VerifyError::MissingStaticBytes { ip, .. }
+        | VerifyError::MissingStaticObjectKeys { ip, .. }
+        | VerifyError::ShapeIndexOutOfBounds { ip, .. }
+        | VerifyError::MissingFunction { ip, .. }
+        | VerifyError::StackUnderflow { ip, .. }
+        | VerifyError::FrameOffsetOutOfBounds { ip, .. }
+        | VerifyError::InconsistentStack { ip, .. } => ip,
+    }
+}
+
+/// Get the target of a jump at `ip`, if it's inside of the given range.
+fn jump_target(ip: usize, offset: isize, range: &Range<usize>) -> Option<usize> {
+    // NB: the instruction pointer is advanced after the jump.
+    let target = (ip as isize).checked_add(offset)?.checked_add(1)?;
+
+    if target < range.start as isize || target >= range.end as isize {
+        return None;
+    }
+
+    Some(target as usize)
+}
+
+/// Get the jump offset of an instruction.
+fn jump_offset(inst: &Inst) -> Option<isize> {
+    match *inst {
+        Inst::Jump { offset }
+        | Inst::JumpIf { offset }
+        | Inst::JumpIfNot { offset }
+        | Inst::JumpIfBranch { offset, .. }
+        | Inst::JumpIfNotLt { offset, .. }
+        | Inst::PopAndJumpIfNot { offset, .. }
+        | Inst::PopAndJumpIfNotInteger { offset, .. } => Some(offset),
+        _ => None,
+    }
+}
+
+/// How control continues after the given instruction.
+fn flow(inst: &Inst) -> Flow {
+    match *inst {
+        Inst::Return | Inst::ReturnUnit | Inst::Panic { .. } => Flow::Exit,
+        Inst::Jump { offset } => Flow::Jump(offset),
+        Inst::JumpIf { offset } | Inst::JumpIfNot { offset } | Inst::JumpIfNotLt { offset, .. } => {
+            Flow::Branch(offset, 0)
+        }
+        Inst::JumpIfBranch { offset, .. } => Flow::Branch(offset, 1),
+        Inst::PopAndJumpIfNot { count, offset }
+        | Inst::PopAndJumpIfNotInteger { count, offset, .. } => Flow::Branch(offset, count),
+        _ => Flow::Next,
+    }
+}
+
+/// The frame offsets accessed by an instruction, and whether they are accessed
+/// after the instruction has popped its operands.
+fn frame_offsets(inst: &Inst) -> Vec<(usize, bool)> {
+    match *inst {
+        Inst::Copy { offset }
+        | Inst::Drop { offset }
+        | Inst::AddImmediate { offset, .. }
+        | Inst::TupleIndexGetAt { offset, .. }
+        | Inst::ObjectSlotIndexGetAt { offset, .. }
+        | Inst::ShapeIndexGetAt { offset, .. } => vec![(offset, false)],
+        Inst::JumpIfNotLt { lhs, rhs, .. } => vec![(lhs, false), (rhs, false)],
+        Inst::Replace { offset }
+        | Inst::AddAssign { offset }
+        | Inst::SubAssign { offset }
+        | Inst::MulAssign { offset }
+        | Inst::DivAssign { offset }
+        | Inst::RemAssign { offset }
+        | Inst::BitAndAssign { offset }
+        | Inst::BitXorAssign { offset }
+        | Inst::BitOrAssign { offset }
+        | Inst::ShlAssign { offset }
+        | Inst::ShrAssign { offset } => vec![(offset, true)],
+        _ => Vec::new(),
+    }
+}
+
+/// The number of values popped and pushed by an instruction.
+///
+/// The number of values pushed is `None` if it can't be determined
+/// statically.
+fn stack_effect(unit: &Unit, inst: &Inst) -> (usize, Option<usize>) {
+    let (pops, pushes) = match *inst {
+        Inst::Integer { .. }
+        | Inst::U64 { .. }
+        | Inst::I128 { .. }
+        | Inst::U128 { .. }
+        | Inst::BigInt { .. }
+        | Inst::Float { .. }
+        | Inst::Unit
+        | Inst::Bool { .. }
+        | Inst::Char { .. }
+        | Inst::Byte { .. }
+        | Inst::String { .. }
+        | Inst::Bytes { .. }
+        | Inst::Type { .. }
+        | Inst::Fn { .. }
+        | Inst::Copy { .. }
+        | Inst::AddImmediate { .. }
+        | Inst::TupleIndexGetAt { .. }
+        | Inst::ObjectSlotIndexGetAt { .. }
+        | Inst::ShapeIndexGetAt { .. }
+        | Inst::YieldUnit => (0, 1),
+        Inst::Not
+        | Inst::Await
+        | Inst::Yield
+        | Inst::IsUnit
+        | Inst::IsValue
+        | Inst::Unwrap
+        | Inst::LoadInstanceFn { .. }
+        | Inst::TupleIndexGet { .. }
+        | Inst::ObjectSlotIndexGet { .. }
+        | Inst::EqByte { .. }
+        | Inst::EqCharacter { .. }
+        | Inst::EqInteger { .. }
+        | Inst::EqStaticString { .. }
+        | Inst::MatchSequence { .. }
+        | Inst::MatchObject { .. } => (1, 1),
+        Inst::Add
+        | Inst::Sub
+        | Inst::Mul
+        | Inst::Div
+        | Inst::Rem
+        | Inst::BitAnd
+        | Inst::BitXor
+        | Inst::BitOr
+        | Inst::Shl
+        | Inst::Shr
+        | Inst::Lt
+        | Inst::Gt
+        | Inst::Lte
+        | Inst::Gte
+        | Inst::Eq
+        | Inst::Neq
+        | Inst::And
+        | Inst::Or
+        | Inst::Is
+        | Inst::IsNot
+        | Inst::IndexGet => (2, 1),
+        Inst::AddAssign { .. }
+        | Inst::SubAssign { .. }
+        | Inst::MulAssign { .. }
+        | Inst::DivAssign { .. }
+        | Inst::RemAssign { .. }
+        | Inst::BitAndAssign { .. }
+        | Inst::BitXorAssign { .. }
+        | Inst::BitOrAssign { .. }
+        | Inst::ShlAssign { .. }
+        | Inst::ShrAssign { .. }
+        | Inst::Replace { .. }
+        | Inst::Pop
+        | Inst::JumpIf { .. }
+        | Inst::JumpIfNot { .. }
+        | Inst::PopAndJumpIfNot { .. }
+        | Inst::PopAndJumpIfNotInteger { .. }
+        | Inst::Return => (1, 0),
+        Inst::TupleIndexSet { .. } | Inst::ObjectSlotIndexSet { .. } => (2, 0),
+        Inst::IndexSet => (3, 0),
+        Inst::Dup => (1, 2),
+        Inst::Drop { .. }
+        | Inst::Jump { .. }
+        | Inst::JumpIfBranch { .. }
+        | Inst::JumpIfNotLt { .. }
+        | Inst::ReturnUnit
+        | Inst::Panic { .. } => (0, 0),
+        Inst::PopN { count } => (count, 0),
+        Inst::Clean { count } => (count + 1, 1),
+        Inst::Call { args, .. } => (args, 1),
+        Inst::CallInstance { args, .. } | Inst::CallFn { args } => (args + 1, 1),
+        Inst::Closure { count, .. } => (count, 1),
+        Inst::Vec { count } | Inst::Tuple { count } => (count, 1),
+        Inst::StringConcat { len, .. } => (len, 1),
+        Inst::Object { slot }
+        | Inst::TypedObject { slot, .. }
+        | Inst::VariantObject { slot, .. } => {
+            let len = unit
+                .lookup_shape(slot)
+                .map(|shape| shape.len())
+                .unwrap_or_default();
+            (len, 1)
+        }
+        // NB: a select pushes a different number of values depending on if
+        // any of its futures are pending.
+        Inst::Select { len } => return (len, None),
+        // NB: the environment of a closure is pushed before it's unpacked,
+        // but isn't counted as one of its arguments.
+        Inst::PushTuple => return (0, None),
+    };
+
+    (pops, Some(pushes))
+}
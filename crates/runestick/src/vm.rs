@@ -1,10 +1,16 @@
 use crate::future::SelectFuture;
+use crate::inline_cache::{InlineCache, Target};
+use crate::numeric::{self, NumericOp};
+use crate::snapshot::{self, SnapshotError, VmSnapshot};
 use crate::unit::UnitFn;
 use crate::{
-    Args, Awaited, Bytes, Call, Context, FromValue, Function, Future, Generator, GuardedArgs, Hash,
-    Inst, IntoHash, Object, Panic, Select, Shared, Stack, Stream, Tuple, TypeCheck, TypedObject,
-    Unit, Value, VariantObject, VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr,
+    Args, Awaited, BigInt, Bytes, Call, Context, Coverage, Fields, FromValue, Function, Future,
+    Generator, GuardedArgs, Hash, Heap, Inst, IntoHash, Object, Panic, Select, Shape, Shared,
+    Stack, StaticString, Stream, Tuple, Type, TypeCheck, TypedObject, Unit, Value, VariantObject,
+    VmError, VmErrorKind, VmExecution, VmHalt, VmIntegerRepr,
 };
+use serde::{Deserialize, Serialize};
+use std::cmp;
 use std::fmt;
 use std::mem;
 use std::sync::Arc;
@@ -22,6 +28,12 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: Vec<CallFrame>,
+    /// Coverage being recorded, if enabled.
+    coverage: Option<Coverage>,
+    /// Heap tracking allocated values, if enabled.
+    heap: Option<Heap>,
+    /// Inline caches for lookups performed by instructions in the unit.
+    cache: InlineCache,
 }
 
 impl Vm {
@@ -38,9 +50,118 @@ impl Vm {
             ip: 0,
             stack,
             call_frames: Vec::new(),
+            coverage: None,
+            heap: None,
+            cache: InlineCache::new(),
         }
     }
 
+    /// Enable recording of line and branch coverage, returning a handle to
+    /// the coverage being recorded.
+    ///
+    /// Coverage is also recorded for any virtual machines constructed to
+    /// run functions in the same unit, like generators and async functions.
+    pub fn enable_coverage(&mut self) -> Coverage {
+        self.coverage.get_or_insert_with(Coverage::new).clone()
+    }
+
+    /// Get the coverage being recorded, if it's enabled.
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    /// Set the coverage being recorded.
+    pub(crate) fn set_coverage(&mut self, coverage: Option<Coverage>) {
+        self.coverage = coverage;
+    }
+
+    /// Set the heap used to track values allocated by the virtual machine, so
+    /// that reference cycles among them can be collected.
+    ///
+    /// The heap is also used by any virtual machines constructed to run
+    /// functions, like generators and async functions.
+    pub fn set_heap(&mut self, heap: Option<Heap>) {
+        self.heap = heap;
+    }
+
+    /// Get the heap used to track allocated values, if it's enabled.
+    pub fn heap(&self) -> Option<&Heap> {
+        self.heap.as_ref()
+    }
+
+    /// Construct a virtual machine from its raw parts.
+    pub(crate) fn from_parts(
+        context: Arc<Context>,
+        unit: Arc<Unit>,
+        ip: usize,
+        stack: Stack,
+        call_frames: Vec<CallFrame>,
+    ) -> Self {
+        Self {
+            context,
+            unit,
+            ip,
+            stack,
+            call_frames,
+            coverage: None,
+            heap: None,
+            cache: InlineCache::new(),
+        }
+    }
+
+    /// Replace the unit of the virtual machine, moving it to the given
+    /// instruction pointer.
+    ///
+    /// Recorded coverage and inline caches are discarded, since they refer to
+    /// the old unit.
+    pub(crate) fn set_unit(&mut self, unit: Arc<Unit>, ip: usize) {
+        self.unit = unit;
+        self.ip = ip;
+        self.coverage = None;
+        self.cache.clear();
+    }
+
+    /// Take a serializable snapshot of the virtual machine, which can later be
+    /// restored with [Vm::restore].
+    ///
+    /// This fails if the virtual machine holds on to values which can't be
+    /// snapshotted, like futures or native types.
+    pub fn snapshot(&self) -> Result<VmSnapshot, SnapshotError> {
+        snapshot::snapshot(self)
+    }
+
+    /// Restore a virtual machine from a snapshot.
+    ///
+    /// The unit must have the same [content hash][Unit::content_hash] as the
+    /// unit the snapshot was taken from, and the context must provide all
+    /// native functions referenced by it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Unit, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let context = Arc::new(Context::with_default_modules()?);
+    /// let unit = Arc::new(Unit::default());
+    ///
+    /// let vm = Vm::new(context.clone(), unit.clone());
+    /// let snapshot = vm.snapshot()?;
+    ///
+    /// let vm = Vm::restore(context, unit, &snapshot)?;
+    /// assert_eq!(vm.ip(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore(
+        context: Arc<Context>,
+        unit: Arc<Unit>,
+        snapshot: &VmSnapshot,
+    ) -> Result<Self, SnapshotError> {
+        snapshot::restore(context, unit, snapshot)
+    }
+
     /// Run the given vm to completion.
     ///
     /// If any async instructions are encountered, this will error.
@@ -309,30 +430,36 @@ impl Vm {
         A: Args,
     {
         let count = A::count() + 1;
-        let hash = Hash::instance_function(target.type_of()?, hash.into_hash());
+        let type_of = target.type_of()?;
+        let hash = hash.into_hash();
 
-        if let Some(UnitFn::Offset {
-            offset,
-            call,
-            args: expected,
-        }) = self.unit.lookup(hash)
-        {
-            Self::check_args(count, expected)?;
-            self.stack.push(target.clone());
-            args.into_stack(&mut self.stack)?;
-            self.call_offset_fn(offset, call, count)?;
-            return Ok(true);
-        }
+        let unit = &self.unit;
+        let context = &self.context;
 
-        let handler = match self.context.lookup(hash) {
-            Some(handler) => handler,
-            None => return Ok(false),
-        };
+        let target_fn = self.cache.get_or_resolve(self.ip, type_of, hash, || {
+            Self::resolve_instance_fn(unit, context, Hash::instance_function(type_of, hash))
+        });
 
-        self.stack.push(target.clone());
-        args.into_stack(&mut self.stack)?;
+        match target_fn {
+            Some(Target::Offset {
+                offset,
+                call,
+                args: expected,
+            }) => {
+                let (offset, call, expected) = (*offset, *call, *expected);
+                Self::check_args(count, expected)?;
+                self.stack.push(target.clone());
+                args.into_stack(&mut self.stack)?;
+                self.call_offset_fn(offset, call, count)?;
+            }
+            Some(Target::Handler(handler)) => {
+                self.stack.push(target.clone());
+                args.into_stack(&mut self.stack)?;
+                handler(&mut self.stack, count)?;
+            }
+            _ => return Ok(false),
+        }
 
-        handler(&mut self.stack, count)?;
         Ok(true)
     }
 
@@ -343,11 +470,17 @@ impl Vm {
         A: Args,
     {
         let count = A::count() + 1;
-        let hash = Hash::getter(target.type_of()?, hash.into_hash());
+        let type_of = target.type_of()?;
+        let hash = hash.into_hash();
 
-        let handler = match self.context.lookup(hash) {
-            Some(handler) => handler,
-            None => return Ok(false),
+        let context = &self.context;
+
+        let handler = match self.cache.get_or_resolve(self.ip, type_of, hash, || {
+            let handler = context.lookup(Hash::getter(type_of, hash))?;
+            Some(Target::Handler(handler.clone()))
+        }) {
+            Some(Target::Handler(handler)) => handler,
+            _ => return Ok(false),
         };
 
         args.into_stack(&mut self.stack)?;
@@ -374,6 +507,24 @@ impl Vm {
         Ok(())
     }
 
+    /// Pop the given number of entries and jump to the given offset unless the
+    /// top of the stack is the given integer.
+    #[inline]
+    fn op_pop_and_jump_if_not_integer(
+        &mut self,
+        integer: i64,
+        count: usize,
+        offset: isize,
+    ) -> Result<(), VmError> {
+        if crate::numeric::eq_integer(&self.stack.pop()?, integer) {
+            return Ok(());
+        }
+
+        self.stack.popn(count)?;
+        self.modify_ip(offset)?;
+        Ok(())
+    }
+
     /// Pop a number of values from the stack, while preserving the top of the
     /// stack.
     fn op_clean(&mut self, n: usize) -> Result<(), VmError> {
@@ -425,13 +576,18 @@ impl Vm {
         let out = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => int_op(lhs, rhs),
             (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
-            (lhs, rhs) => {
-                return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
-                    op,
-                    lhs: lhs.type_info()?,
-                    rhs: rhs.type_info()?,
-                }))
-            }
+            (lhs, rhs) => match numeric::cmp(&lhs, &rhs) {
+                // NB: comparing the ordering of two wide integers against zero
+                // gives the same result as comparing the integers themselves.
+                Some(ordering) => int_op(ordering as i64, 0),
+                None => {
+                    return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                        op,
+                        lhs: lhs.type_info()?,
+                        rhs: rhs.type_info()?,
+                    }))
+                }
+            },
         };
 
         self.stack.push(out);
@@ -514,6 +670,15 @@ impl Vm {
         Ok(())
     }
 
+    /// Record the outcome of the conditional jump at the given instruction
+    /// pointer if coverage is enabled.
+    #[inline]
+    fn record_branch(&self, ip: usize) {
+        if let Some(coverage) = &self.coverage {
+            coverage.branch(ip, self.ip != ip);
+        }
+    }
+
     /// Perform a conditional jump operation.
     #[inline]
     fn op_jump_if(&mut self, offset: isize) -> Result<(), VmError> {
@@ -534,6 +699,32 @@ impl Vm {
         Ok(())
     }
 
+    /// Perform a conditional jump operation unless the value at frame offset
+    /// `lhs` is less than the one at `rhs`.
+    #[inline]
+    fn op_jump_if_not_lt(&mut self, lhs: usize, rhs: usize, offset: isize) -> Result<(), VmError> {
+        let lt = match (self.stack.at_offset(lhs)?, self.stack.at_offset(rhs)?) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => lhs < rhs,
+            (Value::Float(lhs), Value::Float(rhs)) => lhs < rhs,
+            (lhs, rhs) => match numeric::cmp(lhs, rhs) {
+                Some(ordering) => ordering == cmp::Ordering::Less,
+                None => {
+                    return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                        op: "<",
+                        lhs: lhs.type_info()?,
+                        rhs: rhs.type_info()?,
+                    }))
+                }
+            },
+        };
+
+        if !lt {
+            self.modify_ip(offset)?;
+        }
+
+        Ok(())
+    }
+
     /// Perform a branch-conditional jump operation.
     #[inline]
     fn op_jump_if_branch(&mut self, branch: i64, offset: isize) -> Result<(), VmError> {
@@ -551,7 +742,7 @@ impl Vm {
     #[inline]
     fn op_vec(&mut self, count: usize) -> Result<(), VmError> {
         let vec = self.stack.pop_sequence(count)?;
-        self.stack.push(Shared::new(vec));
+        self.push_allocated(Value::from(Shared::new(vec)));
         Ok(())
     }
 
@@ -559,7 +750,7 @@ impl Vm {
     #[inline]
     fn op_tuple(&mut self, count: usize) -> Result<(), VmError> {
         let tuple = self.stack.pop_sequence(count)?;
-        self.stack.push(Tuple::from(tuple));
+        self.push_allocated(Value::from(Tuple::from(tuple)));
         Ok(())
     }
 
@@ -578,6 +769,9 @@ impl Vm {
         let value = match value {
             Value::Bool(value) => Value::from(!value),
             Value::Integer(value) => Value::from(!value),
+            Value::U64(value) => Value::from(!value),
+            Value::I128(value) => Value::from(!*value),
+            Value::U128(value) => Value::from(!*value),
             other => {
                 let operand = other.type_info()?;
                 return Err(VmError::from(VmErrorKind::UnsupportedUnaryOperation {
@@ -596,6 +790,7 @@ impl Vm {
         self.internal_num(
             crate::ADD,
             || VmError::from(VmErrorKind::Overflow),
+            NumericOp::Add,
             i64::checked_add,
             std::ops::Add::add,
             "+",
@@ -603,11 +798,34 @@ impl Vm {
         Ok(())
     }
 
+    /// Add a literal integer to the value at the given frame offset.
+    ///
+    /// Values which aren't integers are added through the regular path, so
+    /// that they can implement the add protocol.
+    #[inline]
+    fn op_add_immediate(&mut self, offset: usize, number: i64) -> Result<(), VmError> {
+        let value = self.stack.at_offset(offset)?;
+
+        if let Value::Integer(value) = *value {
+            let value = value
+                .checked_add(number)
+                .ok_or_else(|| VmError::from(VmErrorKind::Overflow))?;
+            self.stack.push(Value::Integer(value));
+            return Ok(());
+        }
+
+        let value = value.clone();
+        self.stack.push(value);
+        self.stack.push(Value::Integer(number));
+        self.op_add()
+    }
+
     #[inline]
     fn op_sub(&mut self) -> Result<(), VmError> {
         self.internal_num(
             crate::SUB,
             || VmError::from(VmErrorKind::Underflow),
+            NumericOp::Sub,
             i64::checked_sub,
             std::ops::Sub::sub,
             "-",
@@ -618,8 +836,9 @@ impl Vm {
     #[inline]
     fn op_mul(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
+            crate::MUL,
             || VmError::from(VmErrorKind::Overflow),
+            NumericOp::Mul,
             i64::checked_mul,
             std::ops::Mul::mul,
             "*",
@@ -630,11 +849,12 @@ impl Vm {
     #[inline]
     fn op_div(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
+            crate::DIV,
             || VmError::from(VmErrorKind::DivideByZero),
+            NumericOp::Div,
             i64::checked_div,
             std::ops::Div::div,
-            "+",
+            "/",
         )?;
         Ok(())
     }
@@ -644,6 +864,7 @@ impl Vm {
         self.internal_num(
             crate::REM,
             || VmError::from(VmErrorKind::DivideByZero),
+            NumericOp::Rem,
             i64::checked_rem,
             std::ops::Rem::rem,
             "%",
@@ -754,6 +975,7 @@ impl Vm {
             offset,
             crate::ADD_ASSIGN,
             || VmError::from(VmErrorKind::Overflow),
+            NumericOp::Add,
             i64::checked_add,
             std::ops::Add::add,
             "+=",
@@ -767,6 +989,7 @@ impl Vm {
             offset,
             crate::SUB_ASSIGN,
             || VmError::from(VmErrorKind::Underflow),
+            NumericOp::Sub,
             i64::checked_sub,
             std::ops::Sub::sub,
             "-=",
@@ -780,6 +1003,7 @@ impl Vm {
             offset,
             crate::MUL_ASSIGN,
             || VmError::from(VmErrorKind::Overflow),
+            NumericOp::Mul,
             i64::checked_mul,
             std::ops::Mul::mul,
             "*=",
@@ -793,6 +1017,7 @@ impl Vm {
             offset,
             crate::DIV_ASSIGN,
             || VmError::from(VmErrorKind::DivideByZero),
+            NumericOp::Div,
             i64::checked_div,
             std::ops::Div::div,
             "/=",
@@ -806,6 +1031,7 @@ impl Vm {
             offset,
             crate::REM_ASSIGN,
             || VmError::from(VmErrorKind::DivideByZero),
+            NumericOp::Rem,
             i64::checked_rem,
             std::ops::Rem::rem,
             "%=",
@@ -844,7 +1070,7 @@ impl Vm {
                 Value::TypedObject(typed_object) => {
                     let mut typed_object = typed_object.borrow_mut()?;
 
-                    if let Some(v) = typed_object.object.get_mut(field) {
+                    if let Some(v) = typed_object.fields.get_mut(field) {
                         *v = value;
                         return Ok(());
                     }
@@ -857,7 +1083,7 @@ impl Vm {
                 Value::VariantObject(variant_object) => {
                     let mut variant_object = variant_object.borrow_mut()?;
 
-                    if let Some(v) = variant_object.object.get_mut(field) {
+                    if let Some(v) = variant_object.fields.get_mut(field) {
                         *v = value;
                         return Ok(());
                     }
@@ -931,8 +1157,8 @@ impl Vm {
     fn try_object_like_index_get(&mut self, target: &Value, field: &str) -> Result<bool, VmError> {
         let value = match &target {
             Value::Object(target) => target.borrow_ref()?.get(field).cloned(),
-            Value::TypedObject(target) => target.borrow_ref()?.object.get(field).cloned(),
-            Value::VariantObject(target) => target.borrow_ref()?.object.get(field).cloned(),
+            Value::TypedObject(target) => target.borrow_ref()?.fields.get(field).cloned(),
+            Value::VariantObject(target) => target.borrow_ref()?.fields.get(field).cloned(),
             _ => return Ok(false),
         };
 
@@ -1181,6 +1407,34 @@ impl Vm {
         }))
     }
 
+    /// Get the given field out of the fields of a struct, using the inline
+    /// cache of the current instruction to find its slot.
+    #[inline]
+    fn struct_field<'a>(
+        cache: &mut InlineCache,
+        ip: usize,
+        type_of: Type,
+        fields: &'a Fields,
+        field: &StaticString,
+    ) -> Option<&'a Value> {
+        let index = cache.field_index(ip, type_of, field.hash(), fields.shape(), field)?;
+        fields.get_index(index)
+    }
+
+    /// Get the given field out of the fields of a struct mutably, using the
+    /// inline cache of the current instruction to find its slot.
+    #[inline]
+    fn struct_field_mut<'a>(
+        cache: &mut InlineCache,
+        ip: usize,
+        type_of: Type,
+        fields: &'a mut Fields,
+        field: &StaticString,
+    ) -> Option<&'a mut Value> {
+        let index = cache.field_index(ip, type_of, field.hash(), fields.shape(), field)?;
+        fields.get_index_mut(index)
+    }
+
     /// Implementation of getting a string index on an object-like type.
     fn try_object_slot_index_get(
         &mut self,
@@ -1204,9 +1458,16 @@ impl Vm {
             }
             Value::TypedObject(typed_object) => {
                 let typed_object = typed_object.borrow_ref()?;
-
-                match typed_object.get(&***index).cloned() {
-                    Some(value) => Some(value),
+                let type_of = Type::from(typed_object.type_hash());
+
+                match Self::struct_field(
+                    &mut self.cache,
+                    self.ip,
+                    type_of,
+                    &typed_object.fields,
+                    index,
+                ) {
+                    Some(value) => Some(value.clone()),
                     None => {
                         return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
                             slot: string_slot,
@@ -1216,9 +1477,16 @@ impl Vm {
             }
             Value::VariantObject(variant_object) => {
                 let variant_object = variant_object.borrow_ref()?;
-
-                match variant_object.object.get(&***index).cloned() {
-                    Some(value) => Some(value),
+                let type_of = Type::from(variant_object.hash);
+
+                match Self::struct_field(
+                    &mut self.cache,
+                    self.ip,
+                    type_of,
+                    &variant_object.fields,
+                    index,
+                ) {
+                    Some(value) => Some(value.clone()),
                     None => {
                         return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
                             slot: string_slot,
@@ -1274,42 +1542,147 @@ impl Vm {
         }))
     }
 
-    /// Operation to allocate an object.
+    /// Perform a specialized index set operation on an object.
     #[inline]
-    fn op_object(&mut self, slot: usize) -> Result<(), VmError> {
-        let keys = self
-            .unit
-            .lookup_object_keys(slot)
-            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
+    fn op_object_slot_index_set(&mut self, string_slot: usize) -> Result<(), VmError> {
+        let target = self.stack.pop()?;
+        let value = self.stack.pop()?;
+        let field = self.unit.lookup_string(string_slot)?.clone();
 
-        let mut object = Object::with_capacity(keys.len());
-        let values = self.stack.drain_stack_top(keys.len())?;
+        match &target {
+            Value::Object(object) => {
+                let mut object = object.borrow_mut()?;
+                object.insert(field.to_string(), value);
+                return Ok(());
+            }
+            Value::TypedObject(typed_object) => {
+                let mut typed_object = typed_object.borrow_mut()?;
+                let type_of = Type::from(typed_object.type_hash());
+
+                if let Some(v) = Self::struct_field_mut(
+                    &mut self.cache,
+                    self.ip,
+                    type_of,
+                    &mut typed_object.fields,
+                    &field,
+                ) {
+                    *v = value;
+                    return Ok(());
+                }
 
-        for (key, value) in keys.iter().zip(values) {
-            object.insert(key.clone(), value);
+                return Err(VmError::from(VmErrorKind::MissingField {
+                    field: field.to_string(),
+                    target: typed_object.type_info(),
+                }));
+            }
+            Value::VariantObject(variant_object) => {
+                let mut variant_object = variant_object.borrow_mut()?;
+                let type_of = Type::from(variant_object.hash);
+
+                if let Some(v) = Self::struct_field_mut(
+                    &mut self.cache,
+                    self.ip,
+                    type_of,
+                    &mut variant_object.fields,
+                    &field,
+                ) {
+                    *v = value;
+                    return Ok(());
+                }
+
+                return Err(VmError::from(VmErrorKind::MissingField {
+                    field: field.to_string(),
+                    target: variant_object.type_info(),
+                }));
+            }
+            _ => (),
+        }
+
+        let index = Value::StaticString(field);
+
+        if !self.call_instance_fn(&target, crate::INDEX_SET, (&index, &value))? {
+            return Err(VmError::from(VmErrorKind::UnsupportedIndexSet {
+                target: target.type_info()?,
+                index: index.type_info()?,
+                value: value.type_info()?,
+            }));
         }
 
-        self.stack.push(Shared::new(object));
         Ok(())
     }
 
+    /// Get a field by its slot index out of a struct with a known shape from
+    /// the given variable slot.
+    ///
+    /// Structs with a different shape, like ones constructed outside of the
+    /// unit, fall back to looking up the field by name.
+    #[inline]
+    fn op_shape_index_get_at(
+        &mut self,
+        offset: usize,
+        slot: usize,
+        index: usize,
+    ) -> Result<(), VmError> {
+        let target = self.stack.at_offset(offset)?.clone();
+
+        let shape = self
+            .unit
+            .lookup_shape(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
+
+        let value = match &target {
+            Value::TypedObject(typed_object) => {
+                shape_field(shape, &typed_object.borrow_ref()?.fields, index)
+            }
+            Value::VariantObject(variant_object) => {
+                shape_field(shape, &variant_object.borrow_ref()?.fields, index)
+            }
+            _ => None,
+        };
+
+        return match value {
+            Some(value) => {
+                self.stack.push(value);
+                Ok(())
+            }
+            None => Err(VmError::from(VmErrorKind::UnsupportedObjectSlotIndexGet {
+                target: target.type_info()?,
+            })),
+        };
+
+        fn shape_field(shape: &Arc<Shape>, fields: &Fields, index: usize) -> Option<Value> {
+            if Arc::ptr_eq(fields.shape(), shape) {
+                return fields.get_index(index).cloned();
+            }
+
+            fields.get(shape.keys().get(index)?).cloned()
+        }
+    }
+
     /// Operation to allocate an object.
     #[inline]
-    fn op_typed_object(&mut self, hash: Hash, slot: usize) -> Result<(), VmError> {
+    fn op_object(&mut self, slot: usize) -> Result<(), VmError> {
         let keys = self
             .unit
             .lookup_object_keys(slot)
             .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
 
         let mut object = Object::with_capacity(keys.len());
-
         let values = self.stack.drain_stack_top(keys.len())?;
 
         for (key, value) in keys.iter().zip(values) {
             object.insert(key.clone(), value);
         }
 
-        self.stack.push(TypedObject::new(hash, object));
+        self.push_allocated(Value::from(Shared::new(object)));
+        Ok(())
+    }
+
+    /// Operation to allocate an object.
+    #[inline]
+    fn op_typed_object(&mut self, hash: Hash, slot: usize) -> Result<(), VmError> {
+        let fields = self.struct_fields(slot)?;
+        self.push_allocated(Value::from(TypedObject::with_fields(hash, fields)));
         Ok(())
     }
 
@@ -1321,27 +1694,30 @@ impl Vm {
         hash: Hash,
         slot: usize,
     ) -> Result<(), VmError> {
-        let keys = self
-            .unit
-            .lookup_object_keys(slot)
-            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
+        let fields = self.struct_fields(slot)?;
 
-        let mut object = Object::with_capacity(keys.len());
-        let values = self.stack.drain_stack_top(keys.len())?;
-
-        for (key, value) in keys.iter().zip(values) {
-            object.insert(key.clone(), value);
-        }
-
-        self.stack.push(VariantObject {
+        self.push_allocated(Value::from(VariantObject {
             enum_hash,
             hash,
-            object,
-        });
+            fields,
+        }));
 
         Ok(())
     }
 
+    /// Pop the fields of a struct with the shape in the given slot off the
+    /// stack.
+    #[inline]
+    fn struct_fields(&mut self, slot: usize) -> Result<Fields, VmError> {
+        let shape = self
+            .unit
+            .lookup_shape(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
+
+        let values = self.stack.drain_stack_top(shape.len())?.collect();
+        Ok(Fields::new(shape.clone(), values))
+    }
+
     #[inline]
     fn op_string(&mut self, slot: usize) -> Result<(), VmError> {
         let string = self.unit.lookup_string(slot)?;
@@ -1349,6 +1725,13 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_big_int(&mut self, slot: usize) -> Result<(), VmError> {
+        let bytes = self.unit.lookup_bytes(slot)?;
+        self.stack.push(BigInt::from_signed_bytes_le(bytes));
+        Ok(())
+    }
+
     #[inline]
     fn op_bytes(&mut self, slot: usize) -> Result<(), VmError> {
         let bytes = self.unit.lookup_bytes(slot)?.to_owned();
@@ -1359,6 +1742,8 @@ impl Vm {
     /// Optimize operation to perform string concatenation.
     #[inline]
     fn op_string_concat(&mut self, len: usize, size_hint: usize) -> Result<(), VmError> {
+        use std::fmt::Write as _;
+
         let mut buf = String::with_capacity(size_hint);
         let values = self.stack.drain_stack_top(len)?.collect::<Vec<_>>();
 
@@ -1378,6 +1763,19 @@ impl Vm {
                     let mut buffer = ryu::Buffer::new();
                     buf.push_str(buffer.format(float));
                 }
+                Value::U64(integer) => {
+                    let mut buffer = itoa::Buffer::new();
+                    buf.push_str(buffer.format(integer));
+                }
+                Value::I128(integer) => {
+                    write!(buf, "{}", integer).map_err(|_| VmErrorKind::FormatError)?;
+                }
+                Value::U128(integer) => {
+                    write!(buf, "{}", integer).map_err(|_| VmErrorKind::FormatError)?;
+                }
+                Value::BigInt(integer) => {
+                    write!(buf, "{}", integer).map_err(|_| VmErrorKind::FormatError)?;
+                }
                 actual => {
                     let b = Shared::new(std::mem::take(&mut buf));
 
@@ -1559,12 +1957,7 @@ impl Vm {
     #[inline]
     fn op_eq_integer(&mut self, integer: i64) -> Result<(), VmError> {
         let value = self.stack.pop()?;
-
-        self.stack.push(match value {
-            Value::Integer(actual) => actual == integer,
-            _ => false,
-        });
-
+        self.stack.push(crate::numeric::eq_integer(&value, integer));
         Ok(())
     }
 
@@ -1615,19 +2008,19 @@ impl Vm {
         slot: usize,
         exact: bool,
     ) -> Result<(), VmError> {
-        let result = self.on_object_keys(type_check, slot, |object, keys| {
+        let result = self.on_object_keys(type_check, slot, |len, contains_key, keys| {
             if exact {
-                if object.len() != keys.len() {
+                if len != keys.len() {
                     return false;
                 }
-            } else if object.len() < keys.len() {
+            } else if len < keys.len() {
                 return false;
             }
 
             let mut is_match = true;
 
             for key in keys {
-                if !object.contains_key(key) {
+                if !contains_key(key) {
                     is_match = false;
                     break;
                 }
@@ -1709,7 +2102,7 @@ impl Vm {
         f: F,
     ) -> Result<Option<O>, VmError>
     where
-        F: FnOnce(&Object, &[String]) -> O,
+        F: FnOnce(usize, &dyn Fn(&str) -> bool, &[String]) -> O,
     {
         let value = self.stack.pop()?;
 
@@ -1721,20 +2114,25 @@ impl Vm {
         match (type_check, value) {
             (TypeCheck::Object, Value::Object(object)) => {
                 let object = object.borrow_ref()?;
-                return Ok(Some(f(&*object, keys)));
+                let contains_key = |key: &str| object.contains_key(key);
+                return Ok(Some(f(object.len(), &contains_key, keys)));
             }
             (TypeCheck::Type(hash), Value::TypedObject(typed_object)) => {
                 let typed_object = typed_object.borrow_ref()?;
 
                 if typed_object.type_hash() == hash {
-                    return Ok(Some(f(&typed_object.object, keys)));
+                    let fields = &typed_object.fields;
+                    let contains_key = |key: &str| fields.contains_key(key);
+                    return Ok(Some(f(fields.len(), &contains_key, keys)));
                 }
             }
             (TypeCheck::Variant(hash), Value::VariantObject(variant_object)) => {
                 let variant_object = variant_object.borrow_ref()?;
 
                 if variant_object.hash == hash {
-                    return Ok(Some(f(&variant_object.object, keys)));
+                    let fields = &variant_object.fields;
+                    let contains_key = |key: &str| fields.contains_key(key);
+                    return Ok(Some(f(fields.len(), &contains_key, keys)));
                 }
             }
             _ => (),
@@ -1743,11 +2141,24 @@ impl Vm {
         Ok(None)
     }
 
+    /// Push a newly allocated value onto the stack, tracking it in the heap if
+    /// one is used.
+    #[inline]
+    fn push_allocated(&mut self, value: Value) {
+        if let Some(heap) = &self.heap {
+            heap.track(&value);
+        }
+
+        self.stack.push(value);
+    }
+
     /// Construct a future from calling an async function.
     fn call_generator_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
         let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
+        vm.coverage = self.coverage.clone();
+        vm.heap = self.heap.clone();
         self.stack.push(Generator::new(vm));
         Ok(())
     }
@@ -1757,6 +2168,8 @@ impl Vm {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
         let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
+        vm.coverage = self.coverage.clone();
+        vm.heap = self.heap.clone();
         self.stack.push(Stream::new(vm));
         Ok(())
     }
@@ -1766,6 +2179,8 @@ impl Vm {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
         let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
         vm.ip = offset;
+        vm.coverage = self.coverage.clone();
+        vm.heap = self.heap.clone();
         self.stack.push(Future::new(vm.async_complete()));
         Ok(())
     }
@@ -1812,7 +2227,7 @@ impl Vm {
                     .lookup(hash)
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-                Function::from_handler(handler.clone())
+                Function::from_handler(hash, handler.clone())
             }
         };
 
@@ -1844,7 +2259,7 @@ impl Vm {
             environment,
         );
 
-        self.stack.push(Value::Function(Shared::new(function)));
+        self.push_allocated(Value::Function(Shared::new(function)));
         Ok(())
     }
 
@@ -1867,7 +2282,7 @@ impl Vm {
                     Self::check_args(args, expected)?;
                     let tuple = self.stack.pop_sequence(args)?;
                     let value = Value::typed_tuple(hash, tuple);
-                    self.stack.push(value);
+                    self.push_allocated(value);
                 }
                 UnitFn::TupleVariant {
                     enum_hash,
@@ -1877,7 +2292,7 @@ impl Vm {
                     Self::check_args(args, expected)?;
                     let tuple = self.stack.pop_sequence(args)?;
                     let value = Value::variant_tuple(enum_hash, hash, tuple);
-                    self.stack.push(value);
+                    self.push_allocated(value);
                 }
             },
             None => {
@@ -1894,51 +2309,56 @@ impl Vm {
     }
 
     #[inline]
-    fn op_call_instance<H>(&mut self, hash: H, args: usize) -> Result<(), VmError>
-    where
-        H: IntoHash,
-    {
+    fn op_call_instance(&mut self, hash: Hash, args: usize) -> Result<(), VmError> {
         // NB: +1 to include the instance itself.
         let args = args + 1;
-        let instance = self.stack.at_offset_from_top(args)?;
-        let type_of = instance.type_of()?;
-        let hash = Hash::instance_function(type_of, hash);
+        let type_of = self.stack.at_offset_from_top(args)?.type_of()?;
 
-        match self.unit.lookup(hash) {
-            Some(info) => match info {
-                UnitFn::Offset {
-                    offset,
-                    call,
-                    args: expected,
-                } => {
-                    Self::check_args(args, expected)?;
-                    self.call_offset_fn(offset, call, args)?;
-                }
-                _ => {
-                    return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
-                        instance: instance.type_info()?,
-                        hash,
-                    }));
-                }
-            },
-            None => {
-                let handler = match self.context.lookup(hash) {
-                    Some(handler) => handler,
-                    None => {
-                        return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
-                            instance: instance.type_info()?,
-                            hash,
-                        }));
-                    }
-                };
+        let unit = &self.unit;
+        let context = &self.context;
 
+        let target = self.cache.get_or_resolve(self.ip, type_of, hash, || {
+            Self::resolve_instance_fn(unit, context, Hash::instance_function(type_of, hash))
+        });
+
+        match target {
+            Some(Target::Offset {
+                offset,
+                call,
+                args: expected,
+            }) => {
+                let (offset, call, expected) = (*offset, *call, *expected);
+                Self::check_args(args, expected)?;
+                self.call_offset_fn(offset, call, args)?;
+            }
+            Some(Target::Handler(handler)) => {
                 handler(&mut self.stack, args)?;
             }
+            _ => {
+                let instance = self.stack.at_offset_from_top(args)?;
+
+                return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
+                    instance: instance.type_info()?,
+                    hash: Hash::instance_function(type_of, hash),
+                }));
+            }
         }
 
         Ok(())
     }
 
+    /// Resolve the instance function with the given hash, preferring
+    /// functions in the unit over native functions in the context.
+    fn resolve_instance_fn(unit: &Unit, context: &Context, hash: Hash) -> Option<Target> {
+        match unit.lookup(hash) {
+            Some(UnitFn::Offset { offset, call, args }) => {
+                Some(Target::Offset { offset, call, args })
+            }
+            Some(..) => None,
+            None => Some(Target::Handler(context.lookup(hash)?.clone())),
+        }
+    }
+
     fn op_call_fn(&mut self, args: usize) -> Result<Option<VmHalt>, VmError> {
         let function = self.stack.pop()?;
 
@@ -1975,6 +2395,10 @@ impl Vm {
 
             log::trace!("{}: {}", self.ip, inst);
 
+            if let Some(coverage) = &self.coverage {
+                coverage.hit(self.ip);
+            }
+
             match inst {
                 Inst::Not => {
                     self.op_not()?;
@@ -1985,6 +2409,9 @@ impl Vm {
                 Inst::AddAssign { offset } => {
                     self.op_add_assign(offset)?;
                 }
+                Inst::AddImmediate { offset, number } => {
+                    self.op_add_immediate(offset, number)?;
+                }
                 Inst::Sub => {
                     self.op_sub()?;
                 }
@@ -2047,6 +2474,16 @@ impl Vm {
                 Inst::ObjectSlotIndexGetAt { offset, slot } => {
                     self.op_object_slot_index_get_at(offset, slot)?;
                 }
+                Inst::ObjectSlotIndexSet { slot } => {
+                    self.op_object_slot_index_set(slot)?;
+                }
+                Inst::ShapeIndexGetAt {
+                    offset,
+                    slot,
+                    index,
+                } => {
+                    self.op_shape_index_get_at(offset, slot, index)?;
+                }
                 Inst::IndexSet => {
                     self.op_index_set()?;
                 }
@@ -2080,7 +2517,9 @@ impl Vm {
                     self.op_popn(count)?;
                 }
                 Inst::PopAndJumpIfNot { count, offset } => {
+                    let ip = self.ip;
                     self.op_pop_and_jump_if_not(count, offset)?;
+                    self.record_branch(ip);
                 }
                 Inst::Clean { count } => {
                     self.op_clean(count)?;
@@ -2088,6 +2527,18 @@ impl Vm {
                 Inst::Integer { number } => {
                     self.stack.push(Value::Integer(number));
                 }
+                Inst::U64 { number } => {
+                    self.stack.push(Value::U64(number));
+                }
+                Inst::I128 { number } => {
+                    self.stack.push(Value::from(number));
+                }
+                Inst::U128 { number } => {
+                    self.stack.push(Value::from(number));
+                }
+                Inst::BigInt { slot } => {
+                    self.op_big_int(slot)?;
+                }
                 Inst::Float { number } => {
                     self.stack.push(Value::Float(number));
                 }
@@ -2125,14 +2576,23 @@ impl Vm {
                     self.op_jump(offset)?;
                 }
                 Inst::JumpIf { offset } => {
+                    let ip = self.ip;
                     self.op_jump_if(offset)?;
+                    self.record_branch(ip);
                 }
                 Inst::JumpIfNot { offset } => {
+                    let ip = self.ip;
                     self.op_jump_if_not(offset)?;
+                    self.record_branch(ip);
                 }
                 Inst::JumpIfBranch { branch, offset } => {
                     self.op_jump_if_branch(branch, offset)?;
                 }
+                Inst::JumpIfNotLt { lhs, rhs, offset } => {
+                    let ip = self.ip;
+                    self.op_jump_if_not_lt(lhs, rhs, offset)?;
+                    self.record_branch(ip);
+                }
                 Inst::Unit => {
                     self.stack.push(Value::Unit);
                 }
@@ -2239,6 +2699,15 @@ impl Vm {
                 Inst::EqInteger { integer } => {
                     self.op_eq_integer(integer)?;
                 }
+                Inst::PopAndJumpIfNotInteger {
+                    integer,
+                    count,
+                    offset,
+                } => {
+                    let ip = self.ip;
+                    self.op_pop_and_jump_if_not_integer(integer, count, offset)?;
+                    self.record_branch(ip);
+                }
                 Inst::EqStaticString { slot } => {
                     self.op_eq_static_string(slot)?;
                 }
@@ -2284,11 +2753,13 @@ impl Vm {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn internal_num_assign<H, E, I, F>(
         &mut self,
         offset: usize,
         hash: H,
         error: E,
+        numeric_op: NumericOp,
         integer_op: I,
         float_op: F,
         op: &'static str,
@@ -2313,7 +2784,14 @@ impl Vm {
                 *lhs = out;
                 return Ok(());
             }
-            (lhs, rhs) => (lhs.clone(), rhs),
+            (lhs, rhs) => {
+                if let Some(out) = numeric::checked_op(numeric_op, lhs, &rhs, error)? {
+                    *lhs = out;
+                    return Ok(());
+                }
+
+                (lhs.clone(), rhs)
+            }
         };
 
         if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
@@ -2333,6 +2811,7 @@ impl Vm {
         &mut self,
         hash: H,
         error: E,
+        numeric_op: NumericOp,
         integer_op: I,
         float_op: F,
         op: &'static str,
@@ -2355,7 +2834,14 @@ impl Vm {
                 self.stack.push(float_op(lhs, rhs));
                 return Ok(());
             }
-            (lhs, rhs) => (lhs, rhs),
+            (lhs, rhs) => {
+                if let Some(out) = numeric::checked_op(numeric_op, &lhs, &rhs, error)? {
+                    self.stack.push(out);
+                    return Ok(());
+                }
+
+                (lhs, rhs)
+            }
         };
 
         if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
@@ -2522,7 +3008,7 @@ impl Vm {
 /// A call frame.
 ///
 /// This is used to store the return point after an instruction has been run.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CallFrame {
     /// The stored instruction pointer.
     ip: usize,
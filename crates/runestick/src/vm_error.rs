@@ -1,6 +1,7 @@
 use crate::panic::BoxedPanic;
 use crate::{
-    AccessError, Hash, Item, Panic, Protocol, StackError, TypeInfo, TypeOf, Unit, Value, VmHaltInfo,
+    AccessError, Hash, Item, Panic, Protocol, Span, StackError, TypeInfo, TypeOf, Unit, Value, Vm,
+    VmHaltInfo,
 };
 use std::fmt;
 use std::sync::Arc;
@@ -67,13 +68,44 @@ impl VmError {
             kind: self.kind,
             unit: unit.clone(),
             ip,
+            backtrace: vec![BacktraceFrame {
+                unit: unit.clone(),
+                ip,
+            }],
+        })
+    }
+
+    /// Unwind the error through the given virtual machine, adding all of its
+    /// frames to the backtrace of the error.
+    ///
+    /// If the error has already been unwound, the frames are added after the
+    /// ones already in the backtrace since they belong to callers of the
+    /// virtual machine where the error happened.
+    pub(crate) fn unwind(mut self, vm: &Vm) -> Self {
+        let frames = std::iter::once(vm.ip())
+            .chain(vm.call_frames().iter().rev().map(|frame| frame.ip()))
+            .map(|ip| BacktraceFrame {
+                unit: vm.unit().clone(),
+                ip,
+            });
+
+        if let VmErrorKind::Unwound { backtrace, .. } = &mut *self.kind {
+            backtrace.extend(frames);
+            return self;
+        }
+
+        Self::from(VmErrorKind::Unwound {
+            kind: self.kind,
+            unit: vm.unit().clone(),
+            ip: vm.ip(),
+            backtrace: frames.collect(),
         })
     }
 
     /// Unpack an unwinded error, if it is present.
     pub fn into_unwound(self) -> (Self, Option<(Arc<Unit>, usize)>) {
         match *self.kind {
-            VmErrorKind::Unwound { kind, unit, ip } => {
+            VmErrorKind::Unwound { kind, unit, ip, .. } => {
                 let error = Self { kind };
                 (error, Some((unit, ip)))
             }
@@ -81,6 +113,30 @@ impl VmError {
         }
     }
 
+    /// Unpack an unwinded error together with its backtrace, where the first
+    /// frame is the one where the error happened.
+    ///
+    /// The backtrace is empty if the error hasn't been unwound.
+    pub fn into_backtrace(self) -> (Self, Vec<BacktraceFrame>) {
+        match *self.kind {
+            VmErrorKind::Unwound {
+                kind, backtrace, ..
+            } => (Self { kind }, backtrace),
+            kind => (Self::from(kind), Vec::new()),
+        }
+    }
+
+    /// Get the backtrace of the error, where the first frame is the one where
+    /// the error happened.
+    ///
+    /// The backtrace is empty if the error hasn't been unwound.
+    pub fn backtrace(&self) -> &[BacktraceFrame] {
+        match &*self.kind {
+            VmErrorKind::Unwound { backtrace, .. } => backtrace,
+            _ => &[],
+        }
+    }
+
     /// Unsmuggles the vm error, returning Ok(Self) in case the error is
     /// critical and should be propagated unaltered.
     pub fn unpack_critical(self) -> Result<Self, Self> {
@@ -114,6 +170,34 @@ where
     }
 }
 
+/// A single frame in the backtrace of a [VmError].
+#[derive(Debug, Clone)]
+pub struct BacktraceFrame {
+    /// The unit the frame was executing.
+    pub unit: Arc<Unit>,
+    /// The instruction pointer of the frame. For frames other than the one
+    /// where the error happened this is the instruction which performed the
+    /// call.
+    pub ip: usize,
+}
+
+impl BacktraceFrame {
+    /// Get the item of the function the frame was executing, if debug info
+    /// is available.
+    pub fn item(&self) -> Option<&Item> {
+        let debug = self.unit.debug_info()?;
+        let (_, signature) = debug.function_containing(self.ip)?;
+        Some(&signature.path)
+    }
+
+    /// Get the source id and span of the instruction the frame was executing,
+    /// if debug info is available.
+    pub fn span(&self) -> Option<(usize, Span)> {
+        let inst = self.unit.debug_info()?.instruction_at(self.ip)?;
+        Some((inst.source_id, inst.span))
+    }
+}
+
 /// The kind of error encountered.
 #[derive(Debug, Error)]
 pub enum VmErrorKind {
@@ -129,6 +213,8 @@ pub enum VmErrorKind {
         unit: Arc<Unit>,
         /// The instruction pointer of where the original error happened.
         ip: usize,
+        /// The frames the error was unwound through.
+        backtrace: Vec<BacktraceFrame>,
     },
     /// The virtual machine panicked for a specific reason.
     #[error("panicked `{reason}`")]
@@ -315,6 +401,51 @@ pub enum VmErrorKind {
         /// The type that is not supported.
         test_type: TypeInfo,
     },
+    /// Tried to construct a weak reference to a value which isn't shared by
+    /// reference.
+    #[error("cannot construct a weak reference to `{actual}`")]
+    UnsupportedWeak {
+        /// The actual value.
+        actual: TypeInfo,
+    },
+    /// Tried to use a value which can't be hashed or compared by content as a
+    /// key.
+    #[error("`{actual}` can't be used as a key")]
+    UnsupportedKey {
+        /// The actual value.
+        actual: TypeInfo,
+    },
+    /// Tried to use a float which is NaN as a key.
+    #[error("NaN can't be used as a key")]
+    NanKey,
+    /// Tried to construct an iterator from a value which can't be iterated
+    /// over natively.
+    #[error("`{actual}` cannot be converted into an iterator")]
+    UnsupportedIterator {
+        /// The actual value.
+        actual: TypeInfo,
+    },
+    /// Tried to iterate from the back of an iterator which isn't
+    /// double-ended.
+    #[error("iterator is not double-ended")]
+    NotDoubleEnded,
+    /// Tried to order two values which have no well-defined ordering
+    /// relative to each other.
+    #[error("`{lhs}` and `{rhs}` can't be compared")]
+    UnsupportedOrdering {
+        /// The left-hand side value.
+        lhs: TypeInfo,
+        /// The right-hand side value.
+        rhs: TypeInfo,
+    },
+    /// Tried to access an index which is out of bounds.
+    #[error("index `{index}` is out of bounds for length `{len}`")]
+    IndexOutOfBounds {
+        /// The index that was accessed.
+        index: usize,
+        /// The length of the collection.
+        len: usize,
+    },
     /// Encountered a value that could not be called as a function
     #[error("`{actual_type}` cannot be called since it's not a function")]
     UnsupportedCallFn {
@@ -438,14 +569,14 @@ impl VmErrorKind {
     /// Unpack an unwound error, if it is present.
     pub fn as_unwound_ref(&self) -> (&Self, Option<(Arc<Unit>, usize)>) {
         match self {
-            VmErrorKind::Unwound { kind, unit, ip } => (&*kind, Some((unit.clone(), *ip))),
+            VmErrorKind::Unwound { kind, unit, ip, .. } => (&*kind, Some((unit.clone(), *ip))),
             kind => (kind, None),
         }
     }
 }
 
 /// A type-erased rust number.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum VmIntegerRepr {
     /// `u8`
     U8(u8),
@@ -471,11 +602,13 @@ pub enum VmIntegerRepr {
     Isize(isize),
     /// `usize`
     Usize(usize),
+    /// An arbitrary precision integer.
+    BigInt(crate::BigInt),
 }
 
 impl fmt::Display for VmIntegerRepr {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
+        match self {
             Self::U8(n) => write!(fmt, "{}u8", n),
             Self::U16(n) => write!(fmt, "{}u16", n),
             Self::U32(n) => write!(fmt, "{}u32", n),
@@ -488,6 +621,7 @@ impl fmt::Display for VmIntegerRepr {
             Self::I128(n) => write!(fmt, "{}i128", n),
             Self::Isize(n) => write!(fmt, "{}isize", n),
             Self::Usize(n) => write!(fmt, "{}usize", n),
+            Self::BigInt(n) => write!(fmt, "{}n", n),
         }
     }
 }
@@ -7,10 +7,21 @@ pub struct VmExecution {
 
 impl VmExecution {
     /// Construct an execution from a virtual machine.
-    pub(crate) fn new(vm: Vm) -> Self {
+    pub fn new(vm: Vm) -> Self {
         Self { vms: vec![vm] }
     }
 
+    /// Construct an execution from a stack of virtual machines, where the last
+    /// one is the one currently running.
+    pub(crate) fn from_vms(vms: Vec<Vm>) -> Self {
+        Self { vms }
+    }
+
+    /// Get the stack of virtual machines in the execution.
+    pub(crate) fn vms(&self) -> &[Vm] {
+        &self.vms
+    }
+
     /// Get the current virtual machine.
     pub fn vm(&self) -> Result<&Vm, VmError> {
         match self.vms.last() {
@@ -27,6 +38,12 @@ impl VmExecution {
         }
     }
 
+    /// Get the call depth of the execution, which is the number of functions
+    /// currently being called across all virtual machines in it.
+    pub fn call_depth(&self) -> usize {
+        self.vms.iter().map(|vm| vm.call_frames().len() + 1).sum()
+    }
+
     /// Complete the current execution without support for async instructions.
     ///
     /// This will error if the execution is suspended through yielding.
@@ -58,10 +75,18 @@ impl VmExecution {
             let len = self.vms.len();
             let vm = self.vm_mut()?;
 
-            match Self::run_for(vm, None)? {
+            let halt = match vm.run_for(None) {
+                Ok(halt) => halt,
+                Err(error) => return Err(self.unwind(error)),
+            };
+
+            match halt {
                 VmHalt::Exited => (),
                 VmHalt::Awaited(awaited) => {
-                    awaited.into_vm(vm).await?;
+                    if let Err(error) = awaited.into_vm(vm).await {
+                        return Err(self.unwind(error));
+                    }
+
                     continue;
                 }
                 VmHalt::VmCall(vm_call) => {
@@ -95,7 +120,12 @@ impl VmExecution {
             let len = self.vms.len();
             let vm = self.vm_mut()?;
 
-            match Self::run_for(vm, None)? {
+            let halt = match vm.run_for(None) {
+                Ok(halt) => halt,
+                Err(error) => return Err(self.unwind(error)),
+            };
+
+            match halt {
                 VmHalt::Exited => (),
                 VmHalt::VmCall(vm_call) => {
                     vm_call.into_execution(self)?;
@@ -128,7 +158,12 @@ impl VmExecution {
         let len = self.vms.len();
         let vm = self.vm_mut()?;
 
-        match Self::run_for(vm, Some(1))? {
+        let halt = match vm.run_for(Some(1)) {
+            Ok(halt) => halt,
+            Err(error) => return Err(self.unwind(error)),
+        };
+
+        match halt {
             VmHalt::Exited => (),
             VmHalt::VmCall(vm_call) => {
                 vm_call.into_execution(self)?;
@@ -158,10 +193,18 @@ impl VmExecution {
         let len = self.vms.len();
         let vm = self.vm_mut()?;
 
-        match Self::run_for(vm, Some(1))? {
+        let halt = match vm.run_for(Some(1)) {
+            Ok(halt) => halt,
+            Err(error) => return Err(self.unwind(error)),
+        };
+
+        match halt {
             VmHalt::Exited => (),
             VmHalt::Awaited(awaited) => {
-                awaited.into_vm(vm).await?;
+                if let Err(error) = awaited.into_vm(vm).await {
+                    return Err(self.unwind(error));
+                }
+
                 return Ok(None);
             }
             VmHalt::VmCall(vm_call) => {
@@ -209,11 +252,13 @@ impl VmExecution {
         Ok(())
     }
 
-    #[inline]
-    fn run_for(vm: &mut Vm, limit: Option<usize>) -> Result<VmHalt, VmError> {
-        match vm.run_for(limit) {
-            Ok(reason) => Ok(reason),
-            Err(error) => Err(error.into_unwinded(vm.unit(), vm.ip())),
+    /// Unwind the given error through all virtual machines in the execution,
+    /// starting with the current one.
+    fn unwind(&self, mut error: VmError) -> VmError {
+        for vm in self.vms.iter().rev() {
+            error = error.unwind(vm);
         }
+
+        error
     }
 }
@@ -0,0 +1,131 @@
+use crate::{
+    AnyObj, Bytes, FromValue, Function, Future, Generator, GeneratorState, Object, RawRef, Ref,
+    Shared, Stream, Tuple, TupleVariant, TypedObject, TypedTuple, UnsafeFromValue, Value,
+    VariantObject, VmError, VmErrorKind, Weak,
+};
+use std::fmt;
+
+macro_rules! weak_value {
+    ($($variant:ident($ty:ty),)*) => {
+        /// A weak reference to a value, which doesn't keep the value alive.
+        ///
+        /// Weak references can be constructed to any value which is shared by
+        /// reference, like vectors and objects.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use runestick::{Value, WeakValue};
+        ///
+        /// # fn main() -> runestick::Result<()> {
+        /// let value = Value::vec(vec![Value::Integer(1)]);
+        /// let weak = WeakValue::new(&value)?;
+        /// assert!(weak.upgrade().is_some());
+        ///
+        /// drop(value);
+        /// assert!(weak.upgrade().is_none());
+        /// # Ok(())
+        /// # }
+        /// ```
+        #[derive(Clone)]
+        pub struct WeakValue {
+            inner: Inner,
+        }
+
+        #[derive(Clone)]
+        enum Inner {
+            $($variant(Weak<$ty>),)*
+        }
+
+        impl WeakValue {
+            /// Construct a weak reference to the given value.
+            ///
+            /// This errors if the value isn't shared by reference.
+            pub fn new(value: &Value) -> Result<Self, VmError> {
+                let inner = match value {
+                    $(Value::$variant(value) => Inner::$variant(value.downgrade()),)*
+                    actual => {
+                        return Err(VmError::from(VmErrorKind::UnsupportedWeak {
+                            actual: actual.type_info()?,
+                        }));
+                    }
+                };
+
+                Ok(Self { inner })
+            }
+
+            /// Try to get the value being referenced, returning `None` if it
+            /// has already been dropped.
+            pub fn upgrade(&self) -> Option<Value> {
+                match &self.inner {
+                    $(Inner::$variant(weak) => Some(Value::$variant(weak.upgrade()?)),)*
+                }
+            }
+
+            /// Test if the value being referenced is still alive.
+            pub fn is_alive(&self) -> bool {
+                match &self.inner {
+                    $(Inner::$variant(weak) => weak.is_alive(),)*
+                }
+            }
+        }
+    }
+}
+
+weak_value! {
+    String(String),
+    Bytes(Bytes),
+    Vec(Vec<Value>),
+    Tuple(Tuple),
+    Object(Object),
+    Future(Future),
+    Stream(Stream),
+    Generator(Generator),
+    GeneratorState(GeneratorState),
+    Option(Option<Value>),
+    Result(Result<Value, Value>),
+    TypedTuple(TypedTuple),
+    TupleVariant(TupleVariant),
+    TypedObject(TypedObject),
+    VariantObject(VariantObject),
+    Function(Function),
+    Weak(WeakValue),
+    Any(AnyObj),
+}
+
+impl fmt::Debug for WeakValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Weak")
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+impl FromValue for Shared<WeakValue> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(value.into_weak()?)
+    }
+}
+
+impl FromValue for WeakValue {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        let weak = value.into_weak()?;
+        let weak = weak.borrow_ref()?.clone();
+        Ok(weak)
+    }
+}
+
+impl UnsafeFromValue for &WeakValue {
+    type Output = *const WeakValue;
+    type Guard = RawRef;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let weak = value.into_weak()?;
+        let (weak, guard) = Ref::into_raw(weak.into_ref()?);
+        Ok((weak, guard))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &*output
+    }
+}
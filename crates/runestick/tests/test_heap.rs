@@ -0,0 +1,89 @@
+use runestick::{Heap, Object, Shared, Value};
+
+#[global_allocator]
+static ALLOCATOR: checkers::Allocator = checkers::Allocator::system();
+
+fn object() -> Value {
+    Value::Object(Shared::new(Object::new()))
+}
+
+fn insert(target: &Value, key: &str, value: Value) {
+    let object = target.clone().into_object().unwrap();
+    object.borrow_mut().unwrap().insert(key.to_owned(), value);
+}
+
+#[checkers::test]
+fn test_collect_self_reference() {
+    let heap = Heap::new();
+
+    let a = object();
+    insert(&a, "this", a.clone());
+    heap.track(&a);
+    drop(a);
+
+    assert_eq!(heap.collect(), 1);
+    assert_eq!(heap.tracked(), 0);
+}
+
+#[checkers::test]
+fn test_collect_doubly_linked() {
+    let heap = Heap::new();
+
+    let first = object();
+    let second = object();
+    insert(&first, "next", second.clone());
+    insert(&second, "prev", first.clone());
+    insert(
+        &second,
+        "items",
+        Value::vec(vec![first.clone(), Value::Integer(1)]),
+    );
+    heap.track(&first);
+    heap.track(&second);
+
+    // NB: still referenced from here.
+    assert_eq!(heap.collect(), 0);
+    drop(second);
+    assert_eq!(heap.collect(), 0);
+    drop(first);
+
+    // NB: the untracked vector is reclaimed as well.
+    assert_eq!(heap.collect(), 3);
+    assert_eq!(heap.tracked(), 0);
+}
+
+#[checkers::test]
+fn test_collect_keeps_reachable() {
+    let heap = Heap::new();
+
+    let root = object();
+    let garbage = object();
+    let child = object();
+    insert(&root, "child", child.clone());
+    insert(&garbage, "child", child.clone());
+    insert(&child, "parent", garbage.clone());
+    heap.track(&garbage);
+    drop(garbage);
+    drop(child);
+
+    // NB: the garbage is reachable through the root.
+    assert_eq!(heap.collect(), 0);
+    drop(root);
+    assert_eq!(heap.collect(), 2);
+}
+
+#[checkers::test]
+fn test_track_never_collects() {
+    let heap = Heap::new();
+
+    for _ in 0..4 {
+        let a = object();
+        insert(&a, "this", a.clone());
+        heap.track(&a);
+    }
+
+    // NB: collection only happens when it's explicitly requested.
+    assert_eq!(heap.tracked(), 4);
+    assert_eq!(heap.collect(), 4);
+    assert_eq!(heap.tracked(), 0);
+}
@@ -0,0 +1,95 @@
+use hashbrown::HashMap;
+use runestick::{
+    Call, Compression, Context, Hash, Inst, Module, Unit, UnitFileError, UnitFileHeader, UnitFn,
+};
+
+/// Construct a unit with a single `main` function.
+fn unit() -> Unit {
+    let mut functions = HashMap::new();
+
+    functions.insert(
+        Hash::type_hash(["main"]),
+        UnitFn::Offset {
+            offset: 0,
+            call: Call::Immediate,
+            args: 0,
+        },
+    );
+
+    Unit::new(
+        vec![Inst::Integer { number: 42 }, Inst::Return],
+        functions,
+        HashMap::new(),
+        Vec::new(),
+        vec![b"hello".to_vec()],
+        Vec::new(),
+        None,
+    )
+}
+
+fn write(unit: &Unit, context: &Context, compression: Compression) -> Vec<u8> {
+    let mut buf = Vec::new();
+    unit.write_file(&mut buf, context, compression).unwrap();
+    buf
+}
+
+#[test]
+fn test_unit_file_roundtrip() {
+    let context = Context::with_default_modules().unwrap();
+    let unit = unit();
+
+    for &compression in &[Compression::None, Compression::Deflate] {
+        let buf = write(&unit, &context, compression);
+
+        let header = UnitFileHeader::read(&buf[..]).unwrap();
+        assert_eq!(header.version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(header.context(), context.fingerprint());
+        assert_eq!(header.content(), unit.content_hash());
+        assert_eq!(header.compression(), compression);
+
+        let read = Unit::read_file(&buf[..], &context).unwrap();
+        assert_eq!(read.content_hash(), unit.content_hash());
+    }
+}
+
+#[test]
+fn test_unit_file_errors() {
+    let context = Context::with_default_modules().unwrap();
+    let unit = unit();
+    let buf = write(&unit, &context, Compression::None);
+
+    assert!(matches!(
+        Unit::read_file(&b"not a unit file"[..], &context),
+        Err(UnitFileError::NotAUnitFile)
+    ));
+
+    let mut future = buf.clone();
+    future[8] = 2;
+
+    assert!(matches!(
+        Unit::read_file(&future[..], &context),
+        Err(UnitFileError::UnsupportedFormatVersion { version: 2 })
+    ));
+
+    let mut module = Module::new(&["extra"]);
+    module.function(&["answer"], || 42i64).unwrap();
+
+    let mut other = Context::with_default_modules().unwrap();
+    other.install(&module).unwrap();
+    assert_ne!(context.fingerprint(), other.fingerprint());
+
+    assert!(matches!(
+        Unit::read_file(&buf[..], &other),
+        Err(UnitFileError::ContextMismatch { .. })
+    ));
+
+    // Corrupt the static bytes stored at the end of the unit.
+    let mut corrupt = buf.clone();
+    let n = corrupt.windows(5).position(|w| w == b"hello").unwrap();
+    corrupt[n] = b'j';
+
+    assert!(matches!(
+        Unit::read_file(&corrupt[..], &context),
+        Err(UnitFileError::Corrupt { .. })
+    ));
+}
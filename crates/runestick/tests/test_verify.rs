@@ -0,0 +1,125 @@
+use hashbrown::HashMap;
+use runestick::{Call, Context, Hash, Inst, Unit, UnitFn, VerifyError};
+
+/// Construct a unit with a single `main` function taking `args` arguments.
+fn unit(args: usize, instructions: Vec<Inst>) -> Unit {
+    let mut functions = HashMap::new();
+
+    functions.insert(
+        Hash::type_hash(["main"]),
+        UnitFn::Offset {
+            offset: 0,
+            call: Call::Immediate,
+            args,
+        },
+    );
+
+    Unit::new(
+        instructions,
+        functions,
+        HashMap::new(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+        None,
+    )
+}
+
+fn verify(unit: Unit) -> Vec<VerifyError> {
+    match unit.verify(&Context::new()) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.errors().collect(),
+    }
+}
+
+#[test]
+fn test_verify_valid() {
+    let errors = verify(unit(
+        1,
+        vec![
+            Inst::Copy { offset: 0 },
+            Inst::Integer { number: 1 },
+            Inst::Lt,
+            Inst::JumpIfNot { offset: 2 },
+            Inst::Integer { number: 1 },
+            Inst::Return,
+            Inst::Copy { offset: 0 },
+            Inst::Return,
+        ],
+    ));
+
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_verify_operands() {
+    let errors = verify(unit(
+        0,
+        vec![
+            Inst::String { slot: 0 },
+            Inst::Pop,
+            Inst::Call {
+                hash: Hash::type_hash(["missing"]),
+                args: 0,
+            },
+            Inst::Pop,
+            Inst::Jump { offset: 10 },
+        ],
+    ));
+
+    assert!(matches!(
+        &errors[..],
+        [
+            VerifyError::MissingStaticString { ip: 0, slot: 0 },
+            VerifyError::MissingFunction { ip: 2, .. },
+            VerifyError::JumpOutOfBounds { ip: 4, offset: 10 },
+        ]
+    ));
+}
+
+#[test]
+fn test_verify_stack() {
+    let errors = verify(unit(0, vec![Inst::Add, Inst::Return]));
+
+    assert!(matches!(
+        &errors[..],
+        [VerifyError::StackUnderflow {
+            ip: 0,
+            required: 2,
+            depth: 0
+        }]
+    ));
+
+    let errors = verify(unit(1, vec![Inst::Copy { offset: 1 }, Inst::Return]));
+
+    assert!(matches!(
+        &errors[..],
+        [VerifyError::FrameOffsetOutOfBounds {
+            ip: 0,
+            offset: 1,
+            depth: 1
+        }]
+    ));
+
+    let errors = verify(unit(
+        0,
+        vec![
+            Inst::Bool { value: true },
+            Inst::JumpIf { offset: 1 },
+            Inst::Integer { number: 1 },
+            Inst::Unit,
+            Inst::Return,
+        ],
+    ));
+
+    assert!(matches!(
+        &errors[..],
+        [VerifyError::InconsistentStack { ip: 3, .. }]
+    ));
+
+    let errors = verify(unit(0, vec![Inst::Integer { number: 1 }]));
+    assert!(matches!(
+        &errors[..],
+        [VerifyError::FallthroughEnd { ip: 0 }]
+    ));
+}
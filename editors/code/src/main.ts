@@ -9,14 +9,21 @@ export async function activate(context: vscode.ExtensionContext) {
     });
 }
 
-async function tryActivate(_context: vscode.ExtensionContext) {
+async function tryActivate(context: vscode.ExtensionContext) {
     let platform = detectPlatform();
 
     if (!platform) {
         return;
     }
 
-    let command = findCommand(platform);
+    let debugAdapter = findCommand(platform, "rune-debugadapter");
+
+    if (!!debugAdapter) {
+        let factory = new DebugAdapterFactory(debugAdapter);
+        context.subscriptions.push(vscode.debug.registerDebugAdapterDescriptorFactory('rune', factory));
+    }
+
+    let command = findCommand(platform, "rune-languageserver");
 
     if (!command) {
         return;
@@ -55,18 +62,30 @@ async function tryActivate(_context: vscode.ExtensionContext) {
     client.start();
 }
 
+/**
+ * Launches the Rune debug adapter.
+ */
+class DebugAdapterFactory implements vscode.DebugAdapterDescriptorFactory {
+    constructor(private command: string) {
+    }
+
+    createDebugAdapterDescriptor(_session: vscode.DebugSession): vscode.ProviderResult<vscode.DebugAdapterDescriptor> {
+        return new vscode.DebugAdapterExecutable(this.command);
+    }
+}
+
 /**
  * Find the path to the command to execute.
  *
- * @param context The extension context in use.
  * @param platform The detected platform.
+ * @param name The name of the command.
  */
-function findCommand(platform: Platform): string | undefined {
+function findCommand(platform: Platform, name: string): string | undefined {
     if (!!process.env.RUNE_DEBUG_FOLDER) {
-        return path.join(process.env.RUNE_DEBUG_FOLDER, `rune-languageserver${platform.ext}`);
+        return path.join(process.env.RUNE_DEBUG_FOLDER, `${name}${platform.ext}`);
     }
 
-    console.debug("Cannot find a command for the Rune Language Server.");
+    console.debug(`Cannot find a command for ${name}.`);
     return undefined;
 }
 